@@ -0,0 +1,472 @@
+use std::marker::PhantomData;
+
+use serde::de::{self, Visitor};
+
+/// A fully buffered document, which can be deserialized repeatedly.
+///
+/// Unlike [`Content`](crate::content::Content), which only holds primitive values,
+/// [`Buffer`] also captures nested data structures.
+pub enum Buffer<'de> {
+    Bool(bool),
+
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+
+    F32(f32),
+    F64(f64),
+
+    Char(char),
+    String(String),
+    Str(&'de str),
+    ByteBuf(Vec<u8>),
+    Bytes(&'de [u8]),
+
+    None,
+    Some(Box<Buffer<'de>>),
+    Unit,
+    Newtype(Box<Buffer<'de>>),
+    Seq(Vec<Buffer<'de>>),
+    Map(Vec<(Buffer<'de>, Buffer<'de>)>),
+}
+
+impl Buffer<'_> {
+    #[cold]
+    fn unexpected(&self) -> de::Unexpected<'_> {
+        match *self {
+            Buffer::Bool(b) => de::Unexpected::Bool(b),
+            Buffer::U8(n) => de::Unexpected::Unsigned(n as u64),
+            Buffer::U16(n) => de::Unexpected::Unsigned(n as u64),
+            Buffer::U32(n) => de::Unexpected::Unsigned(n as u64),
+            Buffer::U64(n) => de::Unexpected::Unsigned(n),
+            Buffer::U128(n) => match u64::try_from(n) {
+                Ok(n) => de::Unexpected::Unsigned(n),
+                Err(_) => de::Unexpected::Other("u128"),
+            },
+            Buffer::I8(n) => de::Unexpected::Signed(n as i64),
+            Buffer::I16(n) => de::Unexpected::Signed(n as i64),
+            Buffer::I32(n) => de::Unexpected::Signed(n as i64),
+            Buffer::I64(n) => de::Unexpected::Signed(n),
+            Buffer::I128(n) => match i64::try_from(n) {
+                Ok(n) => de::Unexpected::Signed(n),
+                Err(_) => de::Unexpected::Other("i128"),
+            },
+            Buffer::F32(f) => de::Unexpected::Float(f as f64),
+            Buffer::F64(f) => de::Unexpected::Float(f),
+            Buffer::Char(c) => de::Unexpected::Char(c),
+            Buffer::String(ref s) => de::Unexpected::Str(s),
+            Buffer::Str(s) => de::Unexpected::Str(s),
+            Buffer::ByteBuf(ref b) => de::Unexpected::Bytes(b),
+            Buffer::Bytes(b) => de::Unexpected::Bytes(b),
+            Buffer::None | Buffer::Some(_) => de::Unexpected::Option,
+            Buffer::Unit => de::Unexpected::Unit,
+            Buffer::Newtype(_) => de::Unexpected::NewtypeStruct,
+            Buffer::Seq(_) => de::Unexpected::Seq,
+            Buffer::Map(_) => de::Unexpected::Map,
+        }
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Buffer<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(BufferVisitor)
+    }
+}
+
+struct BufferVisitor;
+
+impl<'de> Visitor<'de> for BufferVisitor {
+    type Value = Buffer<'de>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("any value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(Buffer::Bool(v))
+    }
+
+    fn visit_i8<E>(self, v: i8) -> Result<Self::Value, E> {
+        Ok(Buffer::I8(v))
+    }
+
+    fn visit_i16<E>(self, v: i16) -> Result<Self::Value, E> {
+        Ok(Buffer::I16(v))
+    }
+
+    fn visit_i32<E>(self, v: i32) -> Result<Self::Value, E> {
+        Ok(Buffer::I32(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(Buffer::I64(v))
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E> {
+        Ok(Buffer::I128(v))
+    }
+
+    fn visit_u8<E>(self, v: u8) -> Result<Self::Value, E> {
+        Ok(Buffer::U8(v))
+    }
+
+    fn visit_u16<E>(self, v: u16) -> Result<Self::Value, E> {
+        Ok(Buffer::U16(v))
+    }
+
+    fn visit_u32<E>(self, v: u32) -> Result<Self::Value, E> {
+        Ok(Buffer::U32(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Buffer::U64(v))
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E> {
+        Ok(Buffer::U128(v))
+    }
+
+    fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E> {
+        Ok(Buffer::F32(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(Buffer::F64(v))
+    }
+
+    fn visit_char<E>(self, v: char) -> Result<Self::Value, E> {
+        Ok(Buffer::Char(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Buffer::String(v.to_owned()))
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(Buffer::Str(v))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Buffer::String(v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(Buffer::ByteBuf(v.to_vec()))
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        Ok(Buffer::Bytes(v))
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(Buffer::ByteBuf(v))
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(Buffer::None)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        de::Deserialize::deserialize(deserializer).map(|v| Buffer::Some(Box::new(v)))
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(Buffer::Unit)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        de::Deserialize::deserialize(deserializer).map(|v| Buffer::Newtype(Box::new(v)))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Buffer::Seq(values))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0).min(4096));
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(Buffer::Map(entries))
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::EnumAccess<'de>,
+    {
+        // Enums are buffered in their externally tagged representation, `{"variant": value}`.
+        use de::VariantAccess;
+
+        let (variant, access) = data.variant()?;
+        let value = access.newtype_variant()?;
+        Ok(Buffer::Map(vec![(variant, value)]))
+    }
+}
+
+/// A [`de::Deserializer`] replaying a borrowed [`Buffer`].
+pub struct BufferDeserializer<'a, 'de, E> {
+    buffer: &'a Buffer<'de>,
+    human_readable: bool,
+    err: PhantomData<E>,
+}
+
+impl<'a, 'de, E> BufferDeserializer<'a, 'de, E> {
+    pub fn new(buffer: &'a Buffer<'de>, human_readable: bool) -> Self {
+        Self {
+            buffer,
+            human_readable,
+            err: PhantomData,
+        }
+    }
+
+    fn nested(&self, buffer: &'a Buffer<'de>) -> Self {
+        Self::new(buffer, self.human_readable)
+    }
+}
+
+impl<'de, E> de::Deserializer<'de> for BufferDeserializer<'_, 'de, E>
+where
+    E: de::Error,
+{
+    type Error = E;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match *self.buffer {
+            Buffer::Bool(v) => visitor.visit_bool(v),
+            Buffer::U8(v) => visitor.visit_u8(v),
+            Buffer::U16(v) => visitor.visit_u16(v),
+            Buffer::U32(v) => visitor.visit_u32(v),
+            Buffer::U64(v) => visitor.visit_u64(v),
+            Buffer::U128(v) => visitor.visit_u128(v),
+            Buffer::I8(v) => visitor.visit_i8(v),
+            Buffer::I16(v) => visitor.visit_i16(v),
+            Buffer::I32(v) => visitor.visit_i32(v),
+            Buffer::I64(v) => visitor.visit_i64(v),
+            Buffer::I128(v) => visitor.visit_i128(v),
+            Buffer::F32(v) => visitor.visit_f32(v),
+            Buffer::F64(v) => visitor.visit_f64(v),
+            Buffer::Char(v) => visitor.visit_char(v),
+            Buffer::String(ref v) => visitor.visit_str(v),
+            Buffer::Str(v) => visitor.visit_borrowed_str(v),
+            Buffer::ByteBuf(ref v) => visitor.visit_bytes(v),
+            Buffer::Bytes(v) => visitor.visit_borrowed_bytes(v),
+            Buffer::None => visitor.visit_none(),
+            Buffer::Some(ref v) => visitor.visit_some(self.nested(v)),
+            Buffer::Unit => visitor.visit_unit(),
+            Buffer::Newtype(ref v) => visitor.visit_newtype_struct(self.nested(v)),
+            Buffer::Seq(ref v) => {
+                let human_readable = self.human_readable;
+                let seq = v.iter().map(|v| BufferDeserializer::new(v, human_readable));
+                let mut seq = de::value::SeqDeserializer::new(seq);
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Buffer::Map(ref v) => {
+                let human_readable = self.human_readable;
+                let map = v.iter().map(|(k, v)| {
+                    (
+                        BufferDeserializer::new(k, human_readable),
+                        BufferDeserializer::new(v, human_readable),
+                    )
+                });
+                let mut map = de::value::MapDeserializer::new(map);
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match *self.buffer {
+            Buffer::None | Buffer::Unit => visitor.visit_none(),
+            Buffer::Some(ref v) => visitor.visit_some(self.nested(v)),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match *self.buffer {
+            Buffer::Newtype(ref v) => visitor.visit_newtype_struct(self.nested(v)),
+            _ => visitor.visit_newtype_struct(self),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let (variant, value) = match *self.buffer {
+            Buffer::Map(ref v) => match v.as_slice() {
+                [(variant, value)] => (variant, Some(value)),
+                _ => {
+                    return Err(de::Error::invalid_value(
+                        de::Unexpected::Map,
+                        &"map with a single key",
+                    ))
+                }
+            },
+            Buffer::String(_) | Buffer::Str(_) => (self.buffer, None),
+            ref other => {
+                return Err(de::Error::invalid_type(
+                    other.unexpected(),
+                    &"string or map",
+                ))
+            }
+        };
+
+        visitor.visit_enum(EnumDeserializer {
+            variant: self.nested(variant),
+            value: value.map(|v| self.nested(v)),
+        })
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.human_readable
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'a, 'de, E> de::IntoDeserializer<'de, E> for BufferDeserializer<'a, 'de, E>
+where
+    E: de::Error,
+{
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+struct EnumDeserializer<'a, 'de, E> {
+    variant: BufferDeserializer<'a, 'de, E>,
+    value: Option<BufferDeserializer<'a, 'de, E>>,
+}
+
+impl<'a, 'de, E> de::EnumAccess<'de> for EnumDeserializer<'a, 'de, E>
+where
+    E: de::Error,
+{
+    type Error = E;
+    type Variant = VariantDeserializer<'a, 'de, E>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(self.variant)?;
+        Ok((variant, VariantDeserializer { value: self.value }))
+    }
+}
+
+struct VariantDeserializer<'a, 'de, E> {
+    value: Option<BufferDeserializer<'a, 'de, E>>,
+}
+
+impl<'de, E> de::VariantAccess<'de> for VariantDeserializer<'_, 'de, E>
+where
+    E: de::Error,
+{
+    type Error = E;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        match self.value {
+            Some(value) => de::Deserialize::deserialize(value),
+            None => Ok(()),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.value {
+            Some(value) => seed.deserialize(value),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(value) => de::Deserializer::deserialize_seq(value, visitor),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(value) => de::Deserializer::deserialize_map(value, visitor),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"struct variant",
+            )),
+        }
+    }
+}
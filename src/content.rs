@@ -2,6 +2,9 @@ use std::marker::PhantomData;
 
 use serde::de;
 
+/// Private token, used by `serde_json` to represent arbitrary precision numbers as a map.
+pub const NUMBER_TOKEN: &str = "$serde_json::private::Number";
+
 pub enum Content<'de> {
     Bool(bool),
 
@@ -9,11 +12,13 @@ pub enum Content<'de> {
     U16(u16),
     U32(u32),
     U64(u64),
+    U128(u128),
 
     I8(i8),
     I16(i16),
     I32(i32),
     I64(i64),
+    I128(i128),
 
     F32(f32),
     F64(f64),
@@ -34,10 +39,18 @@ impl Content<'_> {
             Content::U16(n) => de::Unexpected::Unsigned(n as u64),
             Content::U32(n) => de::Unexpected::Unsigned(n as u64),
             Content::U64(n) => de::Unexpected::Unsigned(n),
+            Content::U128(n) => match u64::try_from(n) {
+                Ok(n) => de::Unexpected::Unsigned(n),
+                Err(_) => de::Unexpected::Other("u128"),
+            },
             Content::I8(n) => de::Unexpected::Signed(n as i64),
             Content::I16(n) => de::Unexpected::Signed(n as i64),
             Content::I32(n) => de::Unexpected::Signed(n as i64),
             Content::I64(n) => de::Unexpected::Signed(n),
+            Content::I128(n) => match i64::try_from(n) {
+                Ok(n) => de::Unexpected::Signed(n),
+                Err(_) => de::Unexpected::Other("i128"),
+            },
             Content::F32(f) => de::Unexpected::Float(f as f64),
             Content::F64(f) => de::Unexpected::Float(f),
             Content::Char(c) => de::Unexpected::Char(c),
@@ -110,6 +123,13 @@ impl<'de> de::Visitor<'de> for ContentVisitor<'de> {
         Ok(Content::I64(v))
     }
 
+    fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Content::I128(v))
+    }
+
     fn visit_u8<E>(self, v: u8) -> Result<Self::Value, E>
     where
         E: de::Error,
@@ -138,6 +158,13 @@ impl<'de> de::Visitor<'de> for ContentVisitor<'de> {
         Ok(Content::U64(v))
     }
 
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Content::U128(v))
+    }
+
     fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
     where
         E: de::Error,
@@ -200,4 +227,25 @@ impl<'de> de::Visitor<'de> for ContentVisitor<'de> {
     {
         Ok(Content::ByteBuf(v))
     }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        // `serde_json` with the `arbitrary_precision` feature enabled, represents numbers
+        // as a map with a single private key and the number as a string value.
+        if map.next_key::<String>()?.as_deref() != Some(NUMBER_TOKEN) {
+            return Err(de::Error::invalid_type(de::Unexpected::Map, &self));
+        }
+
+        let number = map.next_value::<String>()?;
+        number
+            .parse()
+            .map(Content::U64)
+            .or_else(|_| number.parse().map(Content::I64))
+            .or_else(|_| number.parse().map(Content::U128))
+            .or_else(|_| number.parse().map(Content::I128))
+            .or_else(|_| number.parse().map(Content::F64))
+            .map_err(|_| de::Error::invalid_value(de::Unexpected::Str(&number), &"a number"))
+    }
 }
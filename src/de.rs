@@ -1,6 +1,6 @@
 use std::{borrow::Cow, marker::PhantomData};
 
-use serde::de::{self, Deserialize, Visitor};
+use serde::de::{self, value::MapDeserializer, Deserialize, Visitor};
 
 use crate::{content::Content, source::Source};
 
@@ -9,6 +9,11 @@ use crate::{content::Content, source::Source};
 /// It allows strings in place of arbitrary primitive types to be resolved through the
 /// provided [`Source`].
 ///
+/// Some data structures, like untagged enums, internally tagged enums or adjacently tagged
+/// enums where the content precedes the tag, are buffered by [`serde`] before the target type
+/// is known. Variables in buffered values are expanded like self-describing values,
+/// see [`Source::expand_any`]. Type mismatches in buffered values are only noticed after
+/// the expansion, the resulting errors no longer mention the variable.
 ///
 /// # Examples:
 ///
@@ -59,14 +64,101 @@ use crate::{content::Content, source::Source};
 pub struct Deserializer<'a, D, S> {
     de: D,
     source: &'a mut S,
+    options: Options,
 }
 
 impl<'a, D, S> Deserializer<'a, D, S> {
     pub fn new(de: D, source: &'a mut S) -> Self {
-        Self { de, source }
+        Self::with_options(de, source, Options::default())
+    }
+
+    pub(crate) fn with_options(de: D, source: &'a mut S, options: Options) -> Self {
+        Self {
+            de,
+            source,
+            options,
+        }
+    }
+
+    /// Deserializes optional values, which only consist of a missing variable, as `None`.
+    ///
+    /// By default, missing variables are an error, even for optional values. When enabled,
+    /// an optional value is `None`, if it is a variable, which the source does not contain,
+    /// see [`Source::contains_variable`].
+    ///
+    /// Optional values are deserialized as self-describing values, which requires
+    /// a self-describing format, like JSON or YAML. Values are passed on with the type
+    /// of the format, e.g. YAML numbers cannot be deserialized into optional strings.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_vars::MapSource;
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Config {
+    ///     port: Option<u16>,
+    ///     log_level: Option<String>,
+    /// }
+    ///
+    /// let mut source = MapSource::default().insert("PORT", "8080");
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(
+    ///     r#"{"port": "${PORT}", "log_level": "${LOG_LEVEL}"}"#,
+    /// );
+    /// let de = serde_vars::Deserializer::new(&mut de, &mut source).with_missing_as_none(true);
+    /// let config = Config::deserialize(de).unwrap();
+    ///
+    /// assert_eq!(config.port, Some(8080));
+    /// assert_eq!(config.log_level, None);
+    /// ```
+    pub fn with_missing_as_none(mut self, missing_as_none: bool) -> Self {
+        self.options.missing_as_none = missing_as_none;
+        self
+    }
+
+    /// Expands variables in place of struct field names.
+    ///
+    /// Enum variant names, like unit variants represented as a string or the tag of an
+    /// internally tagged enum, are always expanded. Map keys are never expanded by default.
+    /// When enabled, keys which are deserialized as an identifier, like the field names of a
+    /// struct, are expanded as well. Keys of other types, like the keys of a
+    /// `HashMap<String, _>`, are never expanded.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_vars::MapSource;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Storage {
+    ///     s3: Option<String>,
+    ///     gcs: Option<String>,
+    /// }
+    ///
+    /// let mut source = MapSource::default().insert("STORAGE", "gcs");
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#"{"${STORAGE}": "bucket"}"#);
+    /// let de = serde_vars::Deserializer::new(&mut de, &mut source).with_expand_variants(true);
+    /// let storage = Storage::deserialize(de).unwrap();
+    ///
+    /// assert_eq!(storage.gcs.as_deref(), Some("bucket"));
+    /// ```
+    pub fn with_expand_variants(mut self, expand_variants: bool) -> Self {
+        self.options.expand_variants = expand_variants;
+        self
     }
 }
 
+/// Options of a [`Deserializer`], which are passed on to all nested deserializers.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Options {
+    missing_as_none: bool,
+    expand_variants: bool,
+}
+
 impl<'de, D, S> de::Deserializer<'de> for Deserializer<'_, D, S>
 where
     D: de::Deserializer<'de>,
@@ -78,7 +170,8 @@ where
     where
         V: de::Visitor<'de>,
     {
-        self.de.deserialize_any(Wrap::new(visitor, self.source))
+        self.de
+            .deserialize_any(Wrap::new(visitor, self.source, self.options))
     }
 
     fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -116,6 +209,13 @@ where
         ContentVarDeserializer::from_de(self.de, self.source)?.deserialize_i64(visitor)
     }
 
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        ContentVarDeserializer::from_de(self.de, self.source)?.deserialize_i128(visitor)
+    }
+
     fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
@@ -144,6 +244,13 @@ where
         ContentVarDeserializer::from_de(self.de, self.source)?.deserialize_u64(visitor)
     }
 
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        ContentVarDeserializer::from_de(self.de, self.source)?.deserialize_u128(visitor)
+    }
+
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
@@ -184,18 +291,24 @@ where
         // contains a variable reference or not.
         // This allows formats, like YAML, which can deserialize a value into multiple types,
         // to yield a string when they otherwise would yield another type (e.g. u64).
+        let human_readable = self.de.is_human_readable();
         let content = Content::String(Deserialize::deserialize(self.de)?);
-        ContentVarDeserializer::new(content, self.source).deserialize_string(visitor)
+        ContentVarDeserializer::new(content, human_readable, self.source)
+            .deserialize_string(visitor)
     }
 
     fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        // TODO: support zero copy/borrowed bytes here.
-        // To support this we need a custom visitor which can differentiate between
-        // a borrowed `&'de str` and just a referenced `&str` as well as accept `String`.
-        self.deserialize_byte_buf(visitor)
+        // Bytes without a variable reference are passed on as borrowed from the original
+        // deserializer, if it supports it, to keep zero copy deserialization working.
+        let human_readable = self.de.is_human_readable();
+        let content = match crate::value::deserialize_bytes(self.de)? {
+            Cow::Borrowed(v) => Content::Bytes(v),
+            Cow::Owned(v) => Content::ByteBuf(v),
+        };
+        ContentVarDeserializer::new(content, human_readable, self.source).deserialize_bytes(visitor)
     }
 
     fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -203,15 +316,18 @@ where
         V: de::Visitor<'de>,
     {
         // See `deserialize_string` why we deserialize into a byte buf directly here.
+        let human_readable = self.de.is_human_readable();
         let content = Content::ByteBuf(crate::value::deserialize_byte_buf(self.de)?);
-        ContentVarDeserializer::new(content, self.source).deserialize_byte_buf(visitor)
+        ContentVarDeserializer::new(content, human_readable, self.source)
+            .deserialize_byte_buf(visitor)
     }
 
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        self.de.deserialize_option(Wrap::new(visitor, self.source))
+        self.de
+            .deserialize_option(Wrap::new(visitor, self.source, self.options))
     }
 
     fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -230,7 +346,7 @@ where
         V: de::Visitor<'de>,
     {
         self.de
-            .deserialize_unit_struct(name, Wrap::new(visitor, self.source))
+            .deserialize_unit_struct(name, Wrap::new(visitor, self.source, self.options))
     }
 
     fn deserialize_newtype_struct<V>(
@@ -241,23 +357,40 @@ where
     where
         V: de::Visitor<'de>,
     {
+        if name == crate::masked::NAME {
+            return crate::masked::deserialize(self.de, self.source, self.options, visitor);
+        }
+
         self.de
-            .deserialize_newtype_struct(name, Wrap::new(visitor, self.source))
+            .deserialize_newtype_struct(name, Wrap::new(visitor, self.source, self.options))
     }
 
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        self.de.deserialize_seq(Wrap::new(visitor, self.source))
+        if self.source.expands_structured() {
+            return self
+                .de
+                .deserialize_any(Wrap::structured(visitor, self.source, self.options));
+        }
+
+        self.de
+            .deserialize_seq(Wrap::new(visitor, self.source, self.options))
     }
 
     fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
+        if self.source.expands_structured() {
+            return self
+                .de
+                .deserialize_any(Wrap::structured(visitor, self.source, self.options));
+        }
+
         self.de
-            .deserialize_tuple(len, Wrap::new(visitor, self.source))
+            .deserialize_tuple(len, Wrap::new(visitor, self.source, self.options))
     }
 
     fn deserialize_tuple_struct<V>(
@@ -269,15 +402,28 @@ where
     where
         V: de::Visitor<'de>,
     {
+        if self.source.expands_structured() {
+            return self
+                .de
+                .deserialize_any(Wrap::structured(visitor, self.source, self.options));
+        }
+
         self.de
-            .deserialize_tuple_struct(name, len, Wrap::new(visitor, self.source))
+            .deserialize_tuple_struct(name, len, Wrap::new(visitor, self.source, self.options))
     }
 
     fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        self.de.deserialize_map(Wrap::new(visitor, self.source))
+        if self.source.expands_structured() {
+            return self
+                .de
+                .deserialize_any(Wrap::structured(visitor, self.source, self.options));
+        }
+
+        self.de
+            .deserialize_map(Wrap::new(visitor, self.source, self.options))
     }
 
     fn deserialize_struct<V>(
@@ -289,8 +435,14 @@ where
     where
         V: de::Visitor<'de>,
     {
+        if self.source.expands_structured() {
+            return self
+                .de
+                .deserialize_any(Wrap::structured(visitor, self.source, self.options));
+        }
+
         self.de
-            .deserialize_struct(name, fields, Wrap::new(visitor, self.source))
+            .deserialize_struct(name, fields, Wrap::new(visitor, self.source, self.options))
     }
 
     fn deserialize_enum<V>(
@@ -302,8 +454,11 @@ where
     where
         V: de::Visitor<'de>,
     {
-        self.de
-            .deserialize_enum(name, variants, Wrap::new(visitor, self.source))
+        self.de.deserialize_enum(
+            name,
+            variants,
+            Wrap::new(visitor, self.source, self.options),
+        )
     }
 
     fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -311,7 +466,7 @@ where
         V: de::Visitor<'de>,
     {
         self.de
-            .deserialize_identifier(Wrap::new(visitor, self.source))
+            .deserialize_identifier(Wrap::new(visitor, self.source, self.options))
     }
 
     fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -319,18 +474,39 @@ where
         V: de::Visitor<'de>,
     {
         self.de
-            .deserialize_ignored_any(Wrap::new(visitor, self.source))
+            .deserialize_ignored_any(Wrap::new(visitor, self.source, self.options))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
     }
 }
 
 struct Wrap<'a, T, S> {
     delegate: T,
     source: &'a mut S,
+    structured: bool,
+    options: Options,
 }
 
 impl<'a, T, S> Wrap<'a, T, S> {
-    fn new(delegate: T, source: &'a mut S) -> Self {
-        Self { delegate, source }
+    fn new(delegate: T, source: &'a mut S, options: Options) -> Self {
+        Self {
+            delegate,
+            source,
+            structured: false,
+            options,
+        }
+    }
+
+    /// Creates a [`Wrap`], which expands strings using [`Source::expand_structured`].
+    fn structured(delegate: T, source: &'a mut S, options: Options) -> Self {
+        Self {
+            delegate,
+            source,
+            structured: true,
+            options,
+        }
     }
 }
 
@@ -456,6 +632,18 @@ where
     where
         E: de::Error,
     {
+        if self.structured {
+            if let Some(entries) = self.source.expand_map(v)? {
+                return self
+                    .delegate
+                    .visit_map(MapDeserializer::new(entries.into_iter()));
+            }
+            return self
+                .source
+                .expand_structured(Cow::Borrowed(v))?
+                .visit(self.delegate);
+        }
+
         self.source
             .expand_any(Cow::Borrowed(v))?
             .visit(self.delegate)
@@ -465,6 +653,18 @@ where
     where
         E: de::Error,
     {
+        if self.structured {
+            if let Some(entries) = self.source.expand_map(v)? {
+                return self
+                    .delegate
+                    .visit_map(MapDeserializer::new(entries.into_iter()));
+            }
+            return self
+                .source
+                .expand_structured(Cow::Borrowed(v))?
+                .visit(self.delegate);
+        }
+
         self.source
             .expand_any(Cow::Borrowed(v))?
             .visit_borrowed(self.delegate)
@@ -474,6 +674,18 @@ where
     where
         E: de::Error,
     {
+        if self.structured {
+            if let Some(entries) = self.source.expand_map(&v)? {
+                return self
+                    .delegate
+                    .visit_map(MapDeserializer::new(entries.into_iter()));
+            }
+            return self
+                .source
+                .expand_structured(Cow::Owned(v))?
+                .visit(self.delegate);
+        }
+
         self.source.expand_any(Cow::Owned(v))?.visit(self.delegate)
     }
 
@@ -509,8 +721,19 @@ where
     where
         D: de::Deserializer<'de>,
     {
-        self.delegate
-            .visit_some(Deserializer::new(deserializer, self.source))
+        if self.options.missing_as_none {
+            return deserializer.deserialize_any(Optional {
+                delegate: self.delegate,
+                source: self.source,
+                options: self.options,
+            });
+        }
+
+        self.delegate.visit_some(Deserializer::with_options(
+            deserializer,
+            self.source,
+            self.options,
+        ))
     }
 
     fn visit_unit<E>(self) -> Result<Self::Value, E>
@@ -525,28 +748,35 @@ where
         D: de::Deserializer<'de>,
     {
         self.delegate
-            .visit_newtype_struct(Deserializer::new(deserializer, self.source))
+            .visit_newtype_struct(Deserializer::with_options(
+                deserializer,
+                self.source,
+                self.options,
+            ))
     }
 
     fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
     where
         A: de::SeqAccess<'de>,
     {
-        self.delegate.visit_seq(Wrap::new(seq, self.source))
+        self.delegate
+            .visit_seq(Wrap::new(seq, self.source, self.options))
     }
 
     fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
     where
         A: de::MapAccess<'de>,
     {
-        self.delegate.visit_map(Wrap::new(map, self.source))
+        self.delegate
+            .visit_map(Wrap::new(map, self.source, self.options))
     }
 
     fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
     where
         A: de::EnumAccess<'de>,
     {
-        self.delegate.visit_enum(Wrap::new(data, self.source))
+        self.delegate
+            .visit_enum(Wrap::new(data, self.source, self.options))
     }
 }
 
@@ -561,6 +791,14 @@ where
     where
         K: de::DeserializeSeed<'de>,
     {
+        if self.options.expand_variants {
+            return self.delegate.next_key_seed(Key {
+                delegate: seed,
+                source: self.source,
+                options: self.options,
+            });
+        }
+
         // Do not wrap the key, we do not want to resolve keys.
         self.delegate.next_key_seed(seed)
     }
@@ -569,7 +807,8 @@ where
     where
         V: de::DeserializeSeed<'de>,
     {
-        self.delegate.next_value_seed(Wrap::new(seed, self.source))
+        self.delegate
+            .next_value_seed(Wrap::new(seed, self.source, self.options))
     }
 }
 
@@ -585,7 +824,7 @@ where
         Seed: de::DeserializeSeed<'de>,
     {
         self.delegate
-            .next_element_seed(Wrap::new(seed, self.source))
+            .next_element_seed(Wrap::new(seed, self.source, self.options))
     }
 }
 
@@ -601,7 +840,8 @@ where
     where
         V: de::DeserializeSeed<'de>,
     {
-        self.delegate.variant_seed(Wrap::new(seed, self.source))
+        self.delegate
+            .variant_seed(Wrap::new(seed, self.source, self.options))
     }
 }
 
@@ -616,22 +856,288 @@ where
     where
         D: de::Deserializer<'de>,
     {
-        let deserializer = Deserializer::new(deserializer, self.source);
+        let deserializer = Deserializer::with_options(deserializer, self.source, self.options);
         T::deserialize(self.delegate, deserializer)
     }
 }
 
+/// A map key, which only expands identifiers, like struct field names.
+///
+/// Used as [`de::DeserializeSeed`] for keys and as the [`de::Deserializer`] passed on to the
+/// seed. Keys of other types, like the keys of a `HashMap<String, _>`, are not expanded.
+struct Key<'a, T, S> {
+    delegate: T,
+    source: &'a mut S,
+    options: Options,
+}
+
+impl<'de, T, S> de::DeserializeSeed<'de> for Key<'_, T, S>
+where
+    T: de::DeserializeSeed<'de>,
+    S: Source,
+{
+    type Value = T::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        self.delegate.deserialize(Key {
+            delegate: deserializer,
+            source: self.source,
+            options: self.options,
+        })
+    }
+}
+
+macro_rules! forward_key {
+    ($($method:ident($($arg:ident: $ty:ty),*),)*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.delegate.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, D, S> de::Deserializer<'de> for Key<'_, D, S>
+where
+    D: de::Deserializer<'de>,
+    S: Source,
+{
+    type Error = D::Error;
+
+    forward_key! {
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_ignored_any(),
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.delegate
+            .deserialize_identifier(Wrap::new(visitor, self.source, self.options))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.delegate.is_human_readable()
+    }
+}
+
+/// A [`Visitor`] for the contents of an optional value, which visits `None` for missing variables.
+///
+/// All other values are passed on to the delegate as `Some`, through a [`Deserializer`], which
+/// expands them like any other value.
+struct Optional<'a, T, S> {
+    delegate: T,
+    source: &'a mut S,
+    options: Options,
+}
+
+impl<'de, T, S> Optional<'_, T, S>
+where
+    T: Visitor<'de>,
+    S: Source,
+{
+    fn some<D>(self, deserializer: D) -> Result<T::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        self.delegate.visit_some(Deserializer::with_options(
+            deserializer,
+            self.source,
+            self.options,
+        ))
+    }
+}
+
+macro_rules! forward_optional {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method<E>(self, v: $ty) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                self.some(de::IntoDeserializer::into_deserializer(v))
+            }
+        )*
+    };
+}
+
+impl<'de, T, S> Visitor<'de> for Optional<'_, T, S>
+where
+    T: Visitor<'de>,
+    S: Source,
+{
+    type Value = T::Value;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.delegate.expecting(formatter)
+    }
+
+    forward_optional! {
+        visit_bool(bool),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+        visit_char(char),
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if !self.source.contains_variable(v) {
+            return self.delegate.visit_none();
+        }
+        self.some(de::value::StrDeserializer::new(v))
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if !self.source.contains_variable(v) {
+            return self.delegate.visit_none();
+        }
+        self.some(de::value::BorrowedStrDeserializer::new(v))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if !self.source.contains_variable(&v) {
+            return self.delegate.visit_none();
+        }
+        self.some(de::value::StringDeserializer::new(v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.some(de::value::BytesDeserializer::new(v))
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.some(de::value::BorrowedBytesDeserializer::new(v))
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.some(de::value::BytesDeserializer::new(&v))
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.delegate.visit_none()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        self.some(deserializer)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.some(de::value::UnitDeserializer::new())
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        self.some(deserializer)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        self.some(de::value::SeqAccessDeserializer::new(seq))
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        self.some(de::value::MapAccessDeserializer::new(map))
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::EnumAccess<'de>,
+    {
+        self.some(de::value::EnumAccessDeserializer::new(data))
+    }
+}
+
 /// A [`de::Deserializer`] holding a [`Content`] that expands strings using a [`Source`].
 struct ContentVarDeserializer<'a, 'de, E, S> {
     content: Content<'de>,
+    human_readable: bool,
     err: PhantomData<E>,
     source: &'a mut S,
 }
 
 impl<'a, 'de, E, S> ContentVarDeserializer<'a, 'de, E, S> {
-    fn new(content: Content<'de>, source: &'a mut S) -> Self {
+    fn new(content: Content<'de>, human_readable: bool, source: &'a mut S) -> Self {
         Self {
             content,
+            human_readable,
             err: PhantomData,
             source,
         }
@@ -641,7 +1147,8 @@ impl<'a, 'de, E, S> ContentVarDeserializer<'a, 'de, E, S> {
     where
         D: de::Deserializer<'de, Error = E>,
     {
-        Content::deserialize(deserializer).map(|content| Self::new(content, source))
+        let human_readable = deserializer.is_human_readable();
+        Content::deserialize(deserializer).map(|content| Self::new(content, human_readable, source))
     }
 }
 
@@ -669,10 +1176,12 @@ where
             Content::U16(v) => visitor.visit_u16(v),
             Content::U32(v) => visitor.visit_u32(v),
             Content::U64(v) => visitor.visit_u64(v),
+            Content::U128(v) => visitor.visit_u128(v),
             Content::I8(v) => visitor.visit_i8(v),
             Content::I16(v) => visitor.visit_i16(v),
             Content::I32(v) => visitor.visit_i32(v),
             Content::I64(v) => visitor.visit_i64(v),
+            Content::I128(v) => visitor.visit_i128(v),
             Content::Str(s) => f(visitor, conv(self.source, s)?),
             Content::String(ref s) => f(visitor, conv(self.source, s)?),
             _ => Err(self.invalid_type(&visitor)),
@@ -695,10 +1204,12 @@ where
             Content::U16(v) => visitor.visit_u16(v),
             Content::U32(v) => visitor.visit_u32(v),
             Content::U64(v) => visitor.visit_u64(v),
+            Content::U128(v) => visitor.visit_u128(v),
             Content::I8(v) => visitor.visit_i8(v),
             Content::I16(v) => visitor.visit_i16(v),
             Content::I32(v) => visitor.visit_i32(v),
             Content::I64(v) => visitor.visit_i64(v),
+            Content::I128(v) => visitor.visit_i128(v),
             Content::Str(s) => f(visitor, conv(self.source, s)?),
             Content::String(ref s) => f(visitor, conv(self.source, s)?),
             _ => Err(self.invalid_type(&visitor)),
@@ -760,6 +1271,13 @@ where
         self.deserialize_integer(visitor, Visitor::visit_i64, Source::expand_i64)
     }
 
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_integer(visitor, Visitor::visit_i128, Source::expand_i128)
+    }
+
     fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
@@ -788,6 +1306,13 @@ where
         self.deserialize_integer(visitor, Visitor::visit_u64, Source::expand_u64)
     }
 
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_integer(visitor, Visitor::visit_u128, Source::expand_u128)
+    }
+
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
@@ -852,7 +1377,7 @@ where
             _ => return Err(self.invalid_type(&visitor)),
         } {
             Cow::Owned(v) => visitor.visit_byte_buf(v),
-            Cow::Borrowed(v) => visitor.visit_bytes(v),
+            Cow::Borrowed(v) => visitor.visit_borrowed_bytes(v),
         }
     }
 
@@ -963,4 +1488,8 @@ where
         drop(self);
         visitor.visit_unit()
     }
+
+    fn is_human_readable(&self) -> bool {
+        self.human_readable
+    }
 }
@@ -76,27 +76,51 @@
 //! - Any positive integer is parsed as a `u64`.
 //! - Any negative integer is parsed as a `i64`.
 //! - Any floating point value is parsed as a `f64`.
+//! - Numbers are parsed locale independent, e.g. `.` is always the decimal separator.
 //! - Everything else is parsed as a string. In order to be able to specify numbers as strings,
 //!   the source recognizes arbitrary values wrapped in `"` as a string. For example `"123"` is
 //!   parsed as the literal string `123`.
 //!
 //! For more details read the [`StringSource`] documentation.
 //!
+//! # Structured Values
+//!
+//! By default, variables are only expanded in place of primitive types. Sources can opt in to
+//! expanding variables in place of maps, structs and sequences, see
+//! [`Source::expands_structured`](source::Source::expands_structured). The [`StringSource`]
+//! supports:
+//!
+//! - Values split on a delimiter into sequences, see [`StringSource::with_seq_delimiter`].
+//! - JSON documents expanded into maps, structs and sequences, with the `json` feature.
+//! - Maps collecting all variables sharing a prefix, e.g. `${prefix:FEATURE_}`,
+//!   see [`StringSource::with_prefix_maps`].
+//!
+//! Structured values are deserialized as self-describing values, which requires
+//! a self-describing format, like JSON or YAML.
+//!
 //! # Alternatives
 //!
-//! Variable expansion is limited to primitive types and not supported for nested data structures,
+//! Variable expansion is limited to values and does not merge or layer nested data structures,
 //! this is currently by design. The intention of this library is not to provide another generic
 //! abstraction layer for configurations. If you are looking for a much more powerful mechanism
 //! to load and layer [`serde`] based configurations, you should take a look at
 //! [`figment`](https://docs.rs/figment/) instead.
 
+mod buffer;
 mod content;
 mod de;
+mod masked;
 pub mod source;
 mod value;
+#[cfg(feature = "notify")]
+pub mod watch;
 
 pub use self::de::Deserializer;
-pub use self::source::{EnvSource, FileSource, MapSource, StringSource};
+pub use self::masked::Masked;
+pub use self::source::{
+    DirSource, DotenvSource, EnvSource, EnvironmentFileSource, FileSource, MapSource,
+    PropertiesSource, RoutingSource, StringSource,
+};
 
 /// Entry point. See [crate documentation](crate) for an example.
 pub fn deserialize<'de, D, S, T>(deserializer: D, source: &mut S) -> Result<T, D::Error>
@@ -107,3 +131,55 @@ where
 {
     T::deserialize(self::de::Deserializer::new(deserializer, source))
 }
+
+/// Deserializes the same document once for every source.
+///
+/// This is useful to render a single configuration template for multiple tenants,
+/// each with their own [`source::Source`].
+///
+/// The document is read from the deserializer and buffered once, before it is deserialized
+/// for every source. Errors while reading the document are returned immediately.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::MapSource;
+/// use std::collections::HashMap;
+///
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Config {
+///     database: String,
+/// }
+///
+/// let mut de = serde_json::Deserializer::from_str(r#"{"database": "${DATABASE}"}"#);
+///
+/// let tenants = ["tenant_a", "tenant_b"].map(|tenant| {
+///     MapSource::new(HashMap::from([("DATABASE".to_owned(), format!("db_{tenant}"))]))
+/// });
+///
+/// let configs = serde_vars::deserialize_batch(&mut de, tenants)
+///     .unwrap()
+///     .collect::<Result<Vec<Config>, _>>()
+///     .unwrap();
+///
+/// assert_eq!(configs[0].database, "db_tenant_a");
+/// assert_eq!(configs[1].database, "db_tenant_b");
+/// ```
+pub fn deserialize_batch<'de, D, S, T, I>(
+    deserializer: D,
+    sources: I,
+) -> Result<impl Iterator<Item = Result<T, D::Error>>, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    T: serde::de::Deserialize<'de>,
+    S: source::Source,
+    I: IntoIterator<Item = S>,
+{
+    let human_readable = deserializer.is_human_readable();
+    let buffer = <buffer::Buffer<'de> as serde::Deserialize>::deserialize(deserializer)?;
+
+    Ok(sources.into_iter().map(move |mut source| {
+        let deserializer = buffer::BufferDeserializer::new(&buffer, human_readable);
+        deserialize(deserializer, &mut source)
+    }))
+}
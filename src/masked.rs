@@ -0,0 +1,406 @@
+use std::{
+    borrow::Cow,
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use serde::de::{self, value::BorrowedStrDeserializer, IntoDeserializer};
+
+use crate::source::{Any, Source, Structured};
+
+pub(crate) const NAME: &str = "$serde_vars::private::Masked";
+const FIELD_VALUE: &str = "$serde_vars::private::Masked::value";
+const FIELD_EXPANDED: &str = "$serde_vars::private::Masked::expanded";
+
+/// A wrapper which masks values expanded from variables in its [`Debug`](fmt::Debug) output.
+///
+/// When deserialized through a [`Deserializer`](crate::Deserializer), the wrapper keeps track
+/// whether the contained value was expanded from a variable. Expanded values are printed
+/// as `***`, values which were contained in the original configuration are printed as is.
+///
+/// Whether a value references a variable is determined by [`Source::references_variable`].
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::Masked;
+/// use std::collections::HashMap;
+///
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Config {
+///     user: Masked<String>,
+///     password: Masked<String>,
+/// }
+///
+/// let source = HashMap::from([("PASSWORD".to_owned(), "hunter2".to_owned())]);
+/// let mut source = serde_vars::MapSource::new(source);
+///
+/// let mut de = serde_json::Deserializer::from_str(r#"{"user": "admin", "password": "${PASSWORD}"}"#);
+/// let config: Config = serde_vars::deserialize(&mut de, &mut source).unwrap();
+///
+/// assert_eq!(*config.password, "hunter2");
+/// assert_eq!(
+///     format!("{config:?}"),
+///     r#"Config { user: "admin", password: *** }"#
+/// );
+/// ```
+#[derive(Clone, Default)]
+pub struct Masked<T> {
+    value: T,
+    expanded: bool,
+}
+
+impl<T> Masked<T> {
+    /// Returns `true` if the contained value was expanded from a variable.
+    pub fn is_expanded(&self) -> bool {
+        self.expanded
+    }
+
+    /// Returns the contained value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Masked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Masked<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T> fmt::Debug for Masked<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.expanded {
+            true => f.write_str("***"),
+            false => self.value.fmt(f),
+        }
+    }
+}
+
+impl<'de, T> de::Deserialize<'de> for Masked<T>
+where
+    T: de::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct Visitor<T>(std::marker::PhantomData<T>);
+
+        impl<'de, T> de::Visitor<'de> for Visitor<T>
+        where
+            T: de::Deserialize<'de>,
+        {
+            type Value = Masked<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a masked value")
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: de::Deserializer<'de>,
+            {
+                Ok(Masked {
+                    value: T::deserialize(deserializer)?,
+                    expanded: false,
+                })
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                if map.next_key::<&str>()? != Some(FIELD_VALUE) {
+                    return Err(de::Error::custom("masked value field not found"));
+                }
+                let value = map.next_value()?;
+
+                if map.next_key::<&str>()? != Some(FIELD_EXPANDED) {
+                    return Err(de::Error::custom("masked expanded field not found"));
+                }
+                let expanded = map.next_value()?;
+
+                Ok(Masked { value, expanded })
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(NAME, Visitor(std::marker::PhantomData))
+    }
+}
+
+/// Deserializes a [`Masked`] value, tracking whether the value is expanded from a variable.
+pub(crate) fn deserialize<'de, D, S, V>(
+    deserializer: D,
+    source: &mut S,
+    options: crate::de::Options,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: de::Deserializer<'de>,
+    S: Source,
+    V: de::Visitor<'de>,
+{
+    visitor.visit_map(MaskedAccess {
+        de: Some(deserializer),
+        source: Tracking {
+            source,
+            expanded: false,
+        },
+        field: 0,
+        options,
+    })
+}
+
+struct MaskedAccess<'a, D, S> {
+    de: Option<D>,
+    source: Tracking<'a, S>,
+    field: u8,
+    options: crate::de::Options,
+}
+
+impl<'de, D, S> de::MapAccess<'de> for MaskedAccess<'_, D, S>
+where
+    D: de::Deserializer<'de>,
+    S: Source,
+{
+    type Error = D::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        let key = match self.field {
+            0 => FIELD_VALUE,
+            1 => FIELD_EXPANDED,
+            _ => return Ok(None),
+        };
+        seed.deserialize(BorrowedStrDeserializer::new(key))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        self.field += 1;
+        match self.de.take() {
+            Some(de) => seed.deserialize(crate::Deserializer::with_options(
+                de,
+                &mut self.source,
+                self.options,
+            )),
+            None => seed.deserialize(self.source.expanded.into_deserializer()),
+        }
+    }
+}
+
+/// A [`Source`] which keeps track whether any variable was expanded.
+struct Tracking<'a, S> {
+    source: &'a mut S,
+    expanded: bool,
+}
+
+impl<S> Tracking<'_, S>
+where
+    S: Source,
+{
+    /// Expands `v` with `f` and records whether a variable was expanded.
+    ///
+    /// Values of sources, which cannot tell whether `v` references a variable, are always
+    /// considered to be expanded.
+    fn expand<T, E>(&mut self, v: &str, f: impl FnOnce(&mut S) -> Result<T, E>) -> Result<T, E> {
+        let references = self.source.references_variable(v);
+        let value = f(self.source)?;
+        self.expanded |= references.unwrap_or(true);
+        Ok(value)
+    }
+}
+
+impl<S> Source for Tracking<'_, S>
+where
+    S: Source,
+{
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        self.expand(v, |s| s.expand_bool(v))
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        self.expand(v, |s| s.expand_i8(v))
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        self.expand(v, |s| s.expand_i16(v))
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        self.expand(v, |s| s.expand_i32(v))
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        self.expand(v, |s| s.expand_i64(v))
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        self.expand(v, |s| s.expand_i128(v))
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        self.expand(v, |s| s.expand_u8(v))
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        self.expand(v, |s| s.expand_u16(v))
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        self.expand(v, |s| s.expand_u32(v))
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        self.expand(v, |s| s.expand_u64(v))
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        self.expand(v, |s| s.expand_u128(v))
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        self.expand(v, |s| s.expand_f32(v))
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        self.expand(v, |s| s.expand_f64(v))
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        // Sources return the original string, if it does not contain a variable.
+        let references = self
+            .source
+            .references_variable(&v)
+            .ok_or_else(|| v.to_string());
+        let value = self.source.expand_str(v)?;
+        self.expanded |= references.unwrap_or_else(|original| value != original.as_str());
+        Ok(value)
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        let references = std::str::from_utf8(&v)
+            .ok()
+            .and_then(|s| self.source.references_variable(s))
+            .ok_or_else(|| v.to_vec());
+        let value = self.source.expand_bytes(v)?;
+        self.expanded |= references.unwrap_or_else(|original| value != original.as_slice());
+        Ok(value)
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        let references = self
+            .source
+            .references_variable(&v)
+            .ok_or_else(|| v.to_string());
+        let value = self.source.expand_any(v)?;
+        self.expanded |= references
+            .unwrap_or_else(|original| !matches!(&value, Any::Str(s) if *s == original.as_str()));
+        Ok(value)
+    }
+
+    fn expand_structured<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        let references = self
+            .source
+            .references_variable(&v)
+            .ok_or_else(|| v.to_string());
+        let value = self.source.expand_structured(v)?;
+        self.expanded |= references.unwrap_or_else(
+            |original| !matches!(&value, Structured::Any(Any::Str(s)) if *s == original.as_str()),
+        );
+        Ok(value)
+    }
+
+    fn expand_map<E>(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        let entries = self.source.expand_map(v)?;
+        self.expanded |= entries.is_some();
+        Ok(entries)
+    }
+
+    fn expands_structured(&self) -> bool {
+        self.source.expands_structured()
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        self.source.contains_variable(v)
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        self.source.references_variable(v)
+    }
+
+    fn name(&self) -> String {
+        self.source.name()
+    }
+}
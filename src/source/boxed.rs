@@ -0,0 +1,364 @@
+use std::{borrow::Cow, fmt};
+
+use serde::de;
+
+use crate::source::{Any, Source, Structured};
+
+/// The error of a [`DynSource`].
+pub type DynError = de::value::Error;
+
+/// A dyn-compatible version of [`Source`].
+///
+/// Every [`Source`] implements [`DynSource`], errors are erased into a [`DynError`] and
+/// converted back to the error of the deserializer using [`de::Error::custom`]. This allows
+/// holding sources as trait objects, e.g. `Vec<Box<dyn DynSource>>`, and using them as
+/// a [`Source`] through a [`BoxedSource`].
+///
+/// Methods are prefixed with `erased_` to not conflict with the methods of [`Source`],
+/// see [`Source`] for the documentation of the individual methods.
+pub trait DynSource {
+    /// See [`Source::expand_bool`].
+    fn erased_expand_bool(&mut self, v: &str) -> Result<bool, DynError>;
+    /// See [`Source::expand_i8`].
+    fn erased_expand_i8(&mut self, v: &str) -> Result<i8, DynError>;
+    /// See [`Source::expand_i16`].
+    fn erased_expand_i16(&mut self, v: &str) -> Result<i16, DynError>;
+    /// See [`Source::expand_i32`].
+    fn erased_expand_i32(&mut self, v: &str) -> Result<i32, DynError>;
+    /// See [`Source::expand_i64`].
+    fn erased_expand_i64(&mut self, v: &str) -> Result<i64, DynError>;
+    /// See [`Source::expand_i128`].
+    fn erased_expand_i128(&mut self, v: &str) -> Result<i128, DynError>;
+    /// See [`Source::expand_u8`].
+    fn erased_expand_u8(&mut self, v: &str) -> Result<u8, DynError>;
+    /// See [`Source::expand_u16`].
+    fn erased_expand_u16(&mut self, v: &str) -> Result<u16, DynError>;
+    /// See [`Source::expand_u32`].
+    fn erased_expand_u32(&mut self, v: &str) -> Result<u32, DynError>;
+    /// See [`Source::expand_u64`].
+    fn erased_expand_u64(&mut self, v: &str) -> Result<u64, DynError>;
+    /// See [`Source::expand_u128`].
+    fn erased_expand_u128(&mut self, v: &str) -> Result<u128, DynError>;
+    /// See [`Source::expand_f32`].
+    fn erased_expand_f32(&mut self, v: &str) -> Result<f32, DynError>;
+    /// See [`Source::expand_f64`].
+    fn erased_expand_f64(&mut self, v: &str) -> Result<f64, DynError>;
+    /// See [`Source::expand_str`].
+    fn erased_expand_str<'a>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, DynError>;
+    /// See [`Source::expand_bytes`].
+    fn erased_expand_bytes<'a>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, DynError>;
+    /// See [`Source::expand_any`].
+    fn erased_expand_any<'a>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, DynError>;
+    /// See [`Source::expand_structured`].
+    fn erased_expand_structured<'a>(&mut self, v: Cow<'a, str>)
+        -> Result<Structured<'a>, DynError>;
+    /// See [`Source::expand_map`].
+    fn erased_expand_map(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, DynError>;
+    /// See [`Source::expands_structured`].
+    fn erased_expands_structured(&self) -> bool;
+    /// See [`Source::contains_variable`].
+    fn erased_contains_variable(&mut self, v: &str) -> bool;
+    /// See [`Source::references_variable`].
+    fn erased_references_variable(&mut self, v: &str) -> Option<bool>;
+    /// See [`Source::name`].
+    fn erased_name(&self) -> String;
+}
+
+impl<S> DynSource for S
+where
+    S: Source,
+{
+    fn erased_expand_bool(&mut self, v: &str) -> Result<bool, DynError> {
+        Source::expand_bool(self, v)
+    }
+
+    fn erased_expand_i8(&mut self, v: &str) -> Result<i8, DynError> {
+        Source::expand_i8(self, v)
+    }
+
+    fn erased_expand_i16(&mut self, v: &str) -> Result<i16, DynError> {
+        Source::expand_i16(self, v)
+    }
+
+    fn erased_expand_i32(&mut self, v: &str) -> Result<i32, DynError> {
+        Source::expand_i32(self, v)
+    }
+
+    fn erased_expand_i64(&mut self, v: &str) -> Result<i64, DynError> {
+        Source::expand_i64(self, v)
+    }
+
+    fn erased_expand_i128(&mut self, v: &str) -> Result<i128, DynError> {
+        Source::expand_i128(self, v)
+    }
+
+    fn erased_expand_u8(&mut self, v: &str) -> Result<u8, DynError> {
+        Source::expand_u8(self, v)
+    }
+
+    fn erased_expand_u16(&mut self, v: &str) -> Result<u16, DynError> {
+        Source::expand_u16(self, v)
+    }
+
+    fn erased_expand_u32(&mut self, v: &str) -> Result<u32, DynError> {
+        Source::expand_u32(self, v)
+    }
+
+    fn erased_expand_u64(&mut self, v: &str) -> Result<u64, DynError> {
+        Source::expand_u64(self, v)
+    }
+
+    fn erased_expand_u128(&mut self, v: &str) -> Result<u128, DynError> {
+        Source::expand_u128(self, v)
+    }
+
+    fn erased_expand_f32(&mut self, v: &str) -> Result<f32, DynError> {
+        Source::expand_f32(self, v)
+    }
+
+    fn erased_expand_f64(&mut self, v: &str) -> Result<f64, DynError> {
+        Source::expand_f64(self, v)
+    }
+
+    fn erased_expand_str<'a>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, DynError> {
+        Source::expand_str(self, v)
+    }
+
+    fn erased_expand_bytes<'a>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, DynError> {
+        Source::expand_bytes(self, v)
+    }
+
+    fn erased_expand_any<'a>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, DynError> {
+        Source::expand_any(self, v)
+    }
+
+    fn erased_expand_structured<'a>(
+        &mut self,
+        v: Cow<'a, str>,
+    ) -> Result<Structured<'a>, DynError> {
+        Source::expand_structured(self, v)
+    }
+
+    fn erased_expand_map(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, DynError> {
+        Source::expand_map(self, v)
+    }
+
+    fn erased_expands_structured(&self) -> bool {
+        Source::expands_structured(self)
+    }
+
+    fn erased_contains_variable(&mut self, v: &str) -> bool {
+        Source::contains_variable(self, v)
+    }
+
+    fn erased_references_variable(&mut self, v: &str) -> Option<bool> {
+        Source::references_variable(self, v)
+    }
+
+    fn erased_name(&self) -> String {
+        Source::name(self)
+    }
+}
+
+/// A [`Source`] which expands variables using a boxed [`DynSource`].
+///
+/// Allows choosing sources at runtime, e.g. from a configuration or a plugin, and storing
+/// sources of different types in the same collection.
+///
+/// Errors of the boxed source are converted to errors of the deserializer with
+/// [`de::Error::custom`], which preserves the message, but not the kind of the error.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::{source::{BoxedSource, Source}, EnvSource, MapSource};
+///
+/// let sources = vec![
+///     BoxedSource::new(MapSource::default().insert("MY_PORT", "8080")),
+///     BoxedSource::new(EnvSource::default()),
+/// ];
+///
+/// let mut source = sources
+///     .into_iter()
+///     .reduce(|first, second| BoxedSource::new(first.or(second)))
+///     .unwrap();
+///
+/// let mut de = serde_json::Deserializer::from_str(r#""${MY_PORT}""#);
+/// let r: u16 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, 8080);
+/// ```
+pub struct BoxedSource<'s> {
+    source: Box<dyn DynSource + 's>,
+}
+
+impl<'s> BoxedSource<'s> {
+    /// Creates a [`BoxedSource`] from `source`.
+    pub fn new(source: impl Source + 's) -> Self {
+        Self {
+            source: Box::new(source),
+        }
+    }
+
+    /// Returns the boxed source.
+    pub fn into_inner(self) -> Box<dyn DynSource + 's> {
+        self.source
+    }
+}
+
+impl<'s> From<Box<dyn DynSource + 's>> for BoxedSource<'s> {
+    fn from(source: Box<dyn DynSource + 's>) -> Self {
+        Self { source }
+    }
+}
+
+impl fmt::Debug for BoxedSource<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BoxedSource")
+            .field(&self.source.erased_name())
+            .finish()
+    }
+}
+
+impl Source for BoxedSource<'_> {
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_bool(v).map_err(E::custom)
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_i8(v).map_err(E::custom)
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_i16(v).map_err(E::custom)
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_i32(v).map_err(E::custom)
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_i64(v).map_err(E::custom)
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_i128(v).map_err(E::custom)
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_u8(v).map_err(E::custom)
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_u16(v).map_err(E::custom)
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_u32(v).map_err(E::custom)
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_u64(v).map_err(E::custom)
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_u128(v).map_err(E::custom)
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_f32(v).map_err(E::custom)
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_f64(v).map_err(E::custom)
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_str(v).map_err(E::custom)
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_bytes(v).map_err(E::custom)
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_any(v).map_err(E::custom)
+    }
+
+    fn expand_structured<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_structured(v).map_err(E::custom)
+    }
+
+    fn expand_map<E>(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        self.source.erased_expand_map(v).map_err(E::custom)
+    }
+
+    fn expands_structured(&self) -> bool {
+        self.source.erased_expands_structured()
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        self.source.erased_contains_variable(v)
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        self.source.erased_references_variable(v)
+    }
+
+    fn name(&self) -> String {
+        self.source.erased_name()
+    }
+}
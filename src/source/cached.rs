@@ -0,0 +1,562 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use serde::de;
+
+use crate::source::{Any, Source, Structured};
+
+/// A [`Source`] which caches the expanded values of another source.
+///
+/// Every variable is expanded only once per target type, repeated expansions return the cached
+/// value. This avoids repeatedly hitting expensive sources, like files or network requests,
+/// when the same variable is used in many places of a configuration.
+///
+/// Only successfully expanded variables are cached, strings which do not reference a variable
+/// and failed expansions are passed on to the source every time. By default the cache lives as
+/// long as the [`CachedSource`], create a new one for every deserialization or use
+/// [`Self::clear`] and [`Self::invalidate`] to pick up changed values. Long-running processes,
+/// which repeatedly deserialize their configuration, can limit how long values are cached with
+/// [`Self::with_ttl`], to pick up rotated secrets.
+///
+/// Variables can also be expanded with [`Self::warm_up`], before the deserialization begins,
+/// e.g. at the startup of an application. Warmed up variables are expanded once into their
+/// self-describing value, see [`Source::expand_any`], which is used for all target types it
+/// can be represented in. A variable with the value `5432` is used for integer, floating point
+/// and self-describing targets, a variable with the value `localhost` for strings and bytes.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::{source::CachedSource, FileSource};
+///
+/// let mut source = CachedSource::new(FileSource::new().with_base_path("/run/secrets"));
+/// ```
+#[derive(Debug)]
+pub struct CachedSource<S> {
+    source: S,
+    ttl: Option<Duration>,
+    values: HashMap<String, Vec<Entry>>,
+}
+
+/// A cached value and when it expires.
+#[derive(Debug)]
+struct Entry {
+    value: Value,
+    expires: Option<Instant>,
+}
+
+impl Entry {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+}
+
+/// An expanded value, cached for one of the target types.
+#[derive(Debug)]
+enum Value {
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    F32(f32),
+    F64(f64),
+    Str(String),
+    Bytes(Vec<u8>),
+    Any(Any<'static>),
+    Structured(Structured<'static>),
+}
+
+impl<S> CachedSource<S> {
+    /// Creates a [`CachedSource`], which caches the values expanded by `source`.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            ttl: None,
+            values: HashMap::new(),
+        }
+    }
+
+    /// Expires cached values `ttl` after they were expanded.
+    ///
+    /// Expired values are expanded by the source again on their next use. By default values
+    /// do not expire.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use serde_vars::{source::CachedSource, FileSource};
+    ///
+    /// let mut source = CachedSource::new(FileSource::new().with_base_path("/run/secrets"))
+    ///     .with_ttl(Duration::from_secs(300));
+    /// ```
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Removes all cached values.
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    /// Removes the cached values of the string `v`, as it appears in the configuration.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use serde_vars::{source::CachedSource, MapSource};
+    ///
+    /// let mut source = CachedSource::new(MapSource::default());
+    /// source.invalidate("${DB_PASSWORD}");
+    /// ```
+    pub fn invalidate(&mut self, v: &str) {
+        self.values.remove(v);
+    }
+
+    /// Returns the wrapped source.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S> CachedSource<S>
+where
+    S: Source,
+{
+    /// Expands and caches the variables `vars` ahead of the deserialization.
+    ///
+    /// Variables are given as they appear in the configuration, e.g. `${DB_PASSWORD}`.
+    /// Returns the first error of the source, variables which follow the failed variable
+    /// are not expanded.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use serde_vars::{source::CachedSource, MapSource};
+    /// use std::collections::HashMap;
+    ///
+    /// let source = MapSource::new(HashMap::from([("PORT".to_owned(), "5432".to_owned())]));
+    /// let mut source = CachedSource::new(source);
+    /// source.warm_up::<serde_json::Error, _>(["${PORT}"]).unwrap();
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${PORT}""#);
+    /// let r: u16 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, 5432);
+    /// ```
+    pub fn warm_up<E, I>(&mut self, vars: I) -> Result<(), E>
+    where
+        E: de::Error,
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        for v in vars {
+            let v = v.into();
+            self.expand_any::<E>(Cow::Borrowed(&v))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the cached value of the variable `v`, selected by `get`.
+    fn get<T>(&self, v: &str, get: impl Fn(&Value) -> Option<T>) -> Option<T> {
+        let now = Instant::now();
+        self.values
+            .get(v)?
+            .iter()
+            .filter(|entry| !entry.is_expired(now))
+            .find_map(|entry| get(&entry.value))
+    }
+
+    fn insert(&mut self, v: &str, value: Value) {
+        let now = Instant::now();
+        let entries = self.values.entry(v.to_owned()).or_default();
+        entries.retain(|entry| !entry.is_expired(now));
+        entries.push(Entry {
+            value,
+            expires: self.ttl.and_then(|ttl| now.checked_add(ttl)),
+        });
+    }
+
+    /// Returns the cached value of the variable `v` or expands and caches it.
+    fn cached<T, E>(
+        &mut self,
+        v: &str,
+        get: impl Fn(&Value) -> Option<T>,
+        expand: impl FnOnce(&mut S) -> Result<T, E>,
+        wrap: impl FnOnce(T) -> Value,
+    ) -> Result<T, E>
+    where
+        T: Clone,
+    {
+        if let Some(value) = self.get(v, get) {
+            return Ok(value);
+        }
+
+        let value = expand(&mut self.source)?;
+        self.insert(v, wrap(value.clone()));
+        Ok(value)
+    }
+}
+
+impl<S> Source for CachedSource<S>
+where
+    S: Source,
+{
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        self.cached(
+            v,
+            |value| match value {
+                Value::Bool(value) => Some(*value),
+                Value::Any(Any::Bool(value)) => Some(*value),
+                _ => None,
+            },
+            |source| source.expand_bool(v),
+            Value::Bool,
+        )
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        self.cached(
+            v,
+            |value| match value {
+                Value::I8(value) => Some(*value),
+                Value::Any(value) => integer(value),
+                _ => None,
+            },
+            |source| source.expand_i8(v),
+            Value::I8,
+        )
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        self.cached(
+            v,
+            |value| match value {
+                Value::I16(value) => Some(*value),
+                Value::Any(value) => integer(value),
+                _ => None,
+            },
+            |source| source.expand_i16(v),
+            Value::I16,
+        )
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        self.cached(
+            v,
+            |value| match value {
+                Value::I32(value) => Some(*value),
+                Value::Any(value) => integer(value),
+                _ => None,
+            },
+            |source| source.expand_i32(v),
+            Value::I32,
+        )
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        self.cached(
+            v,
+            |value| match value {
+                Value::I64(value) => Some(*value),
+                Value::Any(value) => integer(value),
+                _ => None,
+            },
+            |source| source.expand_i64(v),
+            Value::I64,
+        )
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        self.cached(
+            v,
+            |value| match value {
+                Value::I128(value) => Some(*value),
+                Value::Any(value) => integer(value),
+                _ => None,
+            },
+            |source| source.expand_i128(v),
+            Value::I128,
+        )
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        self.cached(
+            v,
+            |value| match value {
+                Value::U8(value) => Some(*value),
+                Value::Any(value) => integer(value),
+                _ => None,
+            },
+            |source| source.expand_u8(v),
+            Value::U8,
+        )
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        self.cached(
+            v,
+            |value| match value {
+                Value::U16(value) => Some(*value),
+                Value::Any(value) => integer(value),
+                _ => None,
+            },
+            |source| source.expand_u16(v),
+            Value::U16,
+        )
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        self.cached(
+            v,
+            |value| match value {
+                Value::U32(value) => Some(*value),
+                Value::Any(value) => integer(value),
+                _ => None,
+            },
+            |source| source.expand_u32(v),
+            Value::U32,
+        )
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        self.cached(
+            v,
+            |value| match value {
+                Value::U64(value) => Some(*value),
+                Value::Any(value) => integer(value),
+                _ => None,
+            },
+            |source| source.expand_u64(v),
+            Value::U64,
+        )
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        self.cached(
+            v,
+            |value| match value {
+                Value::U128(value) => Some(*value),
+                Value::Any(value) => integer(value),
+                _ => None,
+            },
+            |source| source.expand_u128(v),
+            Value::U128,
+        )
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        self.cached(
+            v,
+            |value| match value {
+                Value::F32(value) => Some(*value),
+                Value::Any(Any::F32(value)) => Some(*value),
+                // Integers are rounded once, exactly like a parsed integer, unlike an `f64`.
+                Value::Any(value) => integer::<i128>(value)
+                    .map(|value| value as f32)
+                    .or_else(|| integer::<u128>(value).map(|value| value as f32)),
+                _ => None,
+            },
+            |source| source.expand_f32(v),
+            Value::F32,
+        )
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        self.cached(
+            v,
+            |value| match value {
+                Value::F64(value) => Some(*value),
+                Value::Any(Any::F32(value)) => Some(f64::from(*value)),
+                Value::Any(Any::F64(value)) => Some(*value),
+                Value::Any(value) => integer::<i128>(value)
+                    .map(|value| value as f64)
+                    .or_else(|| integer::<u128>(value).map(|value| value as f64)),
+                _ => None,
+            },
+            |source| source.expand_f64(v),
+            Value::F64,
+        )
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        let cached = self.get(&v, |value| match value {
+            Value::Str(value) => Some(value.clone()),
+            Value::Any(Any::Str(value)) => Some(value.clone().into_owned()),
+            _ => None,
+        });
+        if let Some(value) = cached {
+            return Ok(Cow::Owned(value));
+        }
+
+        let key = v.clone();
+        let value = self.source.expand_str(v)?;
+        if value != key {
+            self.insert(&key, Value::Str(value.clone().into_owned()));
+        }
+        Ok(value)
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        // Only strings can reference variables.
+        let Ok(key) = std::str::from_utf8(&v).map(str::to_owned) else {
+            return self.source.expand_bytes(v);
+        };
+
+        let cached = self.get(&key, |value| match value {
+            Value::Bytes(value) => Some(value.clone()),
+            Value::Any(Any::Str(value)) => Some(value.as_bytes().to_vec()),
+            Value::Any(Any::Bytes(value)) => Some(value.clone().into_owned()),
+            _ => None,
+        });
+        if let Some(value) = cached {
+            return Ok(Cow::Owned(value));
+        }
+
+        let value = self.source.expand_bytes(v)?;
+        if *value != *key.as_bytes() {
+            self.insert(&key, Value::Bytes(value.clone().into_owned()));
+        }
+        Ok(value)
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        let cached = self.get(&v, |value| match value {
+            Value::Any(value) => Some(value.clone()),
+            _ => None,
+        });
+        if let Some(value) = cached {
+            return Ok(value);
+        }
+
+        let key = v.clone();
+        let value = self.source.expand_any(v)?;
+        if !matches!(&value, Any::Str(value) if *value == *key) {
+            self.insert(&key, Value::Any(value.clone().into_owned()));
+        }
+        Ok(value)
+    }
+
+    fn expand_structured<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        let cached = self.get(&v, |value| match value {
+            Value::Structured(value) => Some(value.clone()),
+            _ => None,
+        });
+        if let Some(value) = cached {
+            return Ok(value);
+        }
+
+        let key = v.clone();
+        let value = self.source.expand_structured(v)?;
+        if !matches!(&value, Structured::Any(Any::Str(value)) if *value == *key) {
+            self.insert(&key, Value::Structured(value.clone().into_owned()));
+        }
+        Ok(value)
+    }
+
+    fn expand_map<E>(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        self.source.expand_map(v)
+    }
+
+    fn expands_structured(&self) -> bool {
+        self.source.expands_structured()
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        self.values.contains_key(v) || self.source.contains_variable(v)
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        self.source.references_variable(v)
+    }
+
+    fn name(&self) -> String {
+        self.source.name()
+    }
+}
+
+/// Converts the self-describing integer `value` into the integer type `T`.
+///
+/// Returns `None` for values which are not integers or do not fit into `T`.
+fn integer<T>(value: &Any<'_>) -> Option<T>
+where
+    T: TryFrom<i128> + TryFrom<u128>,
+{
+    match *value {
+        Any::I8(value) => T::try_from(i128::from(value)).ok(),
+        Any::I16(value) => T::try_from(i128::from(value)).ok(),
+        Any::I32(value) => T::try_from(i128::from(value)).ok(),
+        Any::I64(value) => T::try_from(i128::from(value)).ok(),
+        Any::I128(value) => T::try_from(value).ok(),
+        Any::U8(value) => T::try_from(u128::from(value)).ok(),
+        Any::U16(value) => T::try_from(u128::from(value)).ok(),
+        Any::U32(value) => T::try_from(u128::from(value)).ok(),
+        Any::U64(value) => T::try_from(u128::from(value)).ok(),
+        Any::U128(value) => T::try_from(value).ok(),
+        _ => None,
+    }
+}
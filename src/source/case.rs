@@ -0,0 +1,129 @@
+use crate::source::MapNames;
+
+/// A naming convention for variable names, used by [`MapCase`].
+///
+/// Names are split into words at non-alphanumeric characters, like `-`, `_` and `.`, and at
+/// changes from lower- to uppercase letters, acronyms are kept together, e.g. `httpServerURL`
+/// and `http-server-url` both consist of the words `http`, `server` and `url`.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::source::Case;
+///
+/// assert_eq!(Case::ScreamingSnake.convert("database-url"), "DATABASE_URL");
+/// assert_eq!(Case::ScreamingSnake.convert("maxConnections"), "MAX_CONNECTIONS");
+/// assert_eq!(Case::Kebab.convert("HTTPServer"), "http-server");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Case {
+    /// `snake_case`.
+    Snake,
+    /// `SCREAMING_SNAKE_CASE`, the conventional naming of environment variables.
+    ScreamingSnake,
+    /// `kebab-case`.
+    Kebab,
+    /// `camelCase`.
+    Camel,
+    /// `PascalCase`.
+    Pascal,
+}
+
+impl Case {
+    /// Converts `name` to this naming convention.
+    pub fn convert(&self, name: &str) -> String {
+        let words = words(name);
+        match self {
+            Self::Snake => join(&words, "_", str::to_lowercase),
+            Self::ScreamingSnake => join(&words, "_", str::to_uppercase),
+            Self::Kebab => join(&words, "-", str::to_lowercase),
+            Self::Camel => match words.split_first() {
+                Some((first, rest)) => first.to_lowercase() + &join(rest, "", capitalize),
+                None => String::new(),
+            },
+            Self::Pascal => join(&words, "", capitalize),
+        }
+    }
+}
+
+/// Splits `name` into its words.
+fn words(name: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+
+    for part in name.split(|c: char| !c.is_alphanumeric()) {
+        let mut start = 0;
+        let mut prev = None;
+        let mut chars = part.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            let next = chars.peek().map(|&(_, c)| c);
+            // A word starts with an uppercase letter following a lowercase letter or digit,
+            // or with the last uppercase letter of an acronym followed by a lowercase letter.
+            let starts_word = prev.is_some_and(|prev: char| {
+                c.is_uppercase() && (!prev.is_uppercase() || next.is_some_and(char::is_lowercase))
+            });
+            if starts_word {
+                words.push(&part[start..i]);
+                start = i;
+            }
+            prev = Some(c);
+        }
+        words.push(&part[start..]);
+    }
+
+    words.retain(|word| !word.is_empty());
+    words
+}
+
+fn join(words: &[&str], separator: &str, f: impl Fn(&str) -> String) -> String {
+    words
+        .iter()
+        .map(|word| f(word))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// A [`Source`](crate::source::Source) which converts the names of variables to a naming
+/// convention, before they are expanded by another source.
+///
+/// This allows configuration authors to use the idiomatic naming of the configuration format,
+/// while deployments keep conventional names, e.g. for environment variables.
+///
+/// Usually created with [`Source::map_case`](crate::source::Source::map_case),
+/// see [`MapNames`] for details.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::{source::{Case, MapCase}, EnvSource};
+///
+/// let mut source = MapCase::new(EnvSource::default(), Case::ScreamingSnake.into());
+/// # unsafe { std::env::set_var("REDIS_HOST", "localhost"); }
+///
+/// let mut de = serde_json::Deserializer::from_str(r#""${redis-host}""#);
+/// let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, "localhost");
+/// ```
+pub type MapCase<S> = MapNames<S, fn(&str) -> String>;
+
+impl From<Case> for fn(&str) -> String {
+    fn from(case: Case) -> Self {
+        match case {
+            Case::Snake => |name| Case::Snake.convert(name),
+            Case::ScreamingSnake => |name| Case::ScreamingSnake.convert(name),
+            Case::Kebab => |name| Case::Kebab.convert(name),
+            Case::Camel => |name| Case::Camel.convert(name),
+            Case::Pascal => |name| Case::Pascal.convert(name),
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use std::borrow::Cow;
+
+use serde::de;
+
+use crate::source::{utils, Any, Source, Structured};
+
+/// A [`Source`] which expands variables using the first of two sources, which contains them.
+///
+/// Variables are expanded by the first source, if it contains the variable, otherwise by the
+/// second source, see [`Source::contains_variable`]. Only if neither source contains the
+/// variable, expansion fails with an error, which names all consulted sources.
+///
+/// Chains are usually built with [`Source::or`], longer chains nest multiple [`ChainSource`]s,
+/// sources earlier in the chain take precedence.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::{source::Source, DotenvSource, EnvSource, MapSource};
+///
+/// # unsafe { std::env::set_var("LOG_LEVEL", "debug"); }
+/// let mut source = EnvSource::default()
+///     .or(DotenvSource::from_contents("LOG_LEVEL=info\nPORT=3000").unwrap())
+///     .or(MapSource::default().insert("PORT", "8080").insert("HOST", "localhost"));
+///
+/// let mut de = serde_json::Deserializer::from_str(r#"["${LOG_LEVEL}", "${PORT}", "${HOST}"]"#);
+/// let r: (String, u16, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, ("debug".to_owned(), 3000, "localhost".to_owned()));
+/// ```
+#[derive(Debug)]
+pub struct ChainSource<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> ChainSource<A, B> {
+    /// Creates a [`ChainSource`], which prefers `first` over `second`.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Returns the first and the second source.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A, B> ChainSource<A, B>
+where
+    A: Source,
+    B: Source,
+{
+    /// Returns `true` if the variable `v` is expanded by the first source.
+    ///
+    /// Returns an error if neither source contains the variable.
+    fn use_first<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        if self.first.contains_variable(v) {
+            return Ok(true);
+        }
+        if self.second.contains_variable(v) {
+            return Ok(false);
+        }
+
+        Err(E::custom(format!(
+            "got variable `{v}`, but it does not exist in any of the sources: {}",
+            self.name()
+        )))
+    }
+
+    fn use_first_bytes<E>(&mut self, v: &[u8]) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        match std::str::from_utf8(v) {
+            Ok(v) => self.use_first(v),
+            // Only strings can reference variables.
+            Err(_) => Ok(true),
+        }
+    }
+}
+
+impl<A, B> Source for ChainSource<A, B>
+where
+    A: Source,
+    B: Source,
+{
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        match self.use_first(v)? {
+            true => self.first.expand_bool(v),
+            false => self.second.expand_bool(v),
+        }
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        match self.use_first(v)? {
+            true => self.first.expand_i8(v),
+            false => self.second.expand_i8(v),
+        }
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        match self.use_first(v)? {
+            true => self.first.expand_i16(v),
+            false => self.second.expand_i16(v),
+        }
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        match self.use_first(v)? {
+            true => self.first.expand_i32(v),
+            false => self.second.expand_i32(v),
+        }
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        match self.use_first(v)? {
+            true => self.first.expand_i64(v),
+            false => self.second.expand_i64(v),
+        }
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        match self.use_first(v)? {
+            true => self.first.expand_i128(v),
+            false => self.second.expand_i128(v),
+        }
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        match self.use_first(v)? {
+            true => self.first.expand_u8(v),
+            false => self.second.expand_u8(v),
+        }
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        match self.use_first(v)? {
+            true => self.first.expand_u16(v),
+            false => self.second.expand_u16(v),
+        }
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        match self.use_first(v)? {
+            true => self.first.expand_u32(v),
+            false => self.second.expand_u32(v),
+        }
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        match self.use_first(v)? {
+            true => self.first.expand_u64(v),
+            false => self.second.expand_u64(v),
+        }
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        match self.use_first(v)? {
+            true => self.first.expand_u128(v),
+            false => self.second.expand_u128(v),
+        }
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        match self.use_first(v)? {
+            true => self.first.expand_f32(v),
+            false => self.second.expand_f32(v),
+        }
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        match self.use_first(v)? {
+            true => self.first.expand_f64(v),
+            false => self.second.expand_f64(v),
+        }
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        match self.use_first(&v)? {
+            true => self.first.expand_str(v),
+            false => self.second.expand_str(v),
+        }
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        match self.use_first_bytes(&v)? {
+            true => self.first.expand_bytes(v),
+            false => self.second.expand_bytes(v),
+        }
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        match self.use_first(&v)? {
+            true => self.first.expand_any(v),
+            false => self.second.expand_any(v),
+        }
+    }
+
+    fn expand_structured<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        match self.use_first(&v)? {
+            true => self.first.expand_structured(v),
+            false => self.second.expand_structured(v),
+        }
+    }
+
+    fn expand_map<E>(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        // Entries of both sources are merged, entries of the first source take precedence.
+        let first = self.first.expand_map(v)?;
+        let second = self.second.expand_map(v)?;
+        Ok(utils::merge_entries(first, second))
+    }
+
+    fn expands_structured(&self) -> bool {
+        self.first.expands_structured() || self.second.expands_structured()
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        self.first.contains_variable(v) || self.second.contains_variable(v)
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        match (
+            self.first.references_variable(v),
+            self.second.references_variable(v),
+        ) {
+            (Some(true), _) | (_, Some(true)) => Some(true),
+            (Some(false), Some(false)) => Some(false),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> String {
+        format!("{}, {}", self.first.name(), self.second.name())
+    }
+}
@@ -0,0 +1,108 @@
+use std::{borrow::Cow, ffi::OsStr};
+
+use clap::{parser::ValueSource, ArgMatches};
+
+use crate::source::{StringLookup, StringSource};
+
+/// A [`StringLookup`] which uses the values of command line arguments parsed by [`clap`].
+///
+/// Variables are looked up by the id of the argument. With the derive API the id is the name of
+/// the field, e.g. `listen_port` for the argument `--listen-port`. Dashes and underscores are
+/// interchangeable, `${listen_port}` also matches an argument with the id `listen-port`.
+///
+/// Values are the raw strings passed on the command line, they are parsed by the
+/// [`StringSource`] into the type of the configuration field. Arguments which occur multiple
+/// times use their last value. Flags, like `--verbose`, have the value `true` when set.
+///
+/// Only arguments which were passed on the command line, or read from an environment variable
+/// configured with `Arg::env`, are used. Default values of arguments are ignored, unless
+/// enabled with [`Self::with_default_values`], so they don't take precedence over values
+/// configured elsewhere.
+///
+/// Only the arguments of the passed [`ArgMatches`] are used, for a subcommand pass the matches
+/// of the subcommand, e.g. from [`ArgMatches::subcommand`].
+///
+/// Generally used through [`ClapSource`].
+#[derive(Debug, Clone)]
+pub struct ClapLookup {
+    matches: ArgMatches,
+    default_values: bool,
+}
+
+impl ClapLookup {
+    /// Creates a [`ClapLookup`] for the arguments in `matches`.
+    pub fn new(matches: ArgMatches) -> Self {
+        Self {
+            matches,
+            default_values: false,
+        }
+    }
+
+    /// Uses the default values of arguments which were not passed on the command line.
+    pub fn with_default_values(mut self, default_values: bool) -> Self {
+        self.default_values = default_values;
+        self
+    }
+
+    fn raw(&self, v: &str) -> Option<&OsStr> {
+        let alternative = match v.contains('_') {
+            true => v.replace('_', "-"),
+            false => v.replace('-', "_"),
+        };
+
+        // Only query known ids, querying unknown ids panics in debug builds of clap.
+        let known = |name: &str| self.matches.ids().find(|id| id.as_str() == name);
+        let id = known(v).or_else(|| known(&alternative))?.as_str();
+
+        let source = self.matches.value_source(id)?;
+        if source == ValueSource::DefaultValue && !self.default_values {
+            return None;
+        }
+        self.matches.try_get_raw(id).ok()??.next_back()
+    }
+}
+
+impl StringLookup for ClapLookup {
+    fn lookup(&mut self, v: &str) -> Option<Cow<'_, str>> {
+        self.raw(v)?.to_str().map(Cow::Borrowed)
+    }
+
+    fn lookup_bytes(&mut self, v: &str) -> Option<Cow<'_, [u8]>> {
+        self.raw(v)
+            .map(|value| Cow::Borrowed(value.as_encoded_bytes()))
+    }
+}
+
+/// A source which uses the values of command line arguments parsed by [`clap`].
+///
+/// This allows values in a configuration to be provided or overridden on the command line.
+///
+/// See [`ClapLookup`] for how arguments are looked up and the [`crate`] and [`StringSource`]
+/// documentation for more details.
+///
+/// # Examples:
+///
+/// ```
+/// use clap::{Arg, ArgAction, Command};
+/// use serde_vars::source::ClapSource;
+///
+/// let matches = Command::new("server")
+///     .arg(Arg::new("listen_port").long("listen-port"))
+///     .arg(Arg::new("verbose").long("verbose").action(ArgAction::SetTrue))
+///     .get_matches_from(["server", "--listen-port", "8080", "--verbose"]);
+/// let mut source = ClapSource::from_matches(matches);
+///
+/// let mut de = serde_json::Deserializer::from_str(r#"["${listen_port}", "${verbose}"]"#);
+/// let r: (u16, bool) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, (8080, true));
+/// ```
+pub type ClapSource = StringSource<ClapLookup>;
+
+impl ClapSource {
+    /// Creates a [`ClapSource`] for the arguments in `matches`.
+    ///
+    /// See [`ClapLookup::new`].
+    pub fn from_matches(matches: ArgMatches) -> Self {
+        StringSource::new(ClapLookup::new(matches))
+    }
+}
@@ -0,0 +1,431 @@
+use std::borrow::Cow;
+
+use serde::de;
+
+use crate::source::{Any, RoutingSource, Source, Structured, Variable};
+
+/// A [`Source`] which expands every variable using the source registered for its scheme.
+///
+/// Sources are registered for a scheme with [`Self::register`] and every variable must name
+/// the source it uses, e.g. `${vault:db/password}` or `${env:DB_USER}`. The scheme is removed
+/// from the variable before it is passed on to the source (`${db/password}`).
+///
+/// Unlike a [`RoutingSource`], there is no fallback source: variables without a scheme or with
+/// a scheme which is not registered are rejected with an error listing the registered schemes.
+/// Strings which do not reference a variable are used as is.
+///
+/// The registered sources should use the same variable prefix and suffix as the
+/// [`CompositeSource`], by default `${` and `}`.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::{source::CompositeSource, EnvSource, MapSource};
+///
+/// # unsafe { std::env::set_var("DB_USER", "admin"); }
+/// let mut source = CompositeSource::new()
+///     .register("env", EnvSource::default())
+///     .register("vault", MapSource::default().insert("db/password", "hunter2"));
+///
+/// let mut de = serde_json::Deserializer::from_str(r#"["${env:DB_USER}", "${vault:db/password}"]"#);
+/// let r: (String, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, ("admin".to_owned(), "hunter2".to_owned()));
+///
+/// let mut de = serde_json::Deserializer::from_str(r#""${file:/run/secrets/token}""#);
+/// let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+/// assert!(r.is_err());
+/// ```
+#[derive(Debug)]
+pub struct CompositeSource<R = Unregistered> {
+    routes: R,
+    schemes: Vec<String>,
+    variable: Variable,
+}
+
+/// The [`Source`] of a [`CompositeSource`] for strings, which do not reference a variable.
+#[derive(Debug)]
+pub struct Unregistered {
+    variable: Variable,
+}
+
+impl CompositeSource {
+    /// Creates a [`CompositeSource`] without any registered sources.
+    pub fn new() -> Self {
+        Self {
+            routes: Unregistered {
+                variable: Variable::default(),
+            },
+            schemes: Vec::new(),
+            variable: Variable::default(),
+        }
+    }
+
+    /// Changes the variable prefix.
+    ///
+    /// The delimiters can only be changed before sources are registered.
+    pub fn with_variable_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.variable.prefix = prefix.into();
+        self.routes.variable = self.variable.clone();
+        self
+    }
+
+    /// Changes the variable suffix.
+    ///
+    /// The delimiters can only be changed before sources are registered.
+    pub fn with_variable_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.variable.suffix = suffix.into();
+        self.routes.variable = self.variable.clone();
+        self
+    }
+}
+
+impl Default for CompositeSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> CompositeSource<R> {
+    /// Registers `source` for variables with the scheme `scheme`.
+    ///
+    /// Registering a scheme again replaces the previously registered source.
+    pub fn register<S>(
+        mut self,
+        scheme: impl Into<String>,
+        source: S,
+    ) -> CompositeSource<RoutingSource<S, R>> {
+        let scheme = scheme.into();
+        if !self.schemes.contains(&scheme) {
+            self.schemes.push(scheme.clone());
+        }
+
+        CompositeSource {
+            routes: RoutingSource::new(scheme, source, self.routes)
+                .with_variable_prefix(self.variable.prefix.clone())
+                .with_variable_suffix(self.variable.suffix.clone()),
+            schemes: self.schemes,
+            variable: self.variable,
+        }
+    }
+
+    /// Returns the registered schemes, in the order they were registered.
+    pub fn schemes(&self) -> impl Iterator<Item = &str> {
+        self.schemes.iter().map(String::as_str)
+    }
+
+    /// Returns an error, if `v` is a variable without a registered scheme.
+    fn check<E>(&self, v: &str) -> Result<(), E>
+    where
+        E: de::Error,
+    {
+        let Some(var) = self.variable.parse_str(v) else {
+            return Ok(());
+        };
+
+        let reason = match var.split_once(':') {
+            Some((scheme, _)) if self.schemes.iter().any(|s| s == scheme) => return Ok(()),
+            _ if self.schemes.is_empty() => {
+                return Err(E::custom(format!(
+                    "got variable `{v}`, but no schemes are registered"
+                )));
+            }
+            Some((scheme, _)) => format!("the scheme `{scheme}` is not registered"),
+            None => "it has no scheme".to_owned(),
+        };
+
+        let schemes = self
+            .schemes
+            .iter()
+            .map(|scheme| format!("`{scheme}`"))
+            .collect::<Vec<_>>();
+        Err(E::custom(format!(
+            "got variable `{v}`, but {reason}, expected one of {}",
+            schemes.join(", ")
+        )))
+    }
+
+    fn check_bytes<E>(&self, v: &[u8]) -> Result<(), E>
+    where
+        E: de::Error,
+    {
+        match std::str::from_utf8(v) {
+            Ok(v) => self.check(v),
+            // Only strings can reference variables.
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+impl<R> Source for CompositeSource<R>
+where
+    R: Source,
+{
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        self.check(v)?;
+        self.routes.expand_bool(v)
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        self.check(v)?;
+        self.routes.expand_i8(v)
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        self.check(v)?;
+        self.routes.expand_i16(v)
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        self.check(v)?;
+        self.routes.expand_i32(v)
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        self.check(v)?;
+        self.routes.expand_i64(v)
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        self.check(v)?;
+        self.routes.expand_i128(v)
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        self.check(v)?;
+        self.routes.expand_u8(v)
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        self.check(v)?;
+        self.routes.expand_u16(v)
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        self.check(v)?;
+        self.routes.expand_u32(v)
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        self.check(v)?;
+        self.routes.expand_u64(v)
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        self.check(v)?;
+        self.routes.expand_u128(v)
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        self.check(v)?;
+        self.routes.expand_f32(v)
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        self.check(v)?;
+        self.routes.expand_f64(v)
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        self.check(&v)?;
+        self.routes.expand_str(v)
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        self.check_bytes(&v)?;
+        self.routes.expand_bytes(v)
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        self.check(&v)?;
+        self.routes.expand_any(v)
+    }
+
+    fn expand_structured<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        self.check(&v)?;
+        self.routes.expand_structured(v)
+    }
+
+    fn expand_map<E>(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        self.check(v)?;
+        self.routes.expand_map(v)
+    }
+
+    fn expands_structured(&self) -> bool {
+        self.routes.expands_structured()
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        self.check::<de::value::Error>(v).is_err() || self.routes.contains_variable(v)
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        Some(self.variable.parse_str(v).is_some())
+    }
+}
+
+impl Unregistered {
+    fn expected_variable<E>(&self, v: &str) -> E
+    where
+        E: de::Error,
+    {
+        let var = self.variable.fmt("<scheme>:<var>");
+        E::invalid_value(
+            de::Unexpected::Str(v),
+            &format!("a variable `{var}`").as_str(),
+        )
+    }
+}
+
+impl Source for Unregistered {
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        Err(self.expected_variable(v))
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        Err(self.expected_variable(v))
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        Err(self.expected_variable(v))
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        Err(self.expected_variable(v))
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        Err(self.expected_variable(v))
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        Err(self.expected_variable(v))
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        Err(self.expected_variable(v))
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        Err(self.expected_variable(v))
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        Err(self.expected_variable(v))
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        Err(self.expected_variable(v))
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        Err(self.expected_variable(v))
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        Ok(v)
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        Ok(v)
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        Ok(Any::Str(v))
+    }
+}
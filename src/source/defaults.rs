@@ -0,0 +1,300 @@
+use std::borrow::Cow;
+
+use serde::de;
+
+use crate::source::{utils, Any, MapSource, Source, Structured};
+
+/// A [`Source`] which expands variables missing in another source to default values.
+///
+/// Variables are expanded by the wrapped source, unless it does not contain the variable
+/// (see [`Source::contains_variable`]) and a default value exists. Default values are converted
+/// to the requested types like a [`MapSource`] converts them.
+///
+/// Unlike defaults in the configuration itself, defaults of a [`DefaultsSource`] are provided
+/// by the application, which keeps them out of configuration files.
+///
+/// Usually created with [`Source::with_defaults`].
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::{source::Source, EnvSource};
+///
+/// let mut source = EnvSource::default().with_defaults([("MY_APP_PORT", "8080")]);
+///
+/// let mut de = serde_json::Deserializer::from_str(r#""${MY_APP_PORT}""#);
+/// let r: u16 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, 8080);
+/// ```
+#[derive(Debug)]
+pub struct DefaultsSource<S> {
+    source: S,
+    defaults: MapSource,
+}
+
+impl<S> DefaultsSource<S> {
+    /// Creates a [`DefaultsSource`], which expands variables missing in `source` to `defaults`.
+    pub fn new<I, K, V>(source: S, defaults: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let defaults = defaults
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect();
+
+        Self {
+            source,
+            defaults: MapSource::new(defaults),
+        }
+    }
+
+    /// Changes the variable prefix of the default values.
+    ///
+    /// Should match the variable prefix of the wrapped source.
+    pub fn with_variable_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.defaults = self.defaults.with_variable_prefix(prefix);
+        self
+    }
+
+    /// Changes the variable suffix of the default values.
+    ///
+    /// Should match the variable suffix of the wrapped source.
+    pub fn with_variable_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.defaults = self.defaults.with_variable_suffix(suffix);
+        self
+    }
+
+    /// Returns the wrapped source.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S> DefaultsSource<S>
+where
+    S: Source,
+{
+    /// Returns `true` if the variable `v` is expanded to its default value.
+    fn use_default(&mut self, v: &str) -> bool {
+        !self.source.contains_variable(v) && self.defaults.contains_variable(v)
+    }
+
+    fn use_default_bytes(&mut self, v: &[u8]) -> bool {
+        match std::str::from_utf8(v) {
+            Ok(v) => self.use_default(v),
+            // Only strings can reference variables.
+            Err(_) => false,
+        }
+    }
+}
+
+impl<S> Source for DefaultsSource<S>
+where
+    S: Source,
+{
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        match self.use_default(v) {
+            true => self.defaults.expand_bool(v),
+            false => self.source.expand_bool(v),
+        }
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        match self.use_default(v) {
+            true => self.defaults.expand_i8(v),
+            false => self.source.expand_i8(v),
+        }
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        match self.use_default(v) {
+            true => self.defaults.expand_i16(v),
+            false => self.source.expand_i16(v),
+        }
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        match self.use_default(v) {
+            true => self.defaults.expand_i32(v),
+            false => self.source.expand_i32(v),
+        }
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        match self.use_default(v) {
+            true => self.defaults.expand_i64(v),
+            false => self.source.expand_i64(v),
+        }
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        match self.use_default(v) {
+            true => self.defaults.expand_i128(v),
+            false => self.source.expand_i128(v),
+        }
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        match self.use_default(v) {
+            true => self.defaults.expand_u8(v),
+            false => self.source.expand_u8(v),
+        }
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        match self.use_default(v) {
+            true => self.defaults.expand_u16(v),
+            false => self.source.expand_u16(v),
+        }
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        match self.use_default(v) {
+            true => self.defaults.expand_u32(v),
+            false => self.source.expand_u32(v),
+        }
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        match self.use_default(v) {
+            true => self.defaults.expand_u64(v),
+            false => self.source.expand_u64(v),
+        }
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        match self.use_default(v) {
+            true => self.defaults.expand_u128(v),
+            false => self.source.expand_u128(v),
+        }
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        match self.use_default(v) {
+            true => self.defaults.expand_f32(v),
+            false => self.source.expand_f32(v),
+        }
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        match self.use_default(v) {
+            true => self.defaults.expand_f64(v),
+            false => self.source.expand_f64(v),
+        }
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        match self.use_default(&v) {
+            true => self.defaults.expand_str(v),
+            false => self.source.expand_str(v),
+        }
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        match self.use_default_bytes(&v) {
+            true => self.defaults.expand_bytes(v),
+            false => self.source.expand_bytes(v),
+        }
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        match self.use_default(&v) {
+            true => self.defaults.expand_any(v),
+            false => self.source.expand_any(v),
+        }
+    }
+
+    fn expand_structured<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        match self.use_default(&v) {
+            true => self.defaults.expand_structured(v),
+            false => self.source.expand_structured(v),
+        }
+    }
+
+    fn expand_map<E>(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        // Entries of the source are merged with the defaults, entries of the source take precedence.
+        let entries = self.source.expand_map(v)?;
+        let defaults = self.defaults.expand_map(v)?;
+        Ok(utils::merge_entries(entries, defaults))
+    }
+
+    fn expands_structured(&self) -> bool {
+        self.source.expands_structured()
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        self.source.contains_variable(v) || self.defaults.contains_variable(v)
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        match (
+            self.source.references_variable(v),
+            self.defaults.references_variable(v),
+        ) {
+            (Some(true), _) | (_, Some(true)) => Some(true),
+            (Some(false), Some(false)) => Some(false),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> String {
+        self.source.name()
+    }
+}
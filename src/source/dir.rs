@@ -0,0 +1,318 @@
+use std::{borrow::Cow, path::PathBuf};
+
+use serde::de;
+
+use crate::source::{Any, FileSource, FileSystem, SecretDetector, Source, StdFileSystem, Variable};
+
+/// A [`Source`] which provides values from files in a single directory.
+///
+/// A variable names a file in the directory, e.g. `${db-password}` reads the file
+/// `<dir>/db-password`. This is the common pattern for secrets mounted as files,
+/// like Kubernetes secret volumes or Docker secrets in `/run/secrets`.
+///
+/// Unlike a general [`FileSource`], a [`DirSource`] only reads files directly in its directory:
+///
+/// - Variables containing path separators, `..` or absolute paths are rejected.
+/// - Symbolic links must resolve to a file within the directory, which allows
+///   the links created by Kubernetes for secret volumes.
+/// - A single trailing newline is removed from file contents,
+///   see [`Self::with_trim_trailing_newline`].
+///
+/// Values are parsed like with a [`FileSource`].
+///
+/// # Examples:
+///
+/// ```
+/// # let temp = tempfile::tempdir().unwrap();
+/// # std::fs::write(temp.path().join("db-password"), "hunter2\n").unwrap();
+/// #
+/// use serde_vars::DirSource;
+///
+/// let mut source = DirSource::new("/run/secrets");
+/// # let mut source = DirSource::new(temp.path());
+///
+/// let mut de = serde_json::Deserializer::from_str(r#""${db-password}""#);
+/// let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, "hunter2");
+///
+/// let mut de = serde_json::Deserializer::from_str(r#""${../etc/passwd}""#);
+/// let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+/// assert!(r.is_err());
+/// ```
+pub struct DirSource<F = StdFileSystem>(FileSource<F>);
+
+impl DirSource {
+    /// Creates a [`DirSource`] reading files from the directory `dir`.
+    ///
+    /// Like with a [`FileSource`], the source uses `${` and `}` as variable specifiers.
+    pub fn new<P>(dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self(
+            FileSource::new()
+                .with_base_path(dir)
+                .with_confine_to_base_path(true)
+                .with_file_names_only(true)
+                .with_trim_trailing_newline(true),
+        )
+    }
+
+    /// Creates a [`DirSource`] for the credentials of a systemd service.
+    ///
+    /// Credentials passed to a service with `LoadCredential=`, `SetCredential=` or similar
+    /// settings are available as files in the directory named by the `CREDENTIALS_DIRECTORY`
+    /// environment variable, e.g. `${db-password}` reads the credential `db-password`.
+    ///
+    /// Fails with [`std::io::ErrorKind::NotFound`] if `CREDENTIALS_DIRECTORY` is not set,
+    /// e.g. because the service has no credentials.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # let temp = tempfile::tempdir().unwrap();
+    /// # std::fs::write(temp.path().join("db-password"), "hunter2\n").unwrap();
+    /// # unsafe { std::env::set_var("CREDENTIALS_DIRECTORY", temp.path()); }
+    /// #
+    /// use serde_vars::DirSource;
+    ///
+    /// let mut source = DirSource::systemd_credentials().unwrap();
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${db-password}""#);
+    /// let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, "hunter2");
+    /// ```
+    pub fn systemd_credentials() -> std::io::Result<Self> {
+        match std::env::var_os("CREDENTIALS_DIRECTORY") {
+            Some(dir) if !dir.is_empty() => Ok(Self::new(dir)),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "no systemd credentials, `CREDENTIALS_DIRECTORY` is not set",
+            )),
+        }
+    }
+}
+
+impl<F> DirSource<F> {
+    /// Configures the [`FileSystem`] to read files from.
+    ///
+    /// See [`FileSource::with_file_system`].
+    pub fn with_file_system<G>(self, fs: G) -> DirSource<G> {
+        DirSource(self.0.with_file_system(fs))
+    }
+
+    /// Discards cached file contents when files change.
+    ///
+    /// See [`FileSource::with_cache_invalidation`].
+    #[cfg(feature = "notify")]
+    pub fn with_cache_invalidation(self, enabled: bool) -> Self {
+        Self(self.0.with_cache_invalidation(enabled))
+    }
+
+    /// Adds a fallback directory, used for files which do not exist in the primary directory.
+    ///
+    /// See [`FileSource::with_fallback_base_path`].
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use serde_vars::DirSource;
+    ///
+    /// let source = DirSource::new("/run/secrets").with_fallback_dir("./dev-secrets");
+    /// ```
+    pub fn with_fallback_dir<P>(self, dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self(self.0.with_fallback_base_path(dir))
+    }
+
+    /// Changes the variable prefix.
+    pub fn with_variable_prefix(self, prefix: impl Into<String>) -> Self {
+        Self(self.0.with_variable_prefix(prefix))
+    }
+
+    /// Changes the variable suffix.
+    pub fn with_variable_suffix(self, suffix: impl Into<String>) -> Self {
+        Self(self.0.with_variable_suffix(suffix))
+    }
+
+    /// Enables or disables removal of a single trailing newline from file contents.
+    ///
+    /// Enabled by default. See [`FileSource::with_trim_trailing_newline`].
+    pub fn with_trim_trailing_newline(self, trim_trailing_newline: bool) -> Self {
+        Self(self.0.with_trim_trailing_newline(trim_trailing_newline))
+    }
+
+    /// Limits the length of file contents.
+    ///
+    /// See [`FileSource::with_max_value_length`].
+    pub fn with_max_value_length(self, max: usize) -> Self {
+        Self(self.0.with_max_value_length(max))
+    }
+
+    /// Rejects files which are accessible by other users or not owned by the current user.
+    ///
+    /// See [`FileSource::with_secure_permissions`].
+    pub fn with_secure_permissions(self, secure_permissions: bool) -> Self {
+        Self(self.0.with_secure_permissions(secure_permissions))
+    }
+
+    /// Enables or disables caching of file contents.
+    ///
+    /// See [`FileSource::with_cache`].
+    pub fn with_cache(self, enabled: bool) -> Self {
+        Self(self.0.with_cache(enabled))
+    }
+
+    /// Configures a [`SecretDetector`].
+    ///
+    /// See [`FileSource::with_secret_detector`].
+    pub fn with_secret_detector(self, detector: SecretDetector) -> Self {
+        Self(self.0.with_secret_detector(detector))
+    }
+
+    /// Rejects strings which resemble variables, but are not well-formed variables.
+    ///
+    /// See [`FileSource::with_strict_variables`].
+    pub fn with_strict_variables(self, strict: bool) -> Self {
+        Self(self.0.with_strict_variables(strict))
+    }
+
+    /// Returns the [`Variable`] grammar used by this source.
+    pub fn variable(&self) -> &Variable {
+        self.0.variable()
+    }
+
+    /// Discards all cached file contents.
+    ///
+    /// See [`FileSource::clear_cache`].
+    pub fn clear_cache(&mut self) {
+        self.0.clear_cache();
+    }
+}
+
+impl<F> Source for DirSource<F>
+where
+    F: FileSystem,
+{
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        self.0.expand_bool(v)
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        self.0.expand_i8(v)
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        self.0.expand_i16(v)
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        self.0.expand_i32(v)
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        self.0.expand_i64(v)
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        self.0.expand_i128(v)
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        self.0.expand_u8(v)
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        self.0.expand_u16(v)
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        self.0.expand_u32(v)
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        self.0.expand_u64(v)
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        self.0.expand_u128(v)
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        self.0.expand_f32(v)
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        self.0.expand_f64(v)
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        self.0.expand_str(v)
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        self.0.expand_bytes(v)
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        self.0.expand_any(v)
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        self.0.contains_variable(v)
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        self.0.references_variable(v)
+    }
+}
@@ -0,0 +1,734 @@
+use std::{borrow::Cow, fmt, marker::PhantomData, path::Path};
+
+use serde::de;
+
+use crate::source::{secret, utils, Any, SecretDetector, Source, Variable};
+
+/// The format of the document of a [`DocumentSource`].
+pub trait DocumentFormat {
+    /// Parses a document.
+    fn parse(contents: &[u8]) -> Result<serde_json::Value, String>;
+
+    /// Selects the value addressed by the variable name `key` in the `document`.
+    fn select<'a>(document: &'a serde_json::Value, key: &str) -> Option<&'a serde_json::Value>;
+}
+
+/// JSON documents, values are selected by [JSON Pointers](https://www.rfc-editor.org/rfc/rfc6901),
+/// e.g. `${/database/password}`.
+///
+/// Used through [`JsonSource`].
+#[derive(Debug, Clone, Copy)]
+pub struct Json;
+
+impl DocumentFormat for Json {
+    fn parse(contents: &[u8]) -> Result<serde_json::Value, String> {
+        serde_json::from_slice(contents).map_err(|err| format!("invalid JSON: {err}"))
+    }
+
+    fn select<'a>(document: &'a serde_json::Value, key: &str) -> Option<&'a serde_json::Value> {
+        document.pointer(key)
+    }
+}
+
+/// In-memory values, selected by dotted keys, e.g. `${database.password}`.
+///
+/// Documents are parsed as JSON. Used through [`ValueSource`].
+#[derive(Debug, Clone, Copy)]
+pub struct KeyPath;
+
+impl DocumentFormat for KeyPath {
+    fn parse(contents: &[u8]) -> Result<serde_json::Value, String> {
+        Json::parse(contents)
+    }
+
+    fn select<'a>(document: &'a serde_json::Value, key: &str) -> Option<&'a serde_json::Value> {
+        utils::select(document, key)
+    }
+}
+
+/// TOML documents, values are selected by dotted keys, e.g. `${database.password}`.
+///
+/// Used through [`TomlSource`].
+#[cfg(feature = "toml")]
+#[derive(Debug, Clone, Copy)]
+pub struct Toml;
+
+#[cfg(feature = "toml")]
+impl DocumentFormat for Toml {
+    fn parse(contents: &[u8]) -> Result<serde_json::Value, String> {
+        std::str::from_utf8(contents)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| toml::from_str(contents).map_err(|err| err.to_string()))
+            .map_err(|err| format!("invalid TOML: {err}"))
+    }
+
+    fn select<'a>(document: &'a serde_json::Value, key: &str) -> Option<&'a serde_json::Value> {
+        utils::select(document, key)
+    }
+}
+
+/// YAML documents, values are selected by dotted keys, e.g. `${database.credentials.password}`.
+///
+/// Used through [`YamlSource`].
+#[cfg(feature = "yaml")]
+#[derive(Debug, Clone, Copy)]
+pub struct Yaml;
+
+#[cfg(feature = "yaml")]
+impl DocumentFormat for Yaml {
+    fn parse(contents: &[u8]) -> Result<serde_json::Value, String> {
+        serde_yaml::from_slice(contents).map_err(|err| format!("invalid YAML: {err}"))
+    }
+
+    fn select<'a>(document: &'a serde_json::Value, key: &str) -> Option<&'a serde_json::Value> {
+        utils::select(document, key)
+    }
+}
+
+/// Property lists, values are selected by dotted keys, e.g. `${Database.Password}`.
+///
+/// Supports XML, binary and ASCII property lists. Data values are passed on as base64
+/// encoded strings, dates as strings in the ISO 8601 format of XML property lists.
+///
+/// Used through [`PlistSource`].
+#[cfg(feature = "plist")]
+#[derive(Debug, Clone, Copy)]
+pub struct Plist;
+
+#[cfg(feature = "plist")]
+impl DocumentFormat for Plist {
+    fn parse(contents: &[u8]) -> Result<serde_json::Value, String> {
+        plist::Value::from_reader(std::io::Cursor::new(contents))
+            .map(plist_to_json)
+            .map_err(|err| format!("invalid property list: {err}"))
+    }
+
+    fn select<'a>(document: &'a serde_json::Value, key: &str) -> Option<&'a serde_json::Value> {
+        utils::select(document, key)
+    }
+}
+
+/// A [`Source`] which provides values from a structured document, like a JSON, YAML or TOML file.
+///
+/// Variables select a single value of the document, the syntax of the selection depends on the
+/// [`DocumentFormat`]. Values keep the type of the document, numbers and booleans are passed on
+/// as numbers and booleans, strings as strings. Unlike with a [`StringSource`](crate::StringSource),
+/// strings are never parsed into other types, a string `"5432"` cannot be used for a number.
+///
+/// Only scalar values can be selected, tables and arrays are rejected.
+///
+/// Generally used through one of the format specific aliases, like [`JsonSource`].
+pub struct DocumentSource<F> {
+    document: serde_json::Value,
+    variable: Variable,
+    secrets: Option<SecretDetector>,
+    sensitive: bool,
+    strict: bool,
+    format: PhantomData<F>,
+}
+
+/// A source which uses values from a JSON document.
+///
+/// Variables are [JSON Pointers](https://www.rfc-editor.org/rfc/rfc6901), e.g.
+/// `${/database/password}`, array elements are selected by their index, e.g. `${/servers/0/host}`.
+/// The characters `~` and `/` in keys are escaped as `~0` and `~1`.
+///
+/// See [`DocumentSource`] for more details.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::source::JsonSource;
+///
+/// let document = serde_json::json!({
+///     "database": {
+///         "password": "hunter2",
+///         "port": 5432,
+///         "tls": true,
+///     }
+/// });
+/// let mut source = JsonSource::new(document);
+///
+/// let mut de = serde_json::Deserializer::from_str(
+///     r#"["${/database/password}", "${/database/port}", "${/database/tls}"]"#,
+/// );
+/// let r: (String, u16, bool) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, ("hunter2".to_owned(), 5432, true));
+/// ```
+pub type JsonSource = DocumentSource<Json>;
+
+/// A source which uses values from an in-memory [`serde_json::Value`].
+///
+/// Variables are dotted keys, e.g. `${database.password}`, array elements are selected
+/// by their index, e.g. `${servers.0.host}`. Unlike with a [`MapSource`](crate::MapSource),
+/// values keep their types and are not parsed from strings.
+///
+/// See [`DocumentSource`] for more details.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::source::ValueSource;
+///
+/// let mut source = ValueSource::new(serde_json::json!({
+///     "database": {
+///         "password": "hunter2",
+///         "port": 5432,
+///     },
+///     "servers": [{"host": "localhost"}],
+/// }));
+///
+/// let mut de = serde_json::Deserializer::from_str(
+///     r#"["${database.password}", "${database.port}", "${servers.0.host}"]"#,
+/// );
+/// let r: (String, u16, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, ("hunter2".to_owned(), 5432, "localhost".to_owned()));
+/// ```
+pub type ValueSource = DocumentSource<KeyPath>;
+
+impl ValueSource {
+    /// Creates a [`ValueSource`] from any serializable value, e.g. a map or a struct.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use serde_vars::source::ValueSource;
+    ///
+    /// let ports = BTreeMap::from([("http", 80), ("https", 443)]);
+    /// let mut source = ValueSource::from_serialize(&ports).unwrap();
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${https}""#);
+    /// let r: u16 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, 443);
+    /// ```
+    pub fn from_serialize<T>(value: &T) -> Result<Self, serde_json::Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        serde_json::to_value(value).map(Self::new)
+    }
+}
+
+/// A source which uses values from a TOML document.
+///
+/// Variables are dotted keys, e.g. `${database.password}`, array elements are selected
+/// by their index, e.g. `${servers.0.host}`.
+///
+/// See [`DocumentSource`] for more details.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::source::TomlSource;
+///
+/// let document = r#"
+///     [database]
+///     password = "hunter2"
+///     port = 5432
+///     tls = true
+/// "#;
+/// let mut source = TomlSource::new(toml::from_str(document).unwrap());
+///
+/// let mut de = serde_json::Deserializer::from_str(
+///     r#"["${database.password}", "${database.port}", "${database.tls}"]"#,
+/// );
+/// let r: (String, u16, bool) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, ("hunter2".to_owned(), 5432, true));
+/// ```
+#[cfg(feature = "toml")]
+pub type TomlSource = DocumentSource<Toml>;
+
+/// A source which uses values from a YAML document.
+///
+/// Variables are dotted keys, e.g. `${database.credentials.password}`, sequence elements are
+/// selected by their index, e.g. `${servers.0.host}`.
+///
+/// See [`DocumentSource`] for more details.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::source::YamlSource;
+///
+/// let document = r#"
+/// database:
+///   credentials:
+///     password: hunter2
+///   port: 5432
+///   tls: true
+/// "#;
+/// let mut source = YamlSource::new(serde_yaml::from_str(document).unwrap());
+///
+/// let mut de = serde_json::Deserializer::from_str(
+///     r#"["${database.credentials.password}", "${database.port}", "${database.tls}"]"#,
+/// );
+/// let r: (String, u16, bool) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, ("hunter2".to_owned(), 5432, true));
+/// ```
+#[cfg(feature = "yaml")]
+pub type YamlSource = DocumentSource<Yaml>;
+
+/// A source which uses values from a property list.
+///
+/// Variables are dotted keys, e.g. `${Database.Password}`, array elements are selected by their
+/// index, e.g. `${Servers.0.Host}`.
+///
+/// On macOS, [`PlistSource::from_domain`] reads the preferences of a `defaults` domain,
+/// including values enforced by configuration profiles.
+///
+/// See [`DocumentSource`] for more details.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::source::PlistSource;
+///
+/// # let temp = tempfile::tempdir().unwrap();
+/// # let path = temp.path().join("com.example.app.plist");
+/// # std::fs::write(&path, r#"<?xml version="1.0" encoding="UTF-8"?>
+/// # <plist version="1.0"><dict>
+/// #   <key>Database</key>
+/// #   <dict><key>Password</key><string>hunter2</string><key>Port</key><integer>5432</integer></dict>
+/// # </dict></plist>"#).unwrap();
+/// let mut source = PlistSource::from_path(path).unwrap();
+///
+/// let mut de =
+///     serde_json::Deserializer::from_str(r#"["${Database.Password}", "${Database.Port}"]"#);
+/// let r: (String, u16) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, ("hunter2".to_owned(), 5432));
+/// ```
+#[cfg(feature = "plist")]
+pub type PlistSource = DocumentSource<Plist>;
+
+#[cfg(feature = "plist")]
+impl PlistSource {
+    /// Creates a [`PlistSource`] from the preferences of the macOS `defaults` domain `domain`,
+    /// e.g. `com.example.app`.
+    ///
+    /// Reads the property lists of the domain in the following order, values of earlier
+    /// property lists take precedence:
+    ///
+    /// 1. Managed preferences of the user, `/Library/Managed Preferences/<user>/<domain>.plist`.
+    /// 2. Managed preferences, `/Library/Managed Preferences/<domain>.plist`.
+    /// 3. Preferences of the user, `~/Library/Preferences/<domain>.plist`.
+    /// 4. Preferences of the system, `/Library/Preferences/<domain>.plist`.
+    ///
+    /// Managed preferences are installed by configuration profiles, e.g. through an MDM.
+    /// Only the top level keys of the property lists are merged.
+    ///
+    /// Preferences which were just changed, e.g. with `defaults write`, may not yet be written
+    /// to the property list by the preferences daemon.
+    ///
+    /// Fails with [`std::io::ErrorKind::NotFound`] if none of the property lists exist.
+    pub fn from_domain(domain: &str) -> std::io::Result<Self> {
+        let file = format!("{domain}.plist");
+        let managed = Path::new("/Library/Managed Preferences");
+
+        let mut paths = Vec::new();
+        if let Some(user) = std::env::var_os("USER") {
+            paths.push(managed.join(user).join(&file));
+        }
+        paths.push(managed.join(&file));
+        if let Some(home) = std::env::var_os("HOME") {
+            paths.push(Path::new(&home).join("Library/Preferences").join(&file));
+        }
+        paths.push(Path::new("/Library/Preferences").join(&file));
+
+        let mut document = serde_json::Map::new();
+        let mut found = false;
+        for path in paths.iter().rev() {
+            let contents = match std::fs::read(path) {
+                Ok(contents) => contents,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            };
+            let preferences = Plist::parse(&contents)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+            if let serde_json::Value::Object(preferences) = preferences {
+                document.extend(preferences);
+            }
+            found = true;
+        }
+
+        match found {
+            true => Ok(Self::new(serde_json::Value::Object(document))),
+            false => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no preferences found for the domain `{domain}`"),
+            )),
+        }
+    }
+}
+
+impl<F> DocumentSource<F> {
+    /// Creates a [`DocumentSource`] from a parsed document.
+    ///
+    /// By default the created source uses `${` and `}` as variable specifiers.
+    pub fn new(document: serde_json::Value) -> Self {
+        Self {
+            document,
+            variable: Default::default(),
+            secrets: None,
+            sensitive: false,
+            strict: false,
+            format: PhantomData,
+        }
+    }
+
+    /// Changes the variable prefix.
+    pub fn with_variable_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.variable.prefix = prefix.into();
+        self
+    }
+
+    /// Changes the variable suffix.
+    pub fn with_variable_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.variable.suffix = suffix.into();
+        self
+    }
+
+    /// Configures a [`SecretDetector`].
+    ///
+    /// Values detected as secrets are redacted from error messages of the source.
+    pub fn with_secret_detector(mut self, detector: SecretDetector) -> Self {
+        self.secrets = Some(detector);
+        self
+    }
+
+    /// Treats all values as secrets.
+    ///
+    /// When enabled, values are redacted from error messages, regardless of their contents.
+    ///
+    /// Only errors created by the source itself are redacted, errors raised by the deserialized
+    /// type, for example an unknown enum variant or a string which is not a single `char`,
+    /// may still include the value.
+    pub fn with_sensitive(mut self, sensitive: bool) -> Self {
+        self.sensitive = sensitive;
+        self
+    }
+
+    /// Rejects strings which resemble variables, but are not well-formed variables.
+    ///
+    /// See [`StringSource::with_strict_variables`](crate::StringSource::with_strict_variables).
+    pub fn with_strict_variables(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Returns the [`Variable`] grammar used by this source.
+    pub fn variable(&self) -> &Variable {
+        &self.variable
+    }
+}
+
+impl<F> fmt::Debug for DocumentSource<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The document contains the values, they are redacted like in error messages.
+        let redact = self.sensitive || self.secrets.is_some();
+
+        f.debug_struct("DocumentSource")
+            .field("document", &secret::redact_debug(redact, &self.document))
+            .field("variable", &self.variable)
+            .field("secrets", &self.secrets)
+            .field("sensitive", &self.sensitive)
+            .field("strict", &self.strict)
+            .field("format", &self.format)
+            .finish()
+    }
+}
+
+impl<F> DocumentSource<F>
+where
+    F: DocumentFormat,
+{
+    /// Creates a [`DocumentSource`] from the document at `path`.
+    ///
+    /// Documents which cannot be parsed are reported as [`std::io::ErrorKind::InvalidData`].
+    pub fn from_path(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let contents = std::fs::read(path)?;
+        F::parse(&contents)
+            .map(Self::new)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+
+    /// Returns the value selected by the variable `var`.
+    fn value<E>(&self, var: &str) -> Result<&serde_json::Value, E>
+    where
+        E: de::Error,
+    {
+        F::select(&self.document, var).ok_or_else(|| {
+            let var = self.variable.fmt(var);
+            E::custom(format!("got variable `{var}`, but it does not exist"))
+        })
+    }
+
+    fn typed<V, E>(&self, v: &str, expected: &str) -> Result<V, E>
+    where
+        V: de::DeserializeOwned,
+        E: de::Error,
+    {
+        let Some(var) = self.variable.parse_str(v) else {
+            let var = self.variable.fmt("<key>");
+            return Err(E::invalid_value(
+                de::Unexpected::Str(v),
+                &format!("{expected} or a variable `{var}`").as_str(),
+            ));
+        };
+
+        let value = self.value(var)?;
+        V::deserialize(value)
+            .map_err(|_: serde_json::Error| self.mismatched_type(var, value, expected))
+    }
+
+    fn mismatched_type<E>(&self, var: &str, value: &serde_json::Value, expected: &str) -> E
+    where
+        E: de::Error,
+    {
+        let is_secret = self.sensitive
+            || match value {
+                serde_json::Value::String(value) => {
+                    self.secrets.as_ref().is_some_and(|s| s.is_secret(value))
+                }
+                _ => false,
+            };
+        let unexpected = secret::redact(is_secret, unexpected(value));
+
+        let var = self.variable.fmt(var);
+        E::invalid_value(
+            unexpected,
+            &format!("variable `{var}` to be {expected}").as_str(),
+        )
+    }
+
+    fn check_malformed<E>(&self, v: &str) -> Result<(), E>
+    where
+        E: de::Error,
+    {
+        match self.strict && self.variable.is_malformed(v) {
+            true => Err(utils::malformed_variable(
+                &self.variable,
+                de::Unexpected::Str(v),
+            )),
+            false => Ok(()),
+        }
+    }
+}
+
+impl<F> Source for DocumentSource<F>
+where
+    F: DocumentFormat,
+{
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        self.typed(v, "a boolean")
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        self.typed(v, "a signed integer (i8)")
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        self.typed(v, "a signed integer (i16)")
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        self.typed(v, "a signed integer (i32)")
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        self.typed(v, "a signed integer (i64)")
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        self.typed(v, "a signed integer (i128)")
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        self.typed(v, "an unsigned integer (u8)")
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        self.typed(v, "an unsigned integer (u16)")
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        self.typed(v, "an unsigned integer (u32)")
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        self.typed(v, "an unsigned integer (u64)")
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        self.typed(v, "an unsigned integer (u128)")
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        self.typed(v, "a floating point")
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        self.typed(v, "a floating point")
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        let Some(var) = self.variable.parse_str(&v) else {
+            self.check_malformed(&v)?;
+            return Ok(v);
+        };
+
+        match self.value(var)? {
+            serde_json::Value::String(value) => Ok(Cow::Owned(value.clone())),
+            value => Err(self.mismatched_type(var, value, "a string")),
+        }
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        let Some(var) = self
+            .variable
+            .parse_bytes(&v)
+            .and_then(|var| std::str::from_utf8(var).ok())
+        else {
+            if self.strict && self.variable.is_malformed_bytes(&v) {
+                return Err(utils::malformed_variable(
+                    &self.variable,
+                    de::Unexpected::Bytes(&v),
+                ));
+            }
+            return Ok(v);
+        };
+
+        match self.value(var)? {
+            serde_json::Value::String(value) => Ok(Cow::Owned(value.clone().into_bytes())),
+            value => Err(self.mismatched_type(var, value, "a string")),
+        }
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        let Some(var) = self.variable.parse_str(&v) else {
+            self.check_malformed(&v)?;
+            return Ok(Any::Str(v));
+        };
+
+        let value = self.value(var)?;
+        let any = match value {
+            serde_json::Value::Bool(value) => Any::Bool(*value),
+            serde_json::Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+                (Some(value), _) => Any::U64(value),
+                (None, Some(value)) => Any::I64(value),
+                (None, None) => match number.as_f64() {
+                    Some(value) => Any::F64(value),
+                    None => Any::Number(Cow::Owned(number.to_string())),
+                },
+            },
+            serde_json::Value::String(value) => Any::Str(Cow::Owned(value.clone())),
+            value => return Err(self.mismatched_type(var, value, "a scalar value")),
+        };
+        Ok(any)
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        match self.variable.parse_str(v) {
+            Some(var) => F::select(&self.document, var).is_some(),
+            None => true,
+        }
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        Some(self.variable.parse_str(v).is_some())
+    }
+}
+
+fn unexpected(value: &serde_json::Value) -> de::Unexpected<'_> {
+    match value {
+        serde_json::Value::Null => de::Unexpected::Unit,
+        serde_json::Value::Bool(value) => de::Unexpected::Bool(*value),
+        serde_json::Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+            (Some(value), _) => de::Unexpected::Unsigned(value),
+            (None, Some(value)) => de::Unexpected::Signed(value),
+            (None, None) => de::Unexpected::Float(number.as_f64().unwrap_or(f64::NAN)),
+        },
+        serde_json::Value::String(value) => de::Unexpected::Str(value),
+        serde_json::Value::Array(_) => de::Unexpected::Seq,
+        serde_json::Value::Object(_) => de::Unexpected::Map,
+    }
+}
+
+#[cfg(feature = "plist")]
+fn plist_to_json(value: plist::Value) -> serde_json::Value {
+    use serde_json::Value;
+
+    match value {
+        plist::Value::Array(values) => {
+            Value::Array(values.into_iter().map(plist_to_json).collect())
+        }
+        plist::Value::Dictionary(values) => Value::Object(
+            values
+                .into_iter()
+                .map(|(key, value)| (key, plist_to_json(value)))
+                .collect(),
+        ),
+        plist::Value::Boolean(value) => Value::Bool(value),
+        plist::Value::Data(value) => Value::String(utils::encode_base64(&value)),
+        plist::Value::Date(value) => Value::String(value.to_xml_format()),
+        plist::Value::Real(value) => {
+            serde_json::Number::from_f64(value).map_or(Value::Null, Value::Number)
+        }
+        plist::Value::Integer(value) => match (value.as_signed(), value.as_unsigned()) {
+            (Some(value), _) => Value::from(value),
+            (None, Some(value)) => Value::from(value),
+            (None, None) => Value::Null,
+        },
+        plist::Value::String(value) => Value::String(value),
+        plist::Value::Uid(value) => Value::from(value.get()),
+        _ => Value::Null,
+    }
+}
@@ -0,0 +1,258 @@
+use std::{borrow::Cow, collections::HashMap, fmt, path::Path};
+
+use crate::source::{StringLookup, StringSource};
+
+/// A [`StringLookup`] which uses variables parsed from a `.env` file.
+///
+/// The file contains one `NAME=value` assignment per line:
+///
+/// ```text
+/// # Comments start with a `#`.
+/// export DATABASE_HOST=localhost   # An optional `export` prefix is ignored.
+/// DATABASE_PASSWORD='single quoted values are used literally'
+/// GREETING="double quoted values support escapes\tlike \n, \t, \" and \\"
+/// CERTIFICATE="-----BEGIN CERTIFICATE-----
+/// quoted values can span multiple lines
+/// -----END CERTIFICATE-----"
+/// ```
+///
+/// Unquoted values end at the end of the line or at a ` #` comment, surrounding whitespace is
+/// removed. If a variable is assigned multiple times, the last assignment wins.
+///
+/// Generally used through [`DotenvSource`].
+#[derive(Debug, Default, Clone)]
+pub struct DotenvLookup {
+    values: HashMap<String, String>,
+}
+
+impl DotenvLookup {
+    /// Parses the contents of a `.env` file.
+    pub fn parse(contents: &str) -> Result<Self, DotenvError> {
+        let mut parser = Parser { contents, pos: 0 };
+
+        let mut values = HashMap::new();
+        while let Some((name, value)) = parser.next_assignment()? {
+            values.insert(name.to_owned(), value);
+        }
+
+        Ok(Self { values })
+    }
+
+    /// Reads and parses the `.env` file at `path`.
+    ///
+    /// Syntax errors are reported as [`std::io::ErrorKind::InvalidData`],
+    /// with a [`DotenvError`] as the inner error.
+    pub fn from_path(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Self::parse(&contents)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+}
+
+impl StringLookup for DotenvLookup {
+    fn lookup(&mut self, v: &str) -> Option<Cow<'_, str>> {
+        self.values
+            .get(v)
+            .map(|value| Cow::Borrowed(value.as_str()))
+    }
+
+    fn lookup_names(&mut self, prefix: &str) -> Option<Vec<String>> {
+        self.values.lookup_names(prefix)
+    }
+}
+
+/// A source which uses values from a `.env` file.
+///
+/// See [`DotenvLookup`] for the supported syntax and the [`crate`] and [`StringSource`]
+/// documentation for more details.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::DotenvSource;
+///
+/// let mut source = DotenvSource::from_contents(
+///     r#"
+///     export REDIS_HOST=localhost  # Local development settings.
+///     REDIS_PORT="6379"
+///     "#,
+/// )
+/// .unwrap();
+///
+/// let mut de = serde_json::Deserializer::from_str(r#"["${REDIS_HOST}", "${REDIS_PORT}"]"#);
+/// let r: (String, u16) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, ("localhost".to_owned(), 6379));
+/// ```
+pub type DotenvSource = StringSource<DotenvLookup>;
+
+impl DotenvSource {
+    /// Creates a [`DotenvSource`] from the contents of a `.env` file.
+    ///
+    /// See [`DotenvLookup::parse`].
+    pub fn from_contents(contents: &str) -> Result<Self, DotenvError> {
+        DotenvLookup::parse(contents).map(StringSource::new)
+    }
+
+    /// Creates a [`DotenvSource`] from the `.env` file at `path`.
+    ///
+    /// See [`DotenvLookup::from_path`].
+    pub fn from_path(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        DotenvLookup::from_path(path).map(StringSource::new)
+    }
+}
+
+/// A syntax error in a `.env` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotenvError {
+    line: usize,
+    message: String,
+}
+
+impl DotenvError {
+    /// Returns the line number of the error, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl fmt::Display for DotenvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at line {}", self.message, self.line)
+    }
+}
+
+impl std::error::Error for DotenvError {}
+
+struct Parser<'a> {
+    contents: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.contents[self.pos..]
+    }
+
+    fn error(&self, message: impl Into<String>) -> DotenvError {
+        let line = self.contents[..self.pos].matches('\n').count() + 1;
+        DotenvError {
+            line,
+            message: message.into(),
+        }
+    }
+
+    /// Skips spaces and tabs.
+    fn skip_blanks(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start_matches([' ', '\t']).len();
+    }
+
+    /// Skips everything up to and including the next line break.
+    fn skip_line(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.find('\n').map_or(rest.len(), |end| end + 1);
+    }
+
+    fn next_assignment(&mut self) -> Result<Option<(&'a str, String)>, DotenvError> {
+        loop {
+            self.skip_blanks();
+            match self.rest().chars().next() {
+                None => return Ok(None),
+                Some('#' | '\r' | '\n') => self.skip_line(),
+                Some(_) => break,
+            }
+        }
+
+        if let Some(rest) = self.rest().strip_prefix("export") {
+            if rest.starts_with([' ', '\t']) {
+                self.pos += "export".len();
+                self.skip_blanks();
+            }
+        }
+
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')))
+            .unwrap_or(rest.len());
+        let name = &rest[..len];
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(self.error("expected a variable name"));
+        }
+        self.pos += len;
+
+        self.skip_blanks();
+        if !self.rest().starts_with('=') {
+            return Err(self.error(format!("expected `=` after `{name}`")));
+        }
+        self.pos += 1;
+        self.skip_blanks();
+
+        let value = match self.rest().chars().next() {
+            Some('\'') => self.single_quoted()?,
+            Some('"') => self.double_quoted()?,
+            _ => return Ok(Some((name, self.unquoted()))),
+        };
+
+        self.skip_blanks();
+        match self.rest().chars().next() {
+            None | Some('#' | '\r' | '\n') => self.skip_line(),
+            Some(_) => return Err(self.error("unexpected characters after a quoted value")),
+        }
+
+        Ok(Some((name, value)))
+    }
+
+    fn unquoted(&mut self) -> String {
+        let rest = self.rest();
+        let line = &rest[..rest.find('\n').unwrap_or(rest.len())];
+        self.skip_line();
+
+        let value = line
+            .find(" #")
+            .into_iter()
+            .chain(line.find("\t#"))
+            .min()
+            .map_or(line, |end| &line[..end]);
+        value.trim().to_owned()
+    }
+
+    fn single_quoted(&mut self) -> Result<String, DotenvError> {
+        let rest = &self.rest()[1..];
+        let Some(end) = rest.find('\'') else {
+            return Err(self.error("unterminated single quoted value"));
+        };
+        self.pos += end + 2;
+        Ok(rest[..end].to_owned())
+    }
+
+    fn double_quoted(&mut self) -> Result<String, DotenvError> {
+        let start = self.pos;
+        self.pos += 1;
+
+        let mut value = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(value);
+                }
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some(c @ ('"' | '\\' | '$' | '\'')) => value.push(c),
+                    Some(c) => {
+                        value.push('\\');
+                        value.push(c);
+                    }
+                    None => break,
+                },
+                c => value.push(c),
+            }
+        }
+
+        self.pos = start;
+        Err(self.error("unterminated double quoted value"))
+    }
+}
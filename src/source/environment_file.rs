@@ -0,0 +1,234 @@
+use std::{borrow::Cow, collections::HashMap, path::Path};
+
+use crate::source::{StringLookup, StringSource};
+
+/// A [`StringLookup`] which uses variables parsed from a systemd `EnvironmentFile=`.
+///
+/// The file is parsed exactly like systemd parses files configured with `EnvironmentFile=`,
+/// which differs from a [`DotenvLookup`](crate::source::DotenvLookup) in a few details:
+///
+/// ```text
+/// # Comments start with a `#` or a `;`, but only at the start of a line.
+/// DATABASE_HOST = localhost  # Everything after the `=` is part of the value.
+/// DATABASE_PASSWORD='single quoted values are used literally'
+/// GREETING="double quoted values only escape \", \\, \` and \$"
+/// UNQUOTED=backslashes escape\ any\ character
+/// CERTIFICATE=-----BEGIN CERTIFICATE----- \
+/// lines ending with a backslash are continued
+/// ```
+///
+/// - Whitespace around the name and the value is removed, whitespace within unquoted values
+///   is preserved.
+/// - Quotes are only recognized at the start of a value, adjacent quoted and unquoted parts
+///   are concatenated.
+/// - Lines without a `=` and assignments to invalid names, like `export NAME=value`, are
+///   ignored.
+///
+/// If a variable is assigned multiple times, the last assignment wins.
+///
+/// Generally used through [`EnvironmentFileSource`].
+#[derive(Debug, Default, Clone)]
+pub struct EnvironmentFileLookup {
+    values: HashMap<String, String>,
+}
+
+impl EnvironmentFileLookup {
+    /// Parses the contents of an environment file.
+    ///
+    /// Like systemd, parsing never fails, invalid lines are ignored.
+    pub fn parse(contents: &str) -> Self {
+        let mut values = HashMap::new();
+        for (name, value) in parse(contents) {
+            if is_valid_name(&name) {
+                values.insert(name, value);
+            }
+        }
+        Self { values }
+    }
+
+    /// Reads and parses the environment file at `path`.
+    pub fn from_path(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(Self::parse(&contents))
+    }
+}
+
+impl StringLookup for EnvironmentFileLookup {
+    fn lookup(&mut self, v: &str) -> Option<Cow<'_, str>> {
+        self.values
+            .get(v)
+            .map(|value| Cow::Borrowed(value.as_str()))
+    }
+}
+
+/// A source which uses values from a systemd `EnvironmentFile=`.
+///
+/// This allows a service to read the same file, which systemd uses for its environment.
+///
+/// See [`EnvironmentFileLookup`] for the supported syntax and the [`crate`] and [`StringSource`]
+/// documentation for more details.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::EnvironmentFileSource;
+///
+/// let mut source = EnvironmentFileSource::from_contents(
+///     r#"
+///     ; Production settings.
+///     REDIS_HOST=redis.internal
+///     REDIS_PORT="6379"
+///     "#,
+/// );
+///
+/// let mut de = serde_json::Deserializer::from_str(r#"["${REDIS_HOST}", "${REDIS_PORT}"]"#);
+/// let r: (String, u16) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, ("redis.internal".to_owned(), 6379));
+/// ```
+pub type EnvironmentFileSource = StringSource<EnvironmentFileLookup>;
+
+impl EnvironmentFileSource {
+    /// Creates an [`EnvironmentFileSource`] from the contents of an environment file.
+    ///
+    /// See [`EnvironmentFileLookup::parse`].
+    pub fn from_contents(contents: &str) -> Self {
+        StringSource::new(EnvironmentFileLookup::parse(contents))
+    }
+
+    /// Creates an [`EnvironmentFileSource`] from the environment file at `path`.
+    ///
+    /// See [`EnvironmentFileLookup::from_path`].
+    pub fn from_path(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        EnvironmentFileLookup::from_path(path).map(StringSource::new)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum State {
+    PreKey,
+    Key,
+    PreValue,
+    Value,
+    ValueEscape,
+    SingleQuoted,
+    DoubleQuoted,
+    DoubleQuotedEscape,
+    Comment,
+    CommentEscape,
+}
+
+/// Parses all assignments, following the state machine of systemd's `parse_env_file`.
+fn parse(contents: &str) -> Vec<(String, String)> {
+    let is_newline = |c: char| matches!(c, '\n' | '\r');
+    let is_whitespace = |c: char| matches!(c, ' ' | '\t' | '\n' | '\r');
+
+    let mut assignments = Vec::new();
+    let mut state = State::PreKey;
+    let mut key = String::new();
+    let mut value = String::new();
+    // Length of the key or value without trailing unescaped whitespace.
+    let mut trimmed = 0;
+
+    for c in contents.chars() {
+        state = match state {
+            State::PreKey if matches!(c, '#' | ';') => State::Comment,
+            State::PreKey if is_whitespace(c) => State::PreKey,
+            State::PreKey => {
+                key.clear();
+                key.push(c);
+                trimmed = key.len();
+                State::Key
+            }
+            State::Key if is_newline(c) => State::PreKey,
+            State::Key if c == '=' => {
+                key.truncate(trimmed);
+                value.clear();
+                trimmed = 0;
+                State::PreValue
+            }
+            State::Key => {
+                key.push(c);
+                if !is_whitespace(c) {
+                    trimmed = key.len();
+                }
+                State::Key
+            }
+            State::PreValue | State::Value if is_newline(c) => {
+                value.truncate(trimmed);
+                assignments.push((std::mem::take(&mut key), std::mem::take(&mut value)));
+                State::PreKey
+            }
+            State::PreValue if c == '\'' => State::SingleQuoted,
+            State::PreValue if c == '"' => State::DoubleQuoted,
+            State::PreValue | State::Value if c == '\\' => State::ValueEscape,
+            State::PreValue if is_whitespace(c) => State::PreValue,
+            State::PreValue | State::Value => {
+                value.push(c);
+                if !is_whitespace(c) {
+                    trimmed = value.len();
+                }
+                State::Value
+            }
+            State::ValueEscape => {
+                if !is_newline(c) {
+                    value.push(c);
+                    trimmed = value.len();
+                }
+                State::Value
+            }
+            State::SingleQuoted if c == '\'' => {
+                trimmed = value.len();
+                State::PreValue
+            }
+            State::SingleQuoted => {
+                value.push(c);
+                State::SingleQuoted
+            }
+            State::DoubleQuoted if c == '"' => {
+                trimmed = value.len();
+                State::PreValue
+            }
+            State::DoubleQuoted if c == '\\' => State::DoubleQuotedEscape,
+            State::DoubleQuoted => {
+                value.push(c);
+                State::DoubleQuoted
+            }
+            State::DoubleQuotedEscape => {
+                match c {
+                    '"' | '\\' | '`' | '$' => value.push(c),
+                    '\n' => {}
+                    c => {
+                        value.push('\\');
+                        value.push(c);
+                    }
+                }
+                State::DoubleQuoted
+            }
+            State::Comment if c == '\\' => State::CommentEscape,
+            State::Comment if is_newline(c) => State::PreKey,
+            State::Comment => State::Comment,
+            State::CommentEscape => State::Comment,
+        };
+    }
+
+    match state {
+        State::PreValue | State::Value | State::ValueEscape => {
+            value.truncate(trimmed);
+            assignments.push((key, value));
+        }
+        // Like systemd, unterminated quotes are accepted at the end of the file.
+        State::SingleQuoted | State::DoubleQuoted | State::DoubleQuotedEscape => {
+            assignments.push((key, value));
+        }
+        State::PreKey | State::Key | State::Comment | State::CommentEscape => {}
+    }
+
+    assignments
+}
+
+/// Returns whether `name` is a valid name of an environment variable, like systemd requires.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
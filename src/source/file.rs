@@ -1,26 +1,46 @@
 use std::{
     borrow::Cow,
-    path::{Path, PathBuf},
+    collections::{BTreeSet, HashMap},
+    fmt,
+    path::{Component, Path, PathBuf},
 };
 
 use serde::de;
 
-use crate::source::{utils, Any, Source};
+use crate::source::{
+    secret, utils, Any, DurationUnit, FileSystem, Metadata, SecretDetector, Source, StdFileSystem,
+    Variable,
+};
 
 // Possible future improvements:
-//  - A file-system abstraction
 //  - Abstract into a byte-source
 //  - Allow modifications to conversions
-//  - More validations (e.g. base-path)
+//  - More validations
 //  - A way to specify base path for relative paths
 
 /// A [`Source`] which provides values by reading them from the filesystem.
 ///
+/// Files are read from the filesystem of the operating system, a custom [`FileSystem`]
+/// can be configured with [`Self::with_file_system`].
+///
 /// For string and byte types, the source will simply attempt to open the file and load its
 /// contents.
 ///
 /// If, during de-serialization, the target type is known, the source will attempt to load the file
 /// as a string parse the value into the target type using [`std::str::FromStr`].
+/// Parsing of numbers is locale independent, floating point values always use `.` as the
+/// decimal separator. Use [`Self::with_comma_decimals`] to additionally accept `,`.
+/// Special floating point values, like `NaN`, are rejected unless enabled with
+/// [`Self::with_special_floats`].
+///
+/// Variables can also be written as local `file://` URIs, e.g. `${file:///run/secrets/token}`.
+/// URIs are percent-decoded and always refer to absolute paths.
+///
+/// With the `json` feature, a variable can select a single value of a structured file with
+/// a fragment, e.g. `${creds.json#database.password}`. Keys are separated by `.`, array elements
+/// are selected by their index. Strings are used as is, all other values as JSON.
+/// The format is chosen by the file extension, `.json` is always supported, `.yaml` and `.yml`
+/// require the `yaml` feature and `.toml` the `toml` feature.
 ///
 /// When de-serializing self-describing formats, like JSON or YAML into dynamic containers,
 /// like for example:
@@ -47,10 +67,33 @@ use crate::source::{utils, Any, Source};
 /// # Warning:
 ///
 /// This source must not be used with untrusted user input, it provides unfiltered access to the
-/// filesystem.
-pub struct FileSource {
+/// filesystem. Access can be restricted with [`Self::with_confine_to_base_path`],
+/// [`Self::with_allowed_paths`] and [`Self::with_allowed_extensions`].
+pub struct FileSource<F = StdFileSystem> {
+    fs: F,
     base_path: PathBuf,
-    variable: utils::Variable,
+    fallback_base_paths: Vec<PathBuf>,
+    variable: Variable,
+    parsing: utils::Parsing,
+    max_value_length: Option<usize>,
+    secrets: Option<SecretDetector>,
+    sensitive: bool,
+    trim: bool,
+    trim_trailing_newline: bool,
+    confine: bool,
+    secure_permissions: bool,
+    allowed_paths: Option<Vec<PathBuf>>,
+    allowed_extensions: Option<Vec<String>>,
+    file_names_only: bool,
+    base64_binary: bool,
+    encoding: Encoding,
+    strict: bool,
+    /// Contents of previously read files, by resolved path.
+    cache: Option<HashMap<PathBuf, Vec<u8>>>,
+    /// Resolved paths of all files accessed by this source.
+    files: BTreeSet<PathBuf>,
+    #[cfg(feature = "notify")]
+    invalidation: Option<crate::watch::Invalidation>,
 }
 
 impl FileSource {
@@ -76,8 +119,76 @@ impl FileSource {
     /// ```
     pub fn new() -> Self {
         Self {
+            fs: StdFileSystem,
             base_path: PathBuf::new(),
+            fallback_base_paths: Vec::new(),
             variable: Default::default(),
+            parsing: Default::default(),
+            max_value_length: None,
+            secrets: None,
+            sensitive: false,
+            trim: false,
+            trim_trailing_newline: false,
+            confine: false,
+            secure_permissions: false,
+            allowed_paths: None,
+            allowed_extensions: None,
+            file_names_only: false,
+            base64_binary: false,
+            encoding: Encoding::Utf8,
+            strict: false,
+            cache: Some(HashMap::new()),
+            files: BTreeSet::new(),
+            #[cfg(feature = "notify")]
+            invalidation: None,
+        }
+    }
+}
+
+impl<F> FileSource<F> {
+    /// Configures the [`FileSystem`] to read files from.
+    ///
+    /// By default files are read from the filesystem of the operating system, [`StdFileSystem`].
+    /// Changing the filesystem discards all cached file contents, the cache configuration,
+    /// including [`Self::with_cache_invalidation`], is kept.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use std::{collections::HashMap, path::PathBuf};
+    /// use serde_vars::FileSource;
+    ///
+    /// let files = HashMap::from([(PathBuf::from("port"), "8080")]);
+    /// let mut source = FileSource::new().with_file_system(files);
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${port}""#);
+    /// let r: u16 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, 8080);
+    /// ```
+    pub fn with_file_system<G>(self, fs: G) -> FileSource<G> {
+        FileSource {
+            fs,
+            base_path: self.base_path,
+            fallback_base_paths: self.fallback_base_paths,
+            variable: self.variable,
+            parsing: self.parsing,
+            max_value_length: self.max_value_length,
+            secrets: self.secrets,
+            sensitive: self.sensitive,
+            trim: self.trim,
+            trim_trailing_newline: self.trim_trailing_newline,
+            confine: self.confine,
+            secure_permissions: self.secure_permissions,
+            allowed_paths: self.allowed_paths,
+            allowed_extensions: self.allowed_extensions,
+            file_names_only: self.file_names_only,
+            base64_binary: self.base64_binary,
+            encoding: self.encoding,
+            strict: self.strict,
+            cache: self.cache.map(|_| HashMap::new()),
+            files: BTreeSet::new(),
+            #[cfg(feature = "notify")]
+            invalidation: self.invalidation.map(|_| crate::watch::Invalidation::new()),
         }
     }
 
@@ -86,7 +197,8 @@ impl FileSource {
     /// The configured path is joined with relative paths. To be independent of the
     /// current working directory it is recommended to configure an absolute path.
     ///
-    /// Note: There is no validation that a final path must be within that base directory.
+    /// Note: By default there is no validation that a final path must be within that base
+    /// directory, see [`Self::with_confine_to_base_path`].
     pub fn with_base_path<P>(mut self, path: P) -> Self
     where
         P: Into<PathBuf>,
@@ -95,6 +207,253 @@ impl FileSource {
         self
     }
 
+    /// Adds a fallback base path for relative paths.
+    ///
+    /// Relative paths are resolved against the base path configured with [`Self::with_base_path`]
+    /// and then against all fallback base paths in the order they were added, the first existing
+    /// file wins. If the file exists in none of them, the path in the primary base path is used.
+    ///
+    /// With [`Self::with_confine_to_base_path`], files may be located in any of the base paths.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # let temp = tempfile::tempdir().unwrap();
+    /// # std::fs::create_dir(temp.path().join("dev-secrets")).unwrap();
+    /// # std::fs::write(temp.path().join("dev-secrets/token"), "dev-token").unwrap();
+    /// #
+    /// use serde_vars::FileSource;
+    ///
+    /// let mut source = FileSource::new()
+    ///     .with_base_path("/run/secrets")
+    ///     .with_fallback_base_path("./dev-secrets");
+    /// # let mut source = source.with_fallback_base_path(temp.path().join("dev-secrets"));
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${token}""#);
+    /// let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, "dev-token");
+    /// ```
+    pub fn with_fallback_base_path<P>(mut self, path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.fallback_base_paths.push(path.into());
+        self
+    }
+
+    /// Rejects files outside of the base path.
+    ///
+    /// When enabled, the final path is canonicalized, resolving `..` components and symbolic
+    /// links, and must be located within the canonicalized base path configured with
+    /// [`Self::with_base_path`]. This prevents escapes like `${../../etc/passwd}`
+    /// and absolute paths outside of the base path.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # let temp = tempfile::tempdir().unwrap();
+    /// # std::fs::write(temp.path().join("my_file.txt"), "some secret value").unwrap();
+    /// #
+    /// use serde_vars::FileSource;
+    ///
+    /// let mut source = FileSource::new().with_confine_to_base_path(true);
+    /// # let mut source = source.with_base_path(temp.path());
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${../../etc/passwd}""#);
+    /// let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+    /// assert!(r.is_err());
+    /// ```
+    pub fn with_confine_to_base_path(mut self, confine: bool) -> Self {
+        self.confine = confine;
+        self
+    }
+
+    /// Rejects files which are accessible by other users or not owned by the current user.
+    ///
+    /// Similar to the checks `ssh` performs for private keys, this prevents loading secrets
+    /// which may have been read or modified by other users. Files must be owned by the
+    /// effective user of the process and must not grant any permissions to others, e.g. mode
+    /// `0600` or `0640`. Symbolic links are followed, the permissions of the target are checked.
+    ///
+    /// The check only applies to Unix platforms, on other platforms this option has no effect.
+    /// Files are rejected if the configured [`FileSystem`] does not report their owner and
+    /// permissions.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # let temp = tempfile::tempdir().unwrap();
+    /// # std::fs::write(temp.path().join("token"), "secret").unwrap();
+    /// # #[cfg(unix)]
+    /// # {
+    /// use std::os::unix::fs::PermissionsExt;
+    /// use serde_vars::FileSource;
+    ///
+    /// let mut source = FileSource::new().with_secure_permissions(true);
+    /// # let mut source = source.with_base_path(temp.path());
+    ///
+    /// # let path = temp.path().join("token");
+    /// std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+    /// let mut de = serde_json::Deserializer::from_str(r#""${token}""#);
+    /// let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+    /// assert!(r.unwrap_err().to_string().contains("accessible by other users"));
+    ///
+    /// std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+    /// let mut de = serde_json::Deserializer::from_str(r#""${token}""#);
+    /// let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, "secret");
+    /// # }
+    /// ```
+    pub fn with_secure_permissions(mut self, secure_permissions: bool) -> Self {
+        self.secure_permissions = secure_permissions;
+        self
+    }
+
+    /// Restricts files to the given paths.
+    ///
+    /// Only files which are one of the `paths`, or located in a directory of the `paths`,
+    /// may be referenced. Relative paths are relative to the base path configured with
+    /// [`Self::with_base_path`]. Paths are canonicalized before they are compared,
+    /// a symbolic link is only allowed if its target is allowed.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # let temp = tempfile::tempdir().unwrap();
+    /// # std::fs::create_dir(temp.path().join("tls")).unwrap();
+    /// # std::fs::write(temp.path().join("tls/ca.pem"), "certificate").unwrap();
+    /// # std::fs::write(temp.path().join("token"), "secret").unwrap();
+    /// #
+    /// use serde_vars::FileSource;
+    ///
+    /// let mut source = FileSource::new().with_allowed_paths(["tls", "/etc/ssl/certs"]);
+    /// # let mut source = source.with_base_path(temp.path());
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${tls/ca.pem}""#);
+    /// let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, "certificate");
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${token}""#);
+    /// let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+    /// assert!(r.unwrap_err().to_string().contains("path is not allowed"));
+    /// ```
+    pub fn with_allowed_paths<P>(mut self, paths: impl IntoIterator<Item = P>) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.allowed_paths = Some(paths.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Restricts files to the given file extensions, e.g. `pem` or `txt`.
+    ///
+    /// Extensions are compared case-insensitively, a leading `.` is ignored.
+    /// Files without an extension are rejected.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # let temp = tempfile::tempdir().unwrap();
+    /// # std::fs::write(temp.path().join("ca.PEM"), "certificate").unwrap();
+    /// # std::fs::write(temp.path().join("id_rsa"), "private key").unwrap();
+    /// #
+    /// use serde_vars::FileSource;
+    ///
+    /// let mut source = FileSource::new().with_allowed_extensions(["txt", "pem"]);
+    /// # let mut source = source.with_base_path(temp.path());
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${ca.PEM}""#);
+    /// let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, "certificate");
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${id_rsa}""#);
+    /// let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+    /// assert!(r.unwrap_err().to_string().contains("file extension is not allowed"));
+    /// ```
+    pub fn with_allowed_extensions<E>(mut self, extensions: impl IntoIterator<Item = E>) -> Self
+    where
+        E: Into<String>,
+    {
+        let extensions = extensions.into_iter().map(|extension| {
+            let extension = extension.into();
+            match extension.strip_prefix('.') {
+                Some(stripped) => stripped.to_owned(),
+                None => extension,
+            }
+        });
+        self.allowed_extensions = Some(extensions.collect());
+        self
+    }
+
+    /// Enables or disables caching of file contents.
+    ///
+    /// By default, every file is read only once and repeated variables referencing the same
+    /// file use the cached contents, until they are discarded with [`Self::clear_cache`].
+    /// Disable the cache to read files again on every access, e.g. for files which change while
+    /// a long lived source is in use. With the `notify` feature, contents of changed files can
+    /// also be discarded automatically, see `with_cache_invalidation`.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # let temp = tempfile::tempdir().unwrap();
+    /// # std::fs::write(temp.path().join("counter"), "1").unwrap();
+    /// #
+    /// use serde_vars::FileSource;
+    ///
+    /// let mut source = FileSource::new();
+    /// # let mut source = source.with_base_path(temp.path());
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${counter}""#);
+    /// let r: u32 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, 1);
+    ///
+    /// # std::fs::write(temp.path().join("counter"), "2").unwrap();
+    /// let mut de = serde_json::Deserializer::from_str(r#""${counter}""#);
+    /// let r: u32 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, 1);
+    ///
+    /// source.clear_cache();
+    /// let mut de = serde_json::Deserializer::from_str(r#""${counter}""#);
+    /// let r: u32 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, 2);
+    ///
+    /// let mut source = source.with_cache(false);
+    /// # std::fs::write(temp.path().join("counter"), "3").unwrap();
+    /// let mut de = serde_json::Deserializer::from_str(r#""${counter}""#);
+    /// let r: u32 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, 3);
+    /// ```
+    pub fn with_cache(mut self, enabled: bool) -> Self {
+        self.cache = enabled.then(HashMap::new);
+        self
+    }
+
+    /// Discards cached file contents when files change.
+    ///
+    /// Requires the `notify` feature. The directories of cached files are watched and after
+    /// any change, all cached contents are discarded. Files are read again on their next access,
+    /// which lets long lived sources pick up rotated secrets. Files in directories which cannot
+    /// be watched are not cached. Directories are watched on the filesystem of the operating
+    /// system, also when files are read through another [`FileSystem`].
+    ///
+    /// Has no effect if caching is disabled with [`Self::with_cache`].
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use serde_vars::FileSource;
+    ///
+    /// let mut source = FileSource::new()
+    ///     .with_base_path("/run/secrets")
+    ///     .with_cache_invalidation(true);
+    /// ```
+    #[cfg(feature = "notify")]
+    pub fn with_cache_invalidation(mut self, enabled: bool) -> Self {
+        self.invalidation = enabled.then(crate::watch::Invalidation::new);
+        self
+    }
+
     /// Changes the variable prefix.
     ///
     /// # Examples:
@@ -122,14 +481,622 @@ impl FileSource {
         self.variable.suffix = suffix.into();
         self
     }
+
+    /// Adds an alternative variable prefix and suffix pair.
+    ///
+    /// See [`StringSource::with_alternative_delimiters`](crate::StringSource::with_alternative_delimiters) for details.
+    pub fn with_alternative_delimiters(
+        mut self,
+        prefix: impl Into<String>,
+        suffix: impl Into<String>,
+    ) -> Self {
+        self.variable
+            .alternatives
+            .push((prefix.into(), suffix.into()));
+        self
+    }
+
+    /// Accepts `,` as decimal separator for floating point values.
+    ///
+    /// See [`StringSource::with_comma_decimals`](crate::StringSource::with_comma_decimals).
+    pub fn with_comma_decimals(mut self, comma_decimals: bool) -> Self {
+        self.parsing.comma_decimals = comma_decimals;
+        self
+    }
+
+    /// Accepts the special values `NaN`, `inf` and `-inf` for floating point values.
+    ///
+    /// See [`StringSource::with_special_floats`](crate::StringSource::with_special_floats).
+    pub fn with_special_floats(mut self, special_floats: bool) -> Self {
+        self.parsing.special_floats = special_floats;
+        self
+    }
+
+    /// Accepts hexadecimal, octal, binary and underscore separated integer literals.
+    ///
+    /// See [`StringSource::with_integer_literals`](crate::StringSource::with_integer_literals).
+    pub fn with_integer_literals(mut self, integer_literals: bool) -> Self {
+        self.parsing.integer_literals = integer_literals;
+        self
+    }
+
+    /// Accepts common boolean synonyms for boolean values.
+    ///
+    /// See [`StringSource::with_relaxed_bools`](crate::StringSource::with_relaxed_bools).
+    pub fn with_relaxed_bools(mut self, relaxed_bools: bool) -> Self {
+        self.parsing.relaxed_bools = relaxed_bools;
+        self
+    }
+
+    /// Accepts durations for integer values, e.g. `5s`, `2m` or `1h30m`.
+    ///
+    /// See [`StringSource::with_duration_suffixes`](crate::StringSource::with_duration_suffixes).
+    pub fn with_duration_suffixes(mut self, unit: DurationUnit) -> Self {
+        self.parsing.duration_unit = Some(unit);
+        self
+    }
+
+    /// Accepts byte sizes for integer values, e.g. `10MB` or `512KiB`.
+    ///
+    /// See [`StringSource::with_byte_size_suffixes`](crate::StringSource::with_byte_size_suffixes).
+    pub fn with_byte_size_suffixes(mut self, byte_sizes: bool) -> Self {
+        self.parsing.byte_sizes = byte_sizes;
+        self
+    }
+
+    /// Removes leading and trailing whitespace from file contents.
+    ///
+    /// Contents are trimmed before they are converted into the target type, byte targets
+    /// always receive the file contents unchanged.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # let temp = tempfile::tempdir().unwrap();
+    /// # std::fs::write(temp.path().join("port"), "5432\n").unwrap();
+    /// #
+    /// use serde_vars::FileSource;
+    ///
+    /// let mut source = FileSource::new().with_trim(true);
+    /// # let mut source = source.with_base_path(temp.path());
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${port}""#);
+    /// let r: u16 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, 5432);
+    /// ```
+    pub fn with_trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Removes a single trailing newline, `\n` or `\r\n`, from file contents.
+    ///
+    /// Files mounted as secrets, e.g. by Kubernetes or Docker, commonly end with a newline.
+    /// Unlike [`Self::with_trim`], the newline is also removed for byte targets and all other
+    /// whitespace is preserved. Disabled by default, as it changes the contents of binary files.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # let temp = tempfile::tempdir().unwrap();
+    /// # std::fs::write(temp.path().join("password"), "hunter2\n").unwrap();
+    /// #
+    /// use serde_vars::FileSource;
+    ///
+    /// let mut source = FileSource::new().with_trim_trailing_newline(true);
+    /// # let mut source = source.with_base_path(temp.path());
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${password}""#);
+    /// let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, "hunter2");
+    /// ```
+    pub fn with_trim_trailing_newline(mut self, trim_trailing_newline: bool) -> Self {
+        self.trim_trailing_newline = trim_trailing_newline;
+        self
+    }
+
+    /// Encodes binary files as base64 when they are expanded into strings.
+    ///
+    /// By default, expanding a file which is not valid UTF-8 into a string fails with an error.
+    /// With this option enabled, the contents of such files are encoded as standard, padded
+    /// base64 instead. Files which are valid UTF-8 are not affected.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # let temp = tempfile::tempdir().unwrap();
+    /// # std::fs::write(temp.path().join("key.bin"), [0xde, 0xad, 0xbe, 0xef]).unwrap();
+    /// #
+    /// use serde_vars::FileSource;
+    ///
+    /// let mut source = FileSource::new().with_base64_binary(true);
+    /// # let mut source = source.with_base_path(temp.path());
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${key.bin}""#);
+    /// let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, "3q2+7w==");
+    /// ```
+    pub fn with_base64_binary(mut self, base64_binary: bool) -> Self {
+        self.base64_binary = base64_binary;
+        self
+    }
+
+    /// Configures the text [`Encoding`] of files.
+    ///
+    /// Files are decoded with the configured encoding when they are expanded into strings,
+    /// numbers or other values parsed from text. Byte targets receive the raw file contents.
+    /// Values selected from structured files, like `${config.json#key}`, are not affected.
+    ///
+    /// Defaults to [`Encoding::Utf8`].
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # let temp = tempfile::tempdir().unwrap();
+    /// # std::fs::write(temp.path().join("password.txt"), b"\xff\xfeh\0\xfc\0n\0t\0e\0r\0").unwrap();
+    /// #
+    /// use serde_vars::{source::Encoding, FileSource};
+    ///
+    /// let mut source = FileSource::new().with_encoding(Encoding::Utf16Le);
+    /// # let mut source = source.with_base_path(temp.path());
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${password.txt}""#);
+    /// let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, "hünter");
+    /// ```
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Limits the length of file contents.
+    ///
+    /// The size of a file is checked before it is read, files larger than `max` bytes
+    /// are rejected with an error describing the size of the file, instead of being
+    /// read into memory completely. By default file contents are not limited.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # let temp = tempfile::tempdir().unwrap();
+    /// # std::fs::write(temp.path().join("huge.bin"), [0; 2048]).unwrap();
+    /// #
+    /// use serde_vars::FileSource;
+    ///
+    /// let mut source = FileSource::new().with_max_value_length(1024);
+    /// # let mut source = source.with_base_path(temp.path());
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${huge.bin}""#);
+    /// let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+    /// assert!(r.unwrap_err().to_string().contains("file of 2048 bytes exceeds"));
+    /// ```
+    pub fn with_max_value_length(mut self, max: usize) -> Self {
+        self.max_value_length = Some(max);
+        self
+    }
+
+    /// Configures a [`SecretDetector`].
+    ///
+    /// File contents detected as secrets are redacted from error messages of the source.
+    pub fn with_secret_detector(mut self, detector: SecretDetector) -> Self {
+        self.secrets = Some(detector);
+        self
+    }
+
+    /// Treats all file contents as secrets.
+    ///
+    /// When enabled, file contents are redacted from error messages, like contents
+    /// detected by a [`SecretDetector`], regardless of their contents.
+    ///
+    /// Only errors created by the source itself are redacted, errors raised by the deserialized
+    /// type, for example an unknown enum variant or a string which is not a single `char`,
+    /// may still include the value.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # let temp = tempfile::tempdir().unwrap();
+    /// # std::fs::write(temp.path().join("db_password"), "hunter2").unwrap();
+    /// #
+    /// use serde_vars::FileSource;
+    ///
+    /// let mut source = FileSource::new().with_sensitive(true);
+    /// # let mut source = source.with_base_path(temp.path());
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${db_password}""#);
+    /// let r: Result<u64, _> = serde_vars::deserialize(&mut de, &mut source);
+    /// assert!(!r.unwrap_err().to_string().contains("hunter2"));
+    /// ```
+    pub fn with_sensitive(mut self, sensitive: bool) -> Self {
+        self.sensitive = sensitive;
+        self
+    }
+
+    /// Rejects strings which resemble variables, but are not well-formed variables.
+    ///
+    /// See [`StringSource::with_strict_variables`](crate::StringSource::with_strict_variables).
+    pub fn with_strict_variables(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Returns the [`Variable`] grammar used by this source.
+    pub fn variable(&self) -> &Variable {
+        &self.variable
+    }
+
+    /// Returns the resolved paths of all files accessed by this source.
+    ///
+    /// Paths are recorded for every file a variable referenced, also for files which
+    /// could not be read, e.g. because they did not exist.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use serde_vars::FileSource;
+    ///
+    /// let mut source = FileSource::new().with_base_path("/run/secrets");
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${token}""#);
+    /// let _: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+    ///
+    /// let files: Vec<_> = source.files().collect();
+    /// assert_eq!(files, [Path::new("/run/secrets/token")]);
+    /// ```
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(PathBuf::as_path)
+    }
+
+    /// Discards all cached file contents, see [`Self::with_cache`].
+    pub fn clear_cache(&mut self) {
+        if let Some(cache) = &mut self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Only allows variables naming a file directly in the base path, see [`DirSource`](super::DirSource).
+    pub(super) fn with_file_names_only(mut self, file_names_only: bool) -> Self {
+        self.file_names_only = file_names_only;
+        self
+    }
 }
 
-impl FileSource {
+impl<F> FileSource<F>
+where
+    F: FileSystem,
+{
     fn resolve_path<'a>(&self, path: &'a Path) -> Cow<'a, Path> {
-        match path.is_absolute() {
-            true => Cow::Borrowed(path),
-            false => Cow::Owned(self.base_path.join(path)),
+        if let Some(path) = file_uri(path) {
+            return Cow::Owned(path);
         }
+
+        if path.is_absolute() {
+            return Cow::Borrowed(path);
+        }
+
+        let primary = self.base_path.join(path);
+        if self.fallback_base_paths.is_empty() || self.fs.metadata(&primary).is_ok() {
+            return Cow::Owned(primary);
+        }
+
+        let fallback = self
+            .fallback_base_paths
+            .iter()
+            .map(|base_path| base_path.join(path))
+            .find(|path| self.fs.metadata(path).is_ok());
+        Cow::Owned(fallback.unwrap_or(primary))
+    }
+
+    /// Returns the primary and all fallback base paths.
+    fn base_paths(&self) -> impl Iterator<Item = &Path> {
+        std::iter::once(&self.base_path)
+            .chain(&self.fallback_base_paths)
+            .map(PathBuf::as_path)
+    }
+
+    fn is_secret(&self, value: &str) -> bool {
+        self.sensitive || self.secrets.as_ref().is_some_and(|s| s.is_secret(value))
+    }
+
+    /// Returns an error if confinement is enabled and `path` is outside of the base path.
+    fn confine<'a>(&self, path: &'a Path) -> std::io::Result<Cow<'a, Path>> {
+        if self.file_names_only && !self.is_file_name(path) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!(
+                    "path is not a file name in the base path `{}`",
+                    self.base_path.display()
+                ),
+            ));
+        }
+
+        if !self.confine {
+            return Ok(Cow::Borrowed(path));
+        }
+
+        let canonicalize = |path: &Path| self.fs.canonicalize(path);
+
+        let path = canonicalize(path)?;
+        if self.fallback_base_paths.is_empty() {
+            let base_path = canonicalize(&self.base_path)?;
+            if path.starts_with(&base_path) {
+                return Ok(Cow::Owned(path));
+            }
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!("path is outside of the base path `{}`", base_path.display()),
+            ));
+        }
+
+        // Base paths which do not exist, e.g. a missing fallback, cannot contain the file.
+        let confined = self
+            .base_paths()
+            .filter_map(|base_path| canonicalize(base_path).ok())
+            .any(|base_path| path.starts_with(base_path));
+        if !confined {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                "path is outside of the base paths",
+            ));
+        }
+
+        Ok(Cow::Owned(path))
+    }
+
+    /// Returns an error if `path` is not permitted by the allowed paths and extensions.
+    fn check_allowed(&self, path: &Path) -> std::io::Result<()> {
+        if self.allowed_paths.is_none() && self.allowed_extensions.is_none() {
+            return Ok(());
+        }
+
+        let path = self.fs.canonicalize(path)?;
+
+        if let Some(allowed_paths) = &self.allowed_paths {
+            let allowed = allowed_paths
+                .iter()
+                .filter_map(|allowed| self.fs.canonicalize(&self.base_path.join(allowed)).ok())
+                .any(|allowed| path.starts_with(allowed));
+            if !allowed {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::PermissionDenied,
+                    "path is not allowed",
+                ));
+            }
+        }
+
+        if let Some(allowed_extensions) = &self.allowed_extensions {
+            let allowed = path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| {
+                    allowed_extensions
+                        .iter()
+                        .any(|allowed| allowed.eq_ignore_ascii_case(extension))
+                });
+            if !allowed {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::PermissionDenied,
+                    "file extension is not allowed",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if `path` is a single file name joined to one of the base paths.
+    fn is_file_name(&self, path: &Path) -> bool {
+        self.base_paths().any(|base_path| {
+            path.strip_prefix(base_path).is_ok_and(|path| {
+                let mut components = path.components();
+                matches!(
+                    (components.next(), components.next()),
+                    (Some(Component::Normal(_)), None)
+                )
+            })
+        })
+    }
+
+    fn read(&mut self, path: &Path) -> std::io::Result<Vec<u8>> {
+        if !self.files.contains(path) {
+            self.files.insert(path.to_owned());
+        }
+
+        #[cfg(feature = "notify")]
+        if self
+            .invalidation
+            .as_ref()
+            .is_some_and(crate::watch::Invalidation::take_changed)
+        {
+            self.clear_cache();
+        }
+
+        // Confinement, size limits and permissions are checked on every access, also for cached
+        // contents.
+        let path = self.confine(path)?;
+        self.check_allowed(&path)?;
+        let mut value = match self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get(path.as_ref()))
+        {
+            Some(value) => {
+                self.check_size(value.len() as u64)?;
+                if self.secure_permissions {
+                    check_permissions(&self.fs.metadata(&path)?)?;
+                }
+                value.clone()
+            }
+            None => {
+                // Watch before reading, changes during the read must discard the contents.
+                let cacheable = self.cache.is_some() && self.watch(&path);
+                let value = self.read_file(&path)?;
+                if let Some(cache) = self.cache.as_mut().filter(|_| cacheable) {
+                    cache.insert(path.into_owned(), value.clone());
+                }
+                value
+            }
+        };
+        if self.trim_trailing_newline && value.ends_with(b"\n") {
+            value.pop();
+            if value.ends_with(b"\r") {
+                value.pop();
+            }
+        }
+        Ok(value)
+    }
+
+    /// Watches the file `path` for changes, if cache invalidation is enabled.
+    ///
+    /// Returns `false` if contents of the file must not be cached.
+    #[cfg_attr(not(feature = "notify"), allow(unused_variables))]
+    fn watch(&mut self, path: &Path) -> bool {
+        #[cfg(feature = "notify")]
+        if let Some(invalidation) = &mut self.invalidation {
+            return invalidation.watch(path);
+        }
+
+        true
+    }
+
+    fn check_size(&self, len: u64) -> std::io::Result<()> {
+        match self.max_value_length {
+            Some(max) if len > max as u64 => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("file of {len} bytes exceeds the maximum length of {max} bytes"),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn read_file(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        if self.max_value_length.is_none() && !self.secure_permissions {
+            return self.fs.read(path);
+        }
+
+        let metadata = self.fs.metadata(path)?;
+        if self.secure_permissions {
+            check_permissions(&metadata)?;
+        }
+
+        let Some(max) = self.max_value_length else {
+            return self.fs.read(path);
+        };
+
+        // Check the size upfront, the read is still limited for files without a reliable size.
+        self.check_size(metadata.len())?;
+
+        let value = self.fs.read_limited(path, max)?;
+        if value.len() > max {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("file exceeds the maximum length of {max} bytes"),
+            ));
+        }
+
+        Ok(value)
+    }
+
+    /// Reads the contents of the file referenced by the variable `var` as a string.
+    ///
+    /// Variables selecting a key of a structured file, like `${config.json#key}`,
+    /// return the value of the key instead.
+    fn contents<E>(&mut self, var: &str) -> Result<String, E>
+    where
+        E: de::Error,
+    {
+        let value = self.contents_bytes(var)?;
+        self.decode(var, value)
+            .map(|value| self.trimmed(value))
+            .map_err(|_| self.invalid_text(var))
+    }
+
+    /// Decodes the contents of the file referenced by the variable `var`.
+    ///
+    /// Returns the original contents, if they are not valid in the configured encoding.
+    #[cfg_attr(not(feature = "json"), allow(unused_variables))]
+    fn decode(&self, var: &str, value: Vec<u8>) -> Result<String, Vec<u8>> {
+        // Values of structured files are already decoded.
+        #[cfg(feature = "json")]
+        if Format::split(var).is_some() {
+            return String::from_utf8(value).map_err(|err| err.into_bytes());
+        }
+
+        self.encoding.decode(value)
+    }
+
+    /// Reads the raw contents of the file referenced by the variable `var`.
+    ///
+    /// Like [`Self::contents`], but without decoding and trimming the contents.
+    fn contents_bytes<E>(&mut self, var: &str) -> Result<Vec<u8>, E>
+    where
+        E: de::Error,
+    {
+        #[cfg(feature = "json")]
+        if let Some((format, file, keys)) = Format::split(var) {
+            return self.select(var, format, file, keys).map(String::into_bytes);
+        }
+
+        let path = self.resolve_path(var.as_ref());
+        self.read(&path)
+            .map_err(|error| self.io_error(&path, var.as_ref(), error))
+    }
+
+    fn invalid_text<E>(&self, var: &str) -> E
+    where
+        E: de::Error,
+    {
+        let path = self.resolve_path(var.as_ref());
+        let error = std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("stream did not contain valid {}", self.encoding),
+        );
+        self.io_error(&path, var.as_ref(), error)
+    }
+
+    /// Selects the value addressed by `keys` in the structured `file`.
+    #[cfg(feature = "json")]
+    fn select<E>(&mut self, var: &str, format: Format, file: &str, keys: &str) -> Result<String, E>
+    where
+        E: de::Error,
+    {
+        let path = self.resolve_path(file.as_ref());
+        let encoding = self.encoding;
+        let document = self
+            .read(&path)
+            .and_then(|contents| match encoding {
+                Encoding::Utf8 => Ok(contents),
+                encoding => encoding
+                    .decode(contents)
+                    .map(String::into_bytes)
+                    .map_err(|_| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            format!("stream did not contain valid {encoding}"),
+                        )
+                    }),
+            })
+            .and_then(|contents| {
+                format
+                    .parse(&contents)
+                    .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+            })
+            .map_err(|error| self.io_error(&path, var.as_ref(), error))?;
+
+        match utils::select(&document, keys) {
+            Some(value) => Ok(utils::json_scalar(value)),
+            None => {
+                let var = self.variable.fmt(var);
+                Err(E::custom(format!(
+                    "got variable `{var}`, but the key `{keys}` does not exist"
+                )))
+            }
+        }
+    }
+
+    fn trimmed(&self, value: String) -> String {
+        utils::trim_if(self.trim, Cow::Owned(value)).into_owned()
     }
 
     fn io_error<E>(&self, path: &Path, v: &Path, error: std::io::Error) -> E
@@ -143,6 +1110,20 @@ impl FileSource {
         ))
     }
 
+    /// Returns an error if strict variables are enabled and `v` is a malformed variable.
+    fn check_malformed<E>(&self, v: &str) -> Result<(), E>
+    where
+        E: de::Error,
+    {
+        match self.strict && self.variable.is_malformed(v) {
+            true => Err(utils::malformed_variable(
+                &self.variable,
+                de::Unexpected::Str(v),
+            )),
+            false => Ok(()),
+        }
+    }
+
     fn expected_variable<E>(&self, v: &str, expected: &str) -> E
     where
         E: de::Error,
@@ -167,40 +1148,52 @@ impl FileSource {
 
     fn parsed<V, E>(&mut self, v: &str, expected: &str) -> Result<V, E>
     where
-        V: std::str::FromStr,
-        V::Err: std::fmt::Display,
+        V: utils::FromValue,
         E: de::Error,
     {
         let Some(var) = self.variable.parse_str(v) else {
             return Err(self.expected_variable(v, expected));
         };
 
-        let path = self.resolve_path(var.as_ref());
-        let value = std::fs::read_to_string(&path)
-            .map_err(|error| self.io_error(&path, var.as_ref(), error))?;
+        let value = self.contents(var)?;
 
-        value
-            .parse()
-            .map_err(|_| self.mismatched_type(var, de::Unexpected::Str(&value), expected))
+        V::from_value(&value, &self.parsing).ok_or_else(|| {
+            let unexpected = secret::redact(self.is_secret(&value), de::Unexpected::Str(&value));
+            let expected = V::expecting(&value, &self.parsing).unwrap_or(expected);
+            self.mismatched_type(var, unexpected, expected)
+        })
     }
 }
 
-impl Source for FileSource {
+impl<F> Source for FileSource<F>
+where
+    F: FileSystem,
+{
     fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
     where
         E: serde::de::Error,
     {
         let Some(var) = self.variable.parse_str(&v) else {
+            self.check_malformed(&v)?;
             return Ok(v);
         };
 
-        let path = self.resolve_path(var.as_ref());
-        let value = std::fs::read_to_string(&path)
-            .map_err(|error| self.io_error(&path, var.as_ref(), error))?;
+        let value = self.contents_bytes(var)?;
+        let value = match self.decode(var, value) {
+            Ok(value) => self.trimmed(value),
+            Err(value) if self.base64_binary => {
+                return Ok(Cow::Owned(utils::encode_base64(&value)));
+            }
+            Err(_) => return Err(self.invalid_text(var)),
+        };
 
+        let is_secret = self.is_secret(&value);
         match utils::parse(Cow::Owned(value)) {
             Any::Str(value) => Ok(value),
-            other => Err(self.mismatched_type(var, other.unexpected(), "a string")),
+            other => {
+                let unexpected = secret::redact(is_secret, other.unexpected());
+                Err(self.mismatched_type(var, unexpected, "a string"))
+            }
         }
     }
 
@@ -209,28 +1202,48 @@ impl Source for FileSource {
         E: serde::de::Error,
     {
         let Some(var) = self.variable.parse_bytes(&v) else {
+            if self.strict && self.variable.is_malformed_bytes(&v) {
+                return Err(utils::malformed_variable(
+                    &self.variable,
+                    de::Unexpected::Bytes(&v),
+                ));
+            }
             return Ok(v);
         };
 
+        #[cfg(feature = "json")]
+        if let Some(var) = std::str::from_utf8(var)
+            .ok()
+            .filter(|var| Format::split(var).is_some())
+        {
+            return self.contents_bytes(var).map(Cow::Owned);
+        }
+
         #[cfg(unix)]
         let path = {
             use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::Path};
             Path::new(OsStr::from_bytes(var))
         };
-        // Technically `wasi` also provides an `OsStrExt` which allows conversion from bytes, but
-        // since that seems to also be conditional on `target_env` for the sake of simplicity it's
-        // omitted here and should be added on demand.
-        #[cfg(not(unix))]
+        #[cfg(target_os = "wasi")]
         let path = {
-            match std::str::from_utf8(var) {
-                Ok(s) => Path::new(s),
-                Err(_) => return Ok(v), // TODO: error here
+            use std::{ffi::OsStr, os::wasi::ffi::OsStrExt, path::Path};
+            Path::new(OsStr::from_bytes(var))
+        };
+        #[cfg(not(any(unix, target_os = "wasi")))]
+        let path = match std::str::from_utf8(var) {
+            Ok(s) => Path::new(s),
+            Err(_) => {
+                let var = self.variable.fmt(String::from_utf8_lossy(var));
+                return Err(E::custom(format!(
+                    "got variable `{var}`, but its path is not valid UTF-8"
+                )));
             }
         };
 
         let full_path = self.resolve_path(path);
-        let value =
-            std::fs::read(&full_path).map_err(|error| self.io_error(&full_path, path, error))?;
+        let value = self
+            .read(&full_path)
+            .map_err(|error| self.io_error(&full_path, path, error))?;
 
         Ok(Cow::Owned(value))
     }
@@ -270,6 +1283,13 @@ impl Source for FileSource {
         self.parsed(v, "a signed integer (i64)")
     }
 
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        self.parsed(v, "a signed integer (i128)")
+    }
+
     fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
     where
         E: de::Error,
@@ -298,6 +1318,13 @@ impl Source for FileSource {
         self.parsed(v, "an unsigned integer (i64)")
     }
 
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        self.parsed(v, "an unsigned integer (u128)")
+    }
+
     fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
     where
         E: de::Error,
@@ -318,19 +1345,230 @@ impl Source for FileSource {
     {
         let Some(var) = self.variable.parse_str(&v) else {
             // There is no variable in the string, the expanded variant is just the original.
+            self.check_malformed(&v)?;
             return Ok(Any::Str(v));
         };
 
-        let path = self.resolve_path(var.as_ref());
-        let value =
-            std::fs::read(&path).map_err(|error| self.io_error(&path, var.as_ref(), error))?;
-
-        let value = String::from_utf8(value)
-            .map(Cow::Owned)
+        let value = self.contents_bytes(var)?;
+        let value = self
+            .decode(var, value)
+            .map(|value| Cow::Owned(self.trimmed(value)))
             .map(utils::parse)
-            .unwrap_or_else(|err| Any::Bytes(Cow::Owned(err.into_bytes())));
+            .unwrap_or_else(|value| Any::Bytes(Cow::Owned(value)));
         Ok(value)
     }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        let Some(var) = self.variable.parse_str(v) else {
+            return true;
+        };
+
+        #[cfg(feature = "json")]
+        let var = Format::split(var).map_or(var, |(_, file, _)| file);
+
+        // Only files which do not exist are missing, all other failures are reported as errors.
+        let path = self.resolve_path(var.as_ref());
+        !matches!(
+            self.fs.metadata(&path),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound
+        )
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        Some(self.variable.parse_str(v).is_some())
+    }
+}
+
+/// Text encoding of files, see [`FileSource::with_encoding`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Encoding {
+    /// UTF-8, the default.
+    #[default]
+    Utf8,
+    /// UTF-16 little endian, the encoding Windows commonly uses for Unicode text files.
+    ///
+    /// A byte order mark is removed and takes precedence over the configured byte order.
+    Utf16Le,
+    /// UTF-16 big endian.
+    ///
+    /// A byte order mark is removed and takes precedence over the configured byte order.
+    Utf16Be,
+    /// ISO-8859-1, also known as Latin-1.
+    ///
+    /// Every byte is decoded to the Unicode code point of the same value.
+    Latin1,
+}
+
+impl Encoding {
+    /// Decodes `value`, returns the original value if it is not valid in this encoding.
+    fn decode(self, value: Vec<u8>) -> Result<String, Vec<u8>> {
+        let little_endian = match self {
+            Self::Utf8 => return String::from_utf8(value).map_err(|err| err.into_bytes()),
+            Self::Latin1 => return Ok(value.iter().map(|&b| char::from(b)).collect()),
+            Self::Utf16Le => true,
+            Self::Utf16Be => false,
+        };
+
+        let (little_endian, units) = match value.as_slice() {
+            [0xff, 0xfe, units @ ..] => (true, units),
+            [0xfe, 0xff, units @ ..] => (false, units),
+            units => (little_endian, units),
+        };
+        if !units.len().is_multiple_of(2) {
+            return Err(value);
+        }
+
+        let units = units.chunks_exact(2).map(|unit| match little_endian {
+            true => u16::from_le_bytes([unit[0], unit[1]]),
+            false => u16::from_be_bytes([unit[0], unit[1]]),
+        });
+        char::decode_utf16(units)
+            .collect::<Result<String, _>>()
+            .map_err(|_| value)
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Utf8 => f.write_str("UTF-8"),
+            Self::Utf16Le => f.write_str("UTF-16LE"),
+            Self::Utf16Be => f.write_str("UTF-16BE"),
+            Self::Latin1 => f.write_str("ISO-8859-1"),
+        }
+    }
+}
+
+/// A structured file format, which allows selecting keys with a fragment.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Json,
+    #[cfg(feature = "yaml")]
+    Yaml,
+    #[cfg(feature = "toml")]
+    Toml,
+}
+
+#[cfg(feature = "json")]
+impl Format {
+    /// Splits a variable like `config.json#database.password` into the format,
+    /// the path of the file and the selected keys.
+    fn split(var: &str) -> Option<(Self, &str, &str)> {
+        let (file, keys) = var.rsplit_once('#')?;
+        let format = match Path::new(file).extension()?.to_str()? {
+            "json" => Self::Json,
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => Self::Yaml,
+            #[cfg(feature = "toml")]
+            "toml" => Self::Toml,
+            _ => return None,
+        };
+        Some((format, file, keys))
+    }
+
+    fn parse(self, contents: &[u8]) -> Result<serde_json::Value, String> {
+        match self {
+            Self::Json => {
+                serde_json::from_slice(contents).map_err(|err| format!("invalid JSON: {err}"))
+            }
+            #[cfg(feature = "yaml")]
+            Self::Yaml => {
+                serde_yaml::from_slice(contents).map_err(|err| format!("invalid YAML: {err}"))
+            }
+            #[cfg(feature = "toml")]
+            Self::Toml => std::str::from_utf8(contents)
+                .map_err(|err| err.to_string())
+                .and_then(|contents| toml::from_str(contents).map_err(|err| err.to_string()))
+                .map_err(|err| format!("invalid TOML: {err}")),
+        }
+    }
+}
+
+/// Returns an error if the file is not owned by the current user or accessible by other users.
+///
+/// On Unix platforms files are rejected if the [`FileSystem`] does not report their owner and
+/// permissions, the check never passes for unknown metadata.
+fn check_permissions(metadata: &Metadata) -> std::io::Result<()> {
+    #[cfg(unix)]
+    let unknown = |what: &str| {
+        std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("the {what} of the file is unknown, unable to verify its permissions"),
+        )
+    };
+
+    #[cfg(unix)]
+    let mode = Some(metadata.mode().ok_or_else(|| unknown("mode"))?);
+    #[cfg(not(unix))]
+    let mode = metadata.mode();
+
+    if let Some(mode) = mode.filter(|mode| mode & 0o007 != 0) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!(
+                "file with permissions {:04o} is accessible by other users, \
+                restrict the permissions, e.g. with `chmod o-rwx`",
+                mode & 0o777
+            ),
+        ));
+    }
+
+    #[cfg(unix)]
+    {
+        let owner = metadata.owner().ok_or_else(|| unknown("owner"))?;
+        // SAFETY: `geteuid` is always successful and has no side effects.
+        let uid = unsafe { libc::geteuid() };
+        if owner != uid {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!("file is owned by user {owner}, but the current user is {uid}"),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Converts a `file://` URI into the path it references.
+///
+/// Only local URIs, with an empty host or `localhost`, are supported.
+fn file_uri(path: &Path) -> Option<PathBuf> {
+    let path = path.to_str()?.strip_prefix("file://")?;
+    let path = path.strip_prefix("localhost").unwrap_or(path);
+    if !path.starts_with('/') {
+        return None;
+    }
+
+    // Drive letters are preceded by a `/`, e.g. `file:///C:/secrets`.
+    #[cfg(windows)]
+    let path = match path.as_bytes() {
+        [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() => &path[1..],
+        _ => path,
+    };
+
+    path_from_bytes(utils::decode_percent(path))
+}
+
+/// Converts raw bytes into a path, returns `None` if the bytes are not a valid path.
+fn path_from_bytes(bytes: Vec<u8>) -> Option<PathBuf> {
+    #[cfg(unix)]
+    {
+        use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+        Some(PathBuf::from(OsString::from_vec(bytes)))
+    }
+
+    #[cfg(target_os = "wasi")]
+    {
+        use std::{ffi::OsString, os::wasi::ffi::OsStringExt};
+        Some(PathBuf::from(OsString::from_vec(bytes)))
+    }
+
+    #[cfg(not(any(unix, target_os = "wasi")))]
+    {
+        String::from_utf8(bytes).ok().map(PathBuf::from)
+    }
 }
 
 impl Default for FileSource {
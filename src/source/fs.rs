@@ -0,0 +1,243 @@
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, HashMap},
+    hash::{BuildHasher, Hash},
+    io::Read,
+    path::{Component, Path, PathBuf},
+};
+
+/// Access to files for a [`FileSource`](super::FileSource).
+///
+/// [`StdFileSystem`] provides access to the filesystem of the operating system. Other
+/// implementations can provide files from custom storage, like an embedded flash or memory.
+///
+/// The trait is implemented for maps of paths to file contents, `HashMap<PathBuf, Vec<u8>>`
+/// or `BTreeMap<PathBuf, &str>`, which can be used as in-memory filesystems, e.g. in tests.
+///
+/// # Examples:
+///
+/// ```
+/// use std::{collections::BTreeMap, path::PathBuf};
+/// use serde_vars::FileSource;
+///
+/// let files = BTreeMap::from([(PathBuf::from("/run/secrets/token"), "secret")]);
+/// let mut source = FileSource::new()
+///     .with_file_system(files)
+///     .with_base_path("/run/secrets");
+///
+/// let mut de = serde_json::Deserializer::from_str(r#""${token}""#);
+/// let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, "secret");
+/// ```
+pub trait FileSystem {
+    /// Reads the entire contents of the file at `path`.
+    fn read(&self, path: &Path) -> std::io::Result<Vec<u8>>;
+
+    /// Reads the contents of the file at `path`, stopping after more than `max` bytes.
+    ///
+    /// Used to limit the memory used for files without a reliable size in their [`Metadata`].
+    /// Callers reject contents longer than `max` bytes. The default implementation reads
+    /// the entire file using [`FileSystem::read`].
+    fn read_limited(&self, path: &Path, max: usize) -> std::io::Result<Vec<u8>> {
+        let _ = max;
+        self.read(path)
+    }
+
+    /// Reads the entire contents of the file at `path` into a string.
+    ///
+    /// The default implementation reads the file using [`FileSystem::read`] and fails with
+    /// [`std::io::ErrorKind::InvalidData`] if the contents are not valid UTF-8.
+    fn read_to_string(&self, path: &Path) -> std::io::Result<String> {
+        String::from_utf8(self.read(path)?).map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            )
+        })
+    }
+
+    /// Returns the [`Metadata`] of the file at `path`.
+    fn metadata(&self, path: &Path) -> std::io::Result<Metadata>;
+
+    /// Returns the canonical, absolute form of `path`.
+    ///
+    /// Used to confine files to base paths. The default implementation only normalizes the path,
+    /// it removes `.` and resolves `..` components, without accessing the filesystem.
+    fn canonicalize(&self, path: &Path) -> std::io::Result<PathBuf> {
+        let mut result = PathBuf::new();
+        for component in path.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => match result.components().next_back() {
+                    Some(Component::Normal(_)) => {
+                        result.pop();
+                    }
+                    Some(Component::RootDir | Component::Prefix(_)) => {}
+                    _ => result.push(".."),
+                },
+                component => result.push(component),
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Metadata of a file, returned by [`FileSystem::metadata`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Metadata {
+    len: u64,
+    owner: Option<u32>,
+    mode: Option<u32>,
+}
+
+impl Metadata {
+    /// Creates [`Metadata`] for a file of `len` bytes.
+    pub fn new(len: u64) -> Self {
+        Self {
+            len,
+            owner: None,
+            mode: None,
+        }
+    }
+
+    /// Configures the user id of the owner of the file.
+    pub fn with_owner(mut self, uid: u32) -> Self {
+        self.owner = Some(uid);
+        self
+    }
+
+    /// Configures the Unix permission bits of the file, e.g. `0o600`.
+    pub fn with_mode(mut self, mode: u32) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Returns the size of the file in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if the file is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the user id of the owner of the file, if known.
+    pub fn owner(&self) -> Option<u32> {
+        self.owner
+    }
+
+    /// Returns the Unix permission bits of the file, if known.
+    pub fn mode(&self) -> Option<u32> {
+        self.mode
+    }
+}
+
+/// The filesystem of the operating system, using [`std::fs`].
+///
+/// This is the default [`FileSystem`] of a [`FileSource`](super::FileSource).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StdFileSystem;
+
+impl FileSystem for StdFileSystem {
+    fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+
+    fn read_limited(&self, path: &Path, max: usize) -> std::io::Result<Vec<u8>> {
+        let mut value = Vec::new();
+        std::fs::File::open(path)?
+            .take(max as u64 + 1)
+            .read_to_end(&mut value)?;
+        Ok(value)
+    }
+
+    fn read_to_string(&self, path: &Path) -> std::io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+        let metadata = std::fs::metadata(path)?;
+        let result = Metadata::new(metadata.len());
+
+        #[cfg(unix)]
+        let result = {
+            use std::os::unix::fs::MetadataExt;
+            result
+                .with_owner(metadata.uid())
+                .with_mode(metadata.mode() & 0o7777)
+        };
+
+        Ok(result)
+    }
+
+    fn canonicalize(&self, path: &Path) -> std::io::Result<PathBuf> {
+        match path.as_os_str().is_empty() {
+            true => Path::new(".").canonicalize(),
+            false => path.canonicalize(),
+        }
+    }
+}
+
+impl<T> FileSystem for &T
+where
+    T: FileSystem + ?Sized,
+{
+    fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        (**self).read(path)
+    }
+
+    fn read_limited(&self, path: &Path, max: usize) -> std::io::Result<Vec<u8>> {
+        (**self).read_limited(path, max)
+    }
+
+    fn read_to_string(&self, path: &Path) -> std::io::Result<String> {
+        (**self).read_to_string(path)
+    }
+
+    fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+        (**self).metadata(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> std::io::Result<PathBuf> {
+        (**self).canonicalize(path)
+    }
+}
+
+impl<K, V, S> FileSystem for HashMap<K, V, S>
+where
+    K: Borrow<Path> + Hash + Eq,
+    V: AsRef<[u8]>,
+    S: BuildHasher,
+{
+    fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        map_file(self.get(path)).map(|value| value.to_vec())
+    }
+
+    fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+        map_file(self.get(path)).map(|value| Metadata::new(value.len() as u64))
+    }
+}
+
+impl<K, V> FileSystem for BTreeMap<K, V>
+where
+    K: Borrow<Path> + Ord,
+    V: AsRef<[u8]>,
+{
+    fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        map_file(self.get(path)).map(|value| value.to_vec())
+    }
+
+    fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+        map_file(self.get(path)).map(|value| Metadata::new(value.len() as u64))
+    }
+}
+
+fn map_file<V>(value: Option<&V>) -> std::io::Result<&[u8]>
+where
+    V: AsRef<[u8]>,
+{
+    value
+        .map(AsRef::as_ref)
+        .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::NotFound))
+}
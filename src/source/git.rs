@@ -0,0 +1,143 @@
+use std::{borrow::Cow, collections::HashMap, path::PathBuf, process::Command};
+
+use crate::source::{StringLookup, StringSource};
+
+/// A [`StringLookup`] which uses values of the Git configuration.
+///
+/// Variables name a configuration key, e.g. `${user.email}` or `${remote.origin.url}`, and are
+/// looked up with `git config --get`. This resolves the configuration like Git itself does,
+/// including the system, global and repository configuration, includes and environment
+/// variables like `GIT_CONFIG_GLOBAL`. If a key is set multiple times, the last value wins.
+///
+/// By default the repository of the current directory is used, see [`Self::with_dir`] and
+/// [`Self::with_file`] to change where the configuration is read from.
+///
+/// Keys which are not set are reported as missing variables, invalid keys and other failures,
+/// like Git not being installed, are reported as errors. Every key is looked up only once and
+/// then cached, including failed lookups.
+///
+/// Generally used through [`GitConfigSource`].
+#[derive(Debug, Default)]
+pub struct GitConfigLookup {
+    dir: Option<PathBuf>,
+    file: Option<PathBuf>,
+    values: HashMap<String, Result<Option<Vec<u8>>, String>>,
+}
+
+impl GitConfigLookup {
+    /// Creates a [`GitConfigLookup`] for the repository of the current directory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses the configuration of the repository containing the directory `dir`.
+    pub fn with_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = Some(dir.into());
+        self
+    }
+
+    /// Only reads the configuration file `file`, e.g. `.gitmodules`.
+    ///
+    /// Relative paths are resolved against the directory configured with [`Self::with_dir`].
+    pub fn with_file(mut self, file: impl Into<PathBuf>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    /// Returns the value of the variable `v`.
+    fn value(&mut self, v: &str) -> Result<Option<&[u8]>, String> {
+        if !self.values.contains_key(v) {
+            let value = self.get(v);
+            self.values.insert(v.to_owned(), value);
+        }
+
+        match &self.values[v] {
+            Ok(value) => Ok(value.as_deref()),
+            Err(err) => Err(err.clone()),
+        }
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        let mut command = Command::new("git");
+        if let Some(dir) = &self.dir {
+            command.arg("-C").arg(dir);
+        }
+        command.arg("config");
+        if let Some(file) = &self.file {
+            command.arg("--file").arg(file);
+        }
+        command.args(["--null", "--get", "--", key]);
+
+        let output = command
+            .output()
+            .map_err(|err| format!("failed to run `git config`: {err}"))?;
+        if output.status.success() {
+            let mut value = output.stdout;
+            if value.last() == Some(&0) {
+                value.pop();
+            }
+            return Ok(Some(value));
+        }
+
+        // Git exits with `1` for keys which are not set, but also for invalid keys,
+        // which are distinguished by an error message.
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = stderr.trim();
+        match (output.status.code(), stderr.is_empty()) {
+            (Some(1), true) => Ok(None),
+            (_, true) => Err(format!("`git config` failed with {}", output.status)),
+            (_, false) => Err(stderr.strip_prefix("error: ").unwrap_or(stderr).to_owned()),
+        }
+    }
+}
+
+impl StringLookup for GitConfigLookup {
+    fn lookup(&mut self, v: &str) -> Option<Cow<'_, str>> {
+        let value = self.value(v).ok()??;
+        std::str::from_utf8(value).ok().map(Cow::Borrowed)
+    }
+
+    fn lookup_bytes(&mut self, v: &str) -> Option<Cow<'_, [u8]>> {
+        self.value(v).ok()?.map(Cow::Borrowed)
+    }
+
+    fn lookup_error(&mut self, v: &str) -> Option<String> {
+        self.value(v).err()
+    }
+}
+
+/// A source which uses values of the Git configuration.
+///
+/// Useful for developer tooling, which seeds its configuration from the ambient repository.
+///
+/// See [`GitConfigLookup`] for how values are looked up and the [`crate`] and [`StringSource`]
+/// documentation for more details.
+///
+/// # Examples:
+///
+/// ```no_run
+/// use serde_vars::source::GitConfigSource;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///     author: String,
+///     upstream: String,
+/// }
+///
+/// let mut source = GitConfigSource::default();
+///
+/// let mut de = serde_json::Deserializer::from_str(
+///     r#"{"author": "${user.email}", "upstream": "${remote.origin.url}"}"#,
+/// );
+/// let config: Config = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// ```
+pub type GitConfigSource = StringSource<GitConfigLookup>;
+
+impl GitConfigSource {
+    /// Creates a [`GitConfigSource`] for the repository containing the directory `dir`.
+    ///
+    /// See [`GitConfigLookup::with_dir`].
+    pub fn for_dir(dir: impl Into<PathBuf>) -> Self {
+        StringSource::new(GitConfigLookup::new().with_dir(dir))
+    }
+}
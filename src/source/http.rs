@@ -0,0 +1,287 @@
+use std::{borrow::Cow, collections::HashMap, fmt, time::Duration};
+
+use crate::source::{utils, StringLookup, StringSource};
+
+/// A [`StringLookup`] which uses values requested from an HTTP(S) endpoint.
+///
+/// The name of the variable is inserted into a URL template in place of `{name}`, e.g. with the
+/// template `https://config.internal/v1/values/{name}` the variable `${db/password}` requests
+/// `https://config.internal/v1/values/db/password`. The name is percent encoded, except for `/`,
+/// which allows variables to address nested paths.
+///
+/// The body of the response is the value of the variable. For endpoints responding with JSON,
+/// a field can be selected using a [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901), see
+/// [`Self::with_pointer`].
+///
+/// Responses with the status `404 Not Found` are reported as missing variables, other
+/// unsuccessful responses and failed requests are reported as errors. Successful and
+/// `404 Not Found` responses are cached, every URL is only requested once until the cache is
+/// cleared with [`Self::clear`]. Failed requests are not cached and requested again by the next
+/// lookup, which allows a [`RetrySource`](crate::source::RetrySource) to recover from
+/// temporary failures.
+///
+/// Generally used through [`HttpSource`].
+pub struct HttpLookup {
+    url: String,
+    headers: Vec<(String, String)>,
+    timeout: Duration,
+    pointer: Option<String>,
+    root_certificates: Option<Vec<u8>>,
+    accept_invalid_certificates: bool,
+    agent: Option<Result<ureq::Agent, String>>,
+    responses: HashMap<String, Option<Vec<u8>>>,
+    /// The URL and error of the last failed request, reported by [`StringLookup::lookup_error`].
+    failed: Option<(String, String)>,
+}
+
+impl HttpLookup {
+    /// Creates an [`HttpLookup`] for the URL template `url`.
+    ///
+    /// By default requests time out after 30 seconds.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            headers: Vec::new(),
+            timeout: Duration::from_secs(30),
+            pointer: None,
+            root_certificates: None,
+            accept_invalid_certificates: false,
+            agent: None,
+            responses: HashMap::new(),
+            failed: None,
+        }
+    }
+
+    /// Adds the header `name` with the value `value` to all requests, e.g. for authentication.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Changes the timeout of requests, including connecting and reading the response.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self.agent = None;
+        self
+    }
+
+    /// Selects the value from a field of a JSON response, using the JSON Pointer `pointer`.
+    ///
+    /// Like the URL template, the pointer can contain `{name}`, which is replaced with the
+    /// escaped name of the variable. This allows a single JSON document to provide all values,
+    /// e.g. with the URL `https://config.internal/v1/values` and the pointer `/data/{name}`.
+    ///
+    /// Strings are used as is, numbers and booleans as their JSON representation. Fields which
+    /// do not exist or are `null` are reported as missing variables.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use serde_vars::source::{HttpLookup, HttpSource};
+    ///
+    /// let mut source = HttpSource::new(
+    ///     HttpLookup::new("https://vault.internal/v1/secret/data/{name}")
+    ///         .with_header("X-Vault-Token", "s.token")
+    ///         .with_pointer("/data/data/value"),
+    /// );
+    /// ```
+    pub fn with_pointer(mut self, pointer: impl Into<String>) -> Self {
+        self.pointer = Some(pointer.into());
+        self
+    }
+
+    /// Trusts the PEM encoded root certificates `pem`, instead of the default root certificates.
+    ///
+    /// Invalid certificates are reported as errors of the variables looked up.
+    pub fn with_root_certificates(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates = Some(pem.into());
+        self.agent = None;
+        self
+    }
+
+    /// Disables the verification of TLS certificates.
+    ///
+    /// This is insecure and should only be used for local development.
+    pub fn with_accept_invalid_certificates(mut self, accept: bool) -> Self {
+        self.accept_invalid_certificates = accept;
+        self.agent = None;
+        self
+    }
+
+    /// Removes all cached responses.
+    ///
+    /// Variables are requested again on their next lookup, which picks up changed values.
+    pub fn clear(&mut self) {
+        self.responses.clear();
+        self.failed = None;
+    }
+
+    /// Returns the URL of the variable `v`.
+    fn url(&self, v: &str) -> String {
+        self.url.replace("{name}", &utils::encode_percent(v, b"/"))
+    }
+
+    /// Returns the value of the variable `v`.
+    fn value(&mut self, v: &str) -> Result<Option<Cow<'_, [u8]>>, String> {
+        let url = self.url(v);
+        if !self.responses.contains_key(&url) {
+            match self.request(&url) {
+                Ok(response) => self.responses.insert(url.clone(), response),
+                Err(err) => {
+                    self.failed = Some((url, err.clone()));
+                    return Err(err);
+                }
+            };
+        }
+
+        let Some(body) = &self.responses[&url] else {
+            return Ok(None);
+        };
+        let Some(pointer) = &self.pointer else {
+            return Ok(Some(Cow::Borrowed(body)));
+        };
+
+        let document: serde_json::Value = serde_json::from_slice(body)
+            .map_err(|err| format!("`{url}` responded with invalid JSON: {err}"))?;
+        let pointer = pointer.replace("{name}", &v.replace('~', "~0").replace('/', "~1"));
+        let value = match document.pointer(&pointer) {
+            None | Some(serde_json::Value::Null) => return Ok(None),
+            Some(serde_json::Value::String(value)) => value.clone(),
+            Some(value) => value.to_string(),
+        };
+        Ok(Some(Cow::Owned(value.into_bytes())))
+    }
+
+    fn request(&mut self, url: &str) -> Result<Option<Vec<u8>>, String> {
+        let agent = self.agent.get_or_insert_with(|| {
+            build_agent(
+                self.timeout,
+                self.root_certificates.as_deref(),
+                self.accept_invalid_certificates,
+            )
+        });
+        let agent = agent.as_ref().map_err(Clone::clone)?;
+
+        let mut request = agent.get(url);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+
+        let response = request
+            .call()
+            .map_err(|err| format!("request to `{url}` failed: {err}"))?;
+        let status = response.status();
+        if status == 404 {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(format!("`{url}` responded with {status}"));
+        }
+
+        response
+            .into_body()
+            .read_to_vec()
+            .map(Some)
+            .map_err(|err| format!("request to `{url}` failed: {err}"))
+    }
+}
+
+impl fmt::Debug for HttpLookup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Headers and responses may contain credentials and secrets.
+        f.debug_struct("HttpLookup")
+            .field("url", &self.url)
+            .field("timeout", &self.timeout)
+            .field("pointer", &self.pointer)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StringLookup for HttpLookup {
+    fn lookup(&mut self, v: &str) -> Option<Cow<'_, str>> {
+        match self.value(v).ok()?? {
+            Cow::Borrowed(value) => std::str::from_utf8(value).ok().map(Cow::Borrowed),
+            Cow::Owned(value) => String::from_utf8(value).ok().map(Cow::Owned),
+        }
+    }
+
+    fn lookup_bytes(&mut self, v: &str) -> Option<Cow<'_, [u8]>> {
+        self.value(v).ok()?
+    }
+
+    fn lookup_error(&mut self, v: &str) -> Option<String> {
+        // Failed requests are not cached, report the failure without requesting the URL again.
+        let url = self.url(v);
+        match self.failed.take_if(|(failed, _)| *failed == url) {
+            Some((_, err)) => Some(err),
+            None => self.value(v).err(),
+        }
+    }
+}
+
+/// A source which uses values requested from an HTTP(S) endpoint.
+///
+/// See [`HttpLookup`] for how values are requested and the [`crate`] and [`StringSource`]
+/// documentation for more details.
+///
+/// # Examples:
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use serde_vars::source::{HttpLookup, HttpSource};
+///
+/// let mut source = HttpSource::new(
+///     HttpLookup::new("https://config.internal/v1/values/{name}")
+///         .with_header("Authorization", "Bearer token")
+///         .with_timeout(Duration::from_secs(5)),
+/// );
+///
+/// let mut de = serde_json::Deserializer::from_str(r#""${db/password}""#);
+/// let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// ```
+pub type HttpSource = StringSource<HttpLookup>;
+
+impl HttpSource {
+    /// Creates an [`HttpSource`] for the URL template `url`.
+    ///
+    /// See [`HttpLookup::new`].
+    pub fn from_url(url: impl Into<String>) -> Self {
+        StringSource::new(HttpLookup::new(url))
+    }
+}
+
+fn build_agent(
+    timeout: Duration,
+    root_certificates: Option<&[u8]>,
+    accept_invalid_certificates: bool,
+) -> Result<ureq::Agent, String> {
+    let mut tls = ureq::tls::TlsConfig::builder().disable_verification(accept_invalid_certificates);
+    if let Some(pem) = root_certificates {
+        let certificates =
+            parse_certificates(pem).map_err(|err| format!("invalid root certificates: {err}"))?;
+        if certificates.is_empty() {
+            return Err("invalid root certificates: no PEM encoded certificate found".to_owned());
+        }
+        tls = tls.root_certs(ureq::tls::RootCerts::new_with_certs(&certificates));
+    }
+
+    let config = ureq::Agent::config_builder()
+        .http_status_as_error(false)
+        .timeout_global(Some(timeout))
+        .tls_config(tls.build())
+        .build();
+    Ok(config.new_agent())
+}
+
+/// Parses all certificates in the PEM encoded `pem`.
+pub(crate) fn parse_certificates(
+    pem: &[u8],
+) -> Result<Vec<ureq::tls::Certificate<'static>>, ureq::Error> {
+    ureq::tls::parse_pem(pem)
+        .filter_map(|item| match item {
+            Ok(ureq::tls::PemItem::Certificate(cert)) => Some(Ok(cert)),
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .collect()
+}
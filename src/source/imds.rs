@@ -0,0 +1,202 @@
+use std::{borrow::Cow, collections::HashMap, fmt, time::Duration};
+
+use crate::source::{StringLookup, StringSource};
+
+/// The IPv4 endpoint of the instance metadata service.
+const ENDPOINT: &str = "http://169.254.169.254";
+
+/// A [`StringLookup`] which uses the AWS EC2 instance metadata service (IMDS).
+///
+/// Variables are prefixed with the kind of metadata they look up:
+///
+/// - `meta:<path>` looks up instance metadata, e.g. `${meta:placement/region}`
+///   or `${meta:instance-id}`.
+/// - `tag:<key>` looks up a tag of the instance, e.g. `${tag:Environment}`. Tags are only
+///   available if access to tags in the instance metadata is enabled for the instance.
+///
+/// Requests use IMDSv2 session tokens and fall back to IMDSv1, if tokens are not supported.
+///
+/// Metadata which does not exist is reported as a missing variable, other failures, like
+/// not running on an EC2 instance, are reported as errors. Metadata rarely changes while an
+/// instance is running, every variable is requested only once and then cached, unless the
+/// request failed. Failed requests are repeated by the next lookup of the variable.
+///
+/// Generally used through [`ImdsSource`].
+pub struct ImdsLookup {
+    endpoint: String,
+    agent: ureq::Agent,
+    token: Option<Option<String>>,
+    values: HashMap<String, Option<String>>,
+    /// The variable and error of the last failed request.
+    failed: Option<(String, String)>,
+}
+
+impl Default for ImdsLookup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImdsLookup {
+    /// Creates an [`ImdsLookup`] for the default endpoint, `http://169.254.169.254`.
+    ///
+    /// Requests time out after 2 seconds.
+    pub fn new() -> Self {
+        Self {
+            endpoint: ENDPOINT.to_owned(),
+            agent: agent(Duration::from_secs(2)),
+            token: None,
+            values: HashMap::new(),
+            failed: None,
+        }
+    }
+
+    /// Changes the endpoint of the metadata service, e.g. to the IPv6 endpoint
+    /// `http://[fd00:ec2::254]`.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into().trim_end_matches('/').to_owned();
+        self
+    }
+
+    /// Changes the timeout of requests.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.agent = agent(timeout);
+        self
+    }
+
+    /// Removes all cached values and the session token.
+    ///
+    /// The next lookup requests a new session token and the values again, e.g. to pick up
+    /// changed tags.
+    pub fn clear(&mut self) {
+        self.token = None;
+        self.values.clear();
+        self.failed = None;
+    }
+
+    /// Returns the value of the variable `v`.
+    fn value(&mut self, v: &str) -> Result<Option<&str>, String> {
+        if !self.values.contains_key(v) {
+            match self.request(v) {
+                Ok(value) => self.values.insert(v.to_owned(), value),
+                Err(err) => {
+                    self.failed = Some((v.to_owned(), err.clone()));
+                    return Err(err);
+                }
+            };
+        }
+        Ok(self.values[v].as_deref())
+    }
+
+    fn request(&mut self, v: &str) -> Result<Option<String>, String> {
+        let path = match v.split_once(':') {
+            Some(("meta", path)) => format!("/latest/meta-data/{path}"),
+            Some(("tag", key)) => format!("/latest/meta-data/tags/instance/{key}"),
+            _ => {
+                return Err(
+                    "expected a variable of the form `meta:<path>` or `tag:<key>`".to_owned(),
+                )
+            }
+        };
+
+        if self.token.is_none() {
+            self.token = Some(token(&self.agent, &self.endpoint)?);
+        }
+        let token = self.token.as_ref().and_then(Option::as_ref);
+
+        let url = format!("{}{path}", self.endpoint);
+        let mut request = self.agent.get(&url);
+        if let Some(token) = token {
+            request = request.header("X-aws-ec2-metadata-token", token);
+        }
+
+        let response = request.call().map_err(request_failed)?;
+        let status = response.status();
+        if status == 404 {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(format!(
+                "the instance metadata service responded with {status}"
+            ));
+        }
+
+        response
+            .into_body()
+            .read_to_string()
+            .map(Some)
+            .map_err(request_failed)
+    }
+}
+
+impl fmt::Debug for ImdsLookup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The session token authenticates requests, looked up values may be sensitive.
+        f.debug_struct("ImdsLookup")
+            .field("endpoint", &self.endpoint)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StringLookup for ImdsLookup {
+    fn lookup(&mut self, v: &str) -> Option<Cow<'_, str>> {
+        self.value(v).ok()?.map(Cow::Borrowed)
+    }
+
+    fn lookup_error(&mut self, v: &str) -> Option<String> {
+        match self.failed.take_if(|(failed, _)| failed == v) {
+            Some((_, err)) => Some(err),
+            None => self.value(v).err(),
+        }
+    }
+}
+
+/// A source which uses the AWS EC2 instance metadata service (IMDS).
+///
+/// This allows a configuration to mix static values with the identity of the instance,
+/// like its region or tags.
+///
+/// See [`ImdsLookup`] for the supported variables and the [`crate`] and [`StringSource`]
+/// documentation for more details.
+///
+/// # Examples:
+///
+/// ```no_run
+/// use serde_vars::source::ImdsSource;
+///
+/// let mut source = ImdsSource::default();
+///
+/// let mut de = serde_json::Deserializer::from_str(r#"["${meta:placement/region}", "${tag:Environment}"]"#);
+/// let r: (String, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// ```
+pub type ImdsSource = StringSource<ImdsLookup>;
+
+fn agent(timeout: Duration) -> ureq::Agent {
+    ureq::Agent::config_builder()
+        .http_status_as_error(false)
+        .timeout_global(Some(timeout))
+        .build()
+        .new_agent()
+}
+
+/// Requests an IMDSv2 session token, `None` if the service only supports IMDSv1.
+fn token(agent: &ureq::Agent, endpoint: &str) -> Result<Option<String>, String> {
+    let response = agent
+        .put(format!("{endpoint}/latest/api/token"))
+        .header("X-aws-ec2-metadata-token-ttl-seconds", "21600")
+        .send_empty()
+        .map_err(request_failed)?;
+
+    match response.status().is_success() {
+        true => response
+            .into_body()
+            .read_to_string()
+            .map(Some)
+            .map_err(request_failed),
+        false => Ok(None),
+    }
+}
+
+fn request_failed(err: ureq::Error) -> String {
+    format!("request to the instance metadata service failed: {err}")
+}
@@ -0,0 +1,311 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::source::{http, secret, utils, StringLookup, StringSource};
+
+/// The directory where Kubernetes mounts the service account of a pod.
+const SERVICE_ACCOUNT: &str = "/var/run/secrets/kubernetes.io/serviceaccount";
+
+/// Keys of a Secret or ConfigMap and their decoded values.
+type Object = HashMap<String, Vec<u8>>;
+
+/// A [`StringLookup`] which uses Secrets or ConfigMaps read from the Kubernetes API.
+///
+/// Variables have the form `namespace/name/key`, e.g. `${production/database/password}` uses
+/// the key `password` of the Secret `database` in the namespace `production`. By default Secrets
+/// are looked up, ConfigMaps can be used with [`Self::with_config_maps`].
+///
+/// Objects which do not exist are reported as missing variables, other failures, like missing
+/// permissions, are reported with the response of the API. Every object is requested only once
+/// and then cached, including objects which do not exist, use [`Self::clear`] to pick up
+/// changed Secrets. Failures are not cached, the object is requested again by the next lookup.
+///
+/// Generally used through [`KubeSource`].
+pub struct KubeLookup {
+    agent: ureq::Agent,
+    url: String,
+    token: Option<Token>,
+    config_maps: bool,
+    objects: HashMap<String, Option<Object>>,
+    /// The object and error of the last failed request.
+    failed: Option<(String, String)>,
+}
+
+enum Token {
+    Static(String),
+    /// Service account tokens are rotated, the file is read again for every request.
+    File(PathBuf),
+}
+
+impl fmt::Debug for KubeLookup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Cached objects contain the values of Secrets.
+        f.debug_struct("KubeLookup")
+            .field("url", &self.url)
+            .field("token", &self.token)
+            .field("config_maps", &self.config_maps)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Static(token) => f
+                .debug_tuple("Static")
+                .field(&secret::redact_debug(true, token))
+                .finish(),
+            Self::File(path) => f.debug_tuple("File").field(path).finish(),
+        }
+    }
+}
+
+impl KubeLookup {
+    /// Creates a [`KubeLookup`] for the Kubernetes API at `url`, e.g. `http://127.0.0.1:8001`
+    /// for an API exposed with `kubectl proxy`.
+    ///
+    /// Requests are not authenticated, unless a token is configured with [`Self::with_token`].
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::with_agent(url.into(), ureq::Agent::config_builder())
+    }
+
+    /// Creates a [`KubeLookup`] from the in-cluster configuration of a pod.
+    ///
+    /// Uses the address of the API from the `KUBERNETES_SERVICE_HOST` and
+    /// `KUBERNETES_SERVICE_PORT` environment variables and authenticates with the token
+    /// and CA certificate of the service account of the pod.
+    pub fn in_cluster() -> std::io::Result<Self> {
+        let host = std::env::var("KUBERNETES_SERVICE_HOST").map_err(not_in_cluster)?;
+        let port = std::env::var("KUBERNETES_SERVICE_PORT").map_err(not_in_cluster)?;
+        let url = match host.contains(':') {
+            true => format!("https://[{host}]:{port}"),
+            false => format!("https://{host}:{port}"),
+        };
+
+        let service_account = Path::new(SERVICE_ACCOUNT);
+        let ca = std::fs::read(service_account.join("ca.crt"))?;
+        let certs = http::parse_certificates(&ca)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        let tls = ureq::tls::TlsConfig::builder()
+            .root_certs(ureq::tls::RootCerts::new_with_certs(&certs))
+            .build();
+
+        let mut lookup = Self::with_agent(url, ureq::Agent::config_builder().tls_config(tls));
+        lookup.token = Some(Token::File(service_account.join("token")));
+        Ok(lookup)
+    }
+
+    fn with_agent(
+        url: String,
+        config: ureq::config::ConfigBuilder<ureq::typestate::AgentScope>,
+    ) -> Self {
+        let config = config
+            .http_status_as_error(false)
+            .timeout_global(Some(Duration::from_secs(30)))
+            .build();
+
+        Self {
+            agent: config.new_agent(),
+            url: url.trim_end_matches('/').to_owned(),
+            token: None,
+            config_maps: false,
+            objects: HashMap::new(),
+            failed: None,
+        }
+    }
+
+    /// Authenticates requests with the bearer token `token`.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(Token::Static(token.into()));
+        self
+    }
+
+    /// Looks up keys of ConfigMaps instead of Secrets.
+    pub fn with_config_maps(mut self, config_maps: bool) -> Self {
+        self.config_maps = config_maps;
+        self
+    }
+
+    /// Removes all cached objects, they are requested again on their next lookup.
+    pub fn clear(&mut self) {
+        self.objects.clear();
+        self.failed = None;
+    }
+
+    /// Returns the value of the variable `v`, requesting its object if necessary.
+    fn value(&mut self, v: &str) -> Option<&[u8]> {
+        let (object, key) = split(v)?;
+        let object = self.object(object)?.as_ref()?;
+        object.get(key).map(Vec::as_slice)
+    }
+
+    fn object(&mut self, object: &str) -> Option<&Option<Object>> {
+        if !self.objects.contains_key(object) {
+            match self.request(object) {
+                Ok(result) => self.objects.insert(object.to_owned(), result),
+                Err(err) => {
+                    self.failed = Some((object.to_owned(), err));
+                    return None;
+                }
+            };
+        }
+        Some(&self.objects[object])
+    }
+
+    fn request(&self, object: &str) -> Result<Option<Object>, String> {
+        let (namespace, name) = object.split_once('/').unwrap_or_default();
+        let kind = match self.config_maps {
+            true => "configmaps",
+            false => "secrets",
+        };
+        let url = format!("{}/api/v1/namespaces/{namespace}/{kind}/{name}", self.url);
+
+        let mut request = self.agent.get(&url).header("Accept", "application/json");
+        if let Some(token) = &self.token {
+            let token = match token {
+                Token::Static(token) => Cow::Borrowed(token.as_str()),
+                Token::File(path) => std::fs::read_to_string(path)
+                    .map(|token| Cow::Owned(token.trim().to_owned()))
+                    .map_err(|err| {
+                        format!("failed to read the token `{}`: {err}", path.display())
+                    })?,
+            };
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+
+        let response = request.call().map_err(|err| err.to_string())?;
+        let status = response.status();
+        let body = response
+            .into_body()
+            .read_to_vec()
+            .map_err(|err| err.to_string())?;
+
+        if status == 404 {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            // Errors are returned as a `Status` object with a human readable message.
+            let message = serde_json::from_slice::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|status| status.get("message")?.as_str().map(str::to_owned))
+                .unwrap_or_else(|| String::from_utf8_lossy(&body).into_owned());
+            return Err(format!(
+                "the Kubernetes API responded with {status}: {message}"
+            ));
+        }
+
+        let object: serde_json::Value = serde_json::from_slice(&body)
+            .map_err(|err| format!("the Kubernetes API responded with invalid JSON: {err}"))?;
+        let field = |name: &str| {
+            object
+                .get(name)
+                .and_then(serde_json::Value::as_object)
+                .into_iter()
+                .flatten()
+                .filter_map(|(key, value)| Some((key.clone(), value.as_str()?)))
+        };
+
+        let mut values = Object::new();
+        for (key, value) in field("binaryData") {
+            values.insert(key, decode(value)?);
+        }
+        for (key, value) in field("data") {
+            let value = match self.config_maps {
+                true => value.as_bytes().to_vec(),
+                false => decode(value)?,
+            };
+            values.insert(key, value);
+        }
+        Ok(Some(values))
+    }
+}
+
+impl StringLookup for KubeLookup {
+    fn lookup(&mut self, v: &str) -> Option<Cow<'_, str>> {
+        std::str::from_utf8(self.value(v)?).ok().map(Cow::Borrowed)
+    }
+
+    fn lookup_bytes(&mut self, v: &str) -> Option<Cow<'_, [u8]>> {
+        self.value(v).map(Cow::Borrowed)
+    }
+
+    fn lookup_error(&mut self, v: &str) -> Option<String> {
+        let Some((object, _)) = split(v) else {
+            return Some("expected a variable of the form `namespace/name/key`".to_owned());
+        };
+        let (_, err) = self.failed.take_if(|(failed, _)| failed == object)?;
+        Some(err)
+    }
+}
+
+/// A source which uses Secrets or ConfigMaps read from the Kubernetes API.
+///
+/// Useful when secrets are not mounted into the pod as files, otherwise see
+/// [`DirSource`](crate::DirSource).
+///
+/// See [`KubeLookup`] for the supported variables and the [`crate`] and [`StringSource`]
+/// documentation for more details.
+///
+/// # Examples:
+///
+/// ```no_run
+/// use serde_vars::source::KubeSource;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///     password: String,
+/// }
+///
+/// let mut source = KubeSource::in_cluster().unwrap();
+///
+/// let mut de = serde_json::Deserializer::from_str(
+///     r#"{"password": "${production/database/password}"}"#,
+/// );
+/// let config: Config = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// ```
+pub type KubeSource = StringSource<KubeLookup>;
+
+impl KubeSource {
+    /// Creates a [`KubeSource`] from the in-cluster configuration of a pod.
+    ///
+    /// See [`KubeLookup::in_cluster`].
+    pub fn in_cluster() -> std::io::Result<Self> {
+        KubeLookup::in_cluster().map(StringSource::new)
+    }
+}
+
+fn not_in_cluster(_: std::env::VarError) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "not running in a Kubernetes cluster, `KUBERNETES_SERVICE_HOST` and `KUBERNETES_SERVICE_PORT` must be set",
+    )
+}
+
+/// Splits a variable into the `namespace/name` of its object and its key.
+fn split(v: &str) -> Option<(&str, &str)> {
+    let (object, key) = v.rsplit_once('/')?;
+    let (namespace, name) = object.split_once('/')?;
+
+    let is_name = |s: &str| {
+        !s.is_empty()
+            && s.bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'.')
+    };
+    let is_key = |s: &str| {
+        !s.is_empty()
+            && s.bytes()
+                .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+    };
+
+    (is_name(namespace) && is_name(name) && is_key(key)).then_some((object, key))
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, String> {
+    utils::decode_base64(value)
+        .ok_or_else(|| "the Kubernetes API responded with invalid base64 data".to_owned())
+}
@@ -0,0 +1,300 @@
+use std::borrow::Cow;
+
+use serde::de;
+
+use crate::source::{utils, Any, Source, Structured, Variable};
+
+/// A [`Source`] which rewrites the names of variables before they are expanded by another source.
+///
+/// The name of every variable, everything between the variable prefix and suffix, is passed
+/// to a function and the variable with the returned name is expanded by the wrapped source.
+/// This allows adding environment specific prefixes, aliasing legacy names or normalizing
+/// names without touching the configuration. Strings which do not reference a variable are
+/// passed on unchanged.
+///
+/// The wrapped source should use the same variable prefix and suffix as the [`MapNames`],
+/// by default `${` and `}`.
+///
+/// Usually created with [`Source::map_names`].
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::{source::Source, MapSource};
+///
+/// let mut source = MapSource::default()
+///     .insert("PROD_DATABASE_URL", "postgres://db.internal")
+///     .map_names(|name| format!("PROD_{}", name.replace('-', "_").to_uppercase()));
+///
+/// let mut de = serde_json::Deserializer::from_str(r#""${database-url}""#);
+/// let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, "postgres://db.internal");
+/// ```
+#[derive(Debug)]
+pub struct MapNames<S, F> {
+    source: S,
+    f: F,
+    variable: Variable,
+}
+
+impl<S, F> MapNames<S, F>
+where
+    F: Fn(&str) -> String,
+{
+    /// Creates a [`MapNames`], which rewrites the names of variables with `f` before they are
+    /// expanded by `source`.
+    pub fn new(source: S, f: F) -> Self {
+        Self {
+            source,
+            f,
+            variable: Default::default(),
+        }
+    }
+
+    /// Changes the variable prefix.
+    pub fn with_variable_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.variable.prefix = prefix.into();
+        self
+    }
+
+    /// Changes the variable suffix.
+    pub fn with_variable_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.variable.suffix = suffix.into();
+        self
+    }
+
+    /// Returns the wrapped source.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+
+    /// Returns the variable with the rewritten name, if `v` is a variable.
+    fn rename(&self, v: &str) -> Option<String> {
+        let var = self.variable.parse_str(v)?;
+        Some(self.variable.fmt((self.f)(var)).to_string())
+    }
+
+    /// Returns the variable with the rewritten name, only rewriting the prefix of map variables.
+    fn rename_map(&self, v: &str) -> Option<String> {
+        let var = self.variable.parse_str(v)?;
+        let name = match var.strip_prefix(utils::MAP_PREFIX) {
+            Some(prefix) => format!("{}{}", utils::MAP_PREFIX, (self.f)(prefix)),
+            None => (self.f)(var),
+        };
+        Some(self.variable.fmt(name).to_string())
+    }
+
+    fn rename_bytes(&self, v: &[u8]) -> Option<Vec<u8>> {
+        // Only strings can reference variables.
+        self.rename(std::str::from_utf8(v).ok()?)
+            .map(String::into_bytes)
+    }
+}
+
+impl<S, F> Source for MapNames<S, F>
+where
+    S: Source,
+    F: Fn(&str) -> String,
+{
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        match self.rename(v) {
+            Some(v) => self.source.expand_bool(&v),
+            None => self.source.expand_bool(v),
+        }
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        match self.rename(v) {
+            Some(v) => self.source.expand_i8(&v),
+            None => self.source.expand_i8(v),
+        }
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        match self.rename(v) {
+            Some(v) => self.source.expand_i16(&v),
+            None => self.source.expand_i16(v),
+        }
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        match self.rename(v) {
+            Some(v) => self.source.expand_i32(&v),
+            None => self.source.expand_i32(v),
+        }
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        match self.rename(v) {
+            Some(v) => self.source.expand_i64(&v),
+            None => self.source.expand_i64(v),
+        }
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        match self.rename(v) {
+            Some(v) => self.source.expand_i128(&v),
+            None => self.source.expand_i128(v),
+        }
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        match self.rename(v) {
+            Some(v) => self.source.expand_u8(&v),
+            None => self.source.expand_u8(v),
+        }
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        match self.rename(v) {
+            Some(v) => self.source.expand_u16(&v),
+            None => self.source.expand_u16(v),
+        }
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        match self.rename(v) {
+            Some(v) => self.source.expand_u32(&v),
+            None => self.source.expand_u32(v),
+        }
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        match self.rename(v) {
+            Some(v) => self.source.expand_u64(&v),
+            None => self.source.expand_u64(v),
+        }
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        match self.rename(v) {
+            Some(v) => self.source.expand_u128(&v),
+            None => self.source.expand_u128(v),
+        }
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        match self.rename(v) {
+            Some(v) => self.source.expand_f32(&v),
+            None => self.source.expand_f32(v),
+        }
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        match self.rename(v) {
+            Some(v) => self.source.expand_f64(&v),
+            None => self.source.expand_f64(v),
+        }
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        match self.rename(&v) {
+            Some(v) => self.source.expand_str(Cow::Owned(v)),
+            None => self.source.expand_str(v),
+        }
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        match self.rename_bytes(&v) {
+            Some(v) => self.source.expand_bytes(Cow::Owned(v)),
+            None => self.source.expand_bytes(v),
+        }
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        match self.rename(&v) {
+            Some(v) => self.source.expand_any(Cow::Owned(v)),
+            None => self.source.expand_any(v),
+        }
+    }
+
+    fn expand_structured<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        match self.rename(&v) {
+            Some(v) => self.source.expand_structured(Cow::Owned(v)),
+            None => self.source.expand_structured(v),
+        }
+    }
+
+    fn expand_map<E>(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        match self.rename_map(v) {
+            Some(v) => self.source.expand_map(&v),
+            None => self.source.expand_map(v),
+        }
+    }
+
+    fn expands_structured(&self) -> bool {
+        self.source.expands_structured()
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        match self.rename(v) {
+            Some(v) => self.source.contains_variable(&v),
+            None => self.source.contains_variable(v),
+        }
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        match self.rename(v) {
+            Some(v) => self.source.references_variable(&v),
+            None => self.source.references_variable(v),
+        }
+    }
+
+    fn name(&self) -> String {
+        self.source.name()
+    }
+}
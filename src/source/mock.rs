@@ -0,0 +1,307 @@
+use std::borrow::Cow;
+
+use serde::de;
+
+use crate::source::{Any, Source, StringSource, Variable};
+
+/// A [`Source`] for tests, which expands only expected variables to canned values.
+///
+/// Variables are declared with [`Self::expect`], which returns an [`Expectation`] to configure
+/// the value and how often the variable is expected to be expanded. Values are converted to
+/// the requested types like a [`StringSource`] converts them.
+///
+/// Expanding a variable, which is not expected or expanded more often than expected, fails.
+/// Expected variables, which are not expanded as often as expected, are reported when the
+/// source is verified with [`Self::verify`] or dropped, by panicking. Variables are matched
+/// by the exact name between the variable prefix and suffix.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::source::MockSource;
+///
+/// let mut source = MockSource::new();
+/// source.expect("DB_HOST").returns("1.2.3.4").times(1);
+/// source.expect("DB_PORT").returns("5432");
+///
+/// let mut de = serde_json::Deserializer::from_str(r#"["${DB_HOST}", "${DB_PORT}", "${DB_PORT}"]"#);
+/// let r: (String, u16, u16) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, ("1.2.3.4".to_owned(), 5432, 5432));
+///
+/// source.verify();
+/// ```
+#[derive(Debug, Default)]
+pub struct MockSource {
+    expectations: Vec<Expectation>,
+    unexpected: Vec<String>,
+    variable: Variable,
+}
+
+/// An expected variable of a [`MockSource`].
+///
+/// Without a value the variable is expected, but does not exist. Without a number of times
+/// the variable is expected to be expanded at least once.
+#[derive(Debug)]
+pub struct Expectation {
+    name: String,
+    value: Option<String>,
+    times: Option<usize>,
+    calls: usize,
+}
+
+impl Expectation {
+    /// Expands the variable to `value`.
+    pub fn returns(&mut self, value: impl Into<String>) -> &mut Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// Expects the variable to be expanded exactly `times` times.
+    pub fn times(&mut self, times: usize) -> &mut Self {
+        self.times = Some(times);
+        self
+    }
+}
+
+impl MockSource {
+    /// Creates a [`MockSource`] without any expected variables.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Changes the variable prefix.
+    pub fn with_variable_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.variable.prefix = prefix.into();
+        self
+    }
+
+    /// Changes the variable suffix.
+    pub fn with_variable_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.variable.suffix = suffix.into();
+        self
+    }
+
+    /// Expects the variable `name`.
+    ///
+    /// Expecting a variable again replaces the previous expectation.
+    pub fn expect(&mut self, name: impl Into<String>) -> &mut Expectation {
+        let name = name.into();
+        self.expectations
+            .retain(|expectation| expectation.name != name);
+        self.expectations.push(Expectation {
+            name,
+            value: None,
+            times: None,
+            calls: 0,
+        });
+
+        let last = self.expectations.len() - 1;
+        &mut self.expectations[last]
+    }
+
+    /// Verifies all expected variables were expanded as often as expected and no unexpected
+    /// variables were expanded.
+    ///
+    /// All expectations are removed, the source can be reused with new expectations.
+    ///
+    /// # Panics:
+    ///
+    /// Panics with a description of all violated expectations.
+    #[allow(clippy::panic)]
+    pub fn verify(&mut self) {
+        let mut errors = std::mem::take(&mut self.unexpected)
+            .into_iter()
+            .map(|name| format!("unexpected variable `{}`", self.variable.fmt(name)))
+            .collect::<Vec<_>>();
+
+        for expectation in std::mem::take(&mut self.expectations) {
+            let var = self.variable.fmt(&expectation.name);
+            match expectation.times {
+                Some(times) if expectation.calls != times => errors.push(format!(
+                    "variable `{var}` expected {times} times, but expanded {} times",
+                    expectation.calls
+                )),
+                None if expectation.calls == 0 => {
+                    errors.push(format!("variable `{var}` expected, but never expanded"))
+                }
+                _ => {}
+            }
+        }
+
+        if !errors.is_empty() {
+            panic!("mock source expectations violated:\n{}", errors.join("\n"));
+        }
+    }
+
+    /// Returns the source expanding `v`, after checking the expectations of the variable.
+    fn source<E>(&mut self, v: &str) -> Result<StringSource<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        let mut values = Vec::new();
+
+        if let Some(name) = self.variable.parse_str(v) {
+            let Some(expectation) = self.expectations.iter_mut().find(|e| e.name == name) else {
+                self.unexpected.push(name.to_owned());
+                return Err(E::custom(format!(
+                    "got variable `{v}`, but it was not expected"
+                )));
+            };
+
+            expectation.calls += 1;
+            if let Some(times) = expectation.times.filter(|&times| expectation.calls > times) {
+                return Err(E::custom(format!(
+                    "got variable `{v}`, but it was expected to be expanded {times} times"
+                )));
+            }
+
+            if let Some(value) = &expectation.value {
+                values.push((expectation.name.clone(), value.clone()));
+            }
+        }
+
+        Ok(StringSource::new(values)
+            .with_variable_prefix(self.variable.prefix.clone())
+            .with_variable_suffix(self.variable.suffix.clone()))
+    }
+}
+
+impl Drop for MockSource {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            self.verify();
+        }
+    }
+}
+
+impl Source for MockSource {
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        self.source(v)?.expand_bool(v)
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        self.source(v)?.expand_i8(v)
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        self.source(v)?.expand_i16(v)
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        self.source(v)?.expand_i32(v)
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        self.source(v)?.expand_i64(v)
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        self.source(v)?.expand_i128(v)
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        self.source(v)?.expand_u8(v)
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        self.source(v)?.expand_u16(v)
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        self.source(v)?.expand_u32(v)
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        self.source(v)?.expand_u64(v)
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        self.source(v)?.expand_u128(v)
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        self.source(v)?.expand_f32(v)
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        self.source(v)?.expand_f64(v)
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        self.source(&v)?.expand_str(v)
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        // Only strings can reference variables.
+        let var = std::str::from_utf8(&v).unwrap_or_default();
+        self.source(var)?.expand_bytes(v)
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        self.source(&v)?.expand_any(v)
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        let Some(name) = self.variable.parse_str(v) else {
+            return true;
+        };
+
+        match self.expectations.iter().find(|e| e.name == name) {
+            Some(expectation) => expectation.value.is_some(),
+            // Unexpected variables are reported by the expansion.
+            None => true,
+        }
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        Some(self.variable.parse_str(v).is_some())
+    }
+}
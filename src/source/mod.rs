@@ -4,12 +4,96 @@ use std::borrow::Cow;
 
 use serde::de;
 
+mod boxed;
+mod cached;
+mod case;
+mod chain;
+#[cfg(feature = "clap")]
+mod cli;
+mod composite;
+mod defaults;
+mod dir;
+#[cfg(feature = "json")]
+mod document;
+mod dotenv;
+mod environment_file;
 mod file;
+mod fs;
+mod git;
+#[cfg(feature = "http")]
+mod http;
+#[cfg(feature = "imds")]
+mod imds;
+#[cfg(feature = "kube")]
+mod kube;
+mod map_names;
+mod mock;
+mod modifier;
+mod multi;
+mod namespaced;
+mod properties;
+mod recording;
+mod retry;
+mod routing;
+mod secret;
+#[cfg(feature = "secret-service")]
+mod secret_service;
+#[cfg(feature = "sqlite")]
+mod sqlite;
 mod string;
+mod structured;
+mod sync;
+mod timeout;
+#[cfg(feature = "tracing")]
+mod traced;
+mod transform;
 mod utils;
+mod variable;
 
+pub use self::boxed::*;
+pub use self::cached::*;
+pub use self::case::*;
+pub use self::chain::*;
+#[cfg(feature = "clap")]
+pub use self::cli::*;
+pub use self::composite::*;
+pub use self::defaults::*;
+pub use self::dir::*;
+#[cfg(feature = "json")]
+pub use self::document::*;
+pub use self::dotenv::*;
+pub use self::environment_file::*;
 pub use self::file::*;
+pub use self::fs::*;
+pub use self::git::*;
+#[cfg(feature = "http")]
+pub use self::http::*;
+#[cfg(feature = "imds")]
+pub use self::imds::*;
+#[cfg(feature = "kube")]
+pub use self::kube::*;
+pub use self::map_names::*;
+pub use self::mock::*;
+pub use self::multi::*;
+pub use self::namespaced::*;
+pub use self::properties::*;
+pub use self::recording::*;
+pub use self::retry::*;
+pub use self::routing::*;
+pub use self::secret::*;
+#[cfg(feature = "secret-service")]
+pub use self::secret_service::*;
+#[cfg(feature = "sqlite")]
+pub use self::sqlite::*;
 pub use self::string::*;
+pub use self::structured::*;
+pub use self::sync::*;
+pub use self::timeout::*;
+#[cfg(feature = "tracing")]
+pub use self::traced::*;
+pub use self::transform::*;
+pub use self::utils::DurationUnit;
+pub use self::variable::Variable;
 
 /// A [`Source`] expands a variable string into a concrete value.
 pub trait Source {
@@ -38,6 +122,16 @@ pub trait Source {
     where
         E: de::Error;
 
+    /// Expands a variable string to an `i128`.
+    ///
+    /// The default implementation expands the variable to an `i64`.
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        self.expand_i64(v).map(i128::from)
+    }
+
     /// Expands a variable string to an `u8`.
     fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
     where
@@ -58,6 +152,16 @@ pub trait Source {
     where
         E: de::Error;
 
+    /// Expands a variable string to an `u128`.
+    ///
+    /// The default implementation expands the variable to an `u64`.
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        self.expand_u64(v).map(u128::from)
+    }
+
     /// Expands a variable string to a `f32`.
     fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
     where
@@ -96,28 +200,398 @@ pub trait Source {
     fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
     where
         E: de::Error;
+
+    /// Expands a variable string to a [`Structured`] value, like a map or a sequence.
+    ///
+    /// Only called for maps, structs and sequences, if [`Source::expands_structured`]
+    /// returns `true`.
+    ///
+    /// If the string `v`, does not contain a variable reference the method
+    /// is supposed to return the original string.
+    fn expand_structured<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        self.expand_any(v).map(Structured::Any)
+    }
+
+    /// Expands a variable string to the entries of a map, e.g. `${prefix:FEATURE_}`.
+    ///
+    /// Only called for maps, structs and sequences, if [`Source::expands_structured`]
+    /// returns `true`, before the string is expanded with [`Source::expand_structured`].
+    ///
+    /// Returns `None` if `v` is not expanded to a map. The default implementation
+    /// always returns `None`.
+    fn expand_map<E>(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        let _ = v;
+        Ok(None)
+    }
+
+    /// Returns `true` if the source expands variables in place of maps, structs and sequences.
+    ///
+    /// Maps, structs and sequences of sources expanding structured values are deserialized as
+    /// self-describing values, which requires a self-describing format, like JSON or YAML.
+    fn expands_structured(&self) -> bool {
+        false
+    }
+
+    /// Returns `false` if `v` references a variable, which does not exist in this source.
+    ///
+    /// Used by [`ChainSource`] to fall back to the next source. Strings which do not reference
+    /// a variable and variables which cannot be looked up for other reasons, like an invalid
+    /// name, are considered to exist, their expansion reports the appropriate error.
+    ///
+    /// The default implementation always returns `true`.
+    fn contains_variable(&mut self, v: &str) -> bool {
+        let _ = v;
+        true
+    }
+
+    /// Returns `true` if the string `v` references a variable, which is expanded by this source.
+    ///
+    /// Used by [`Masked`](crate::Masked) to determine whether a value was expanded. If the source
+    /// returns `None`, the expanded value is compared with the original string instead.
+    ///
+    /// The default implementation returns `None`.
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        let _ = v;
+        None
+    }
+
+    /// Returns a short, human readable name of the source, used in error messages.
+    ///
+    /// The default implementation returns the name of the type without module paths,
+    /// e.g. `StringSource<EnvLookup>`.
+    fn name(&self) -> String {
+        utils::type_name::<Self>()
+    }
+
+    /// Chains this source with `source`, which expands all variables not contained in this
+    /// source.
+    ///
+    /// See [`ChainSource`].
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use serde_vars::{source::Source, EnvSource, MapSource};
+    ///
+    /// let mut source = EnvSource::default().or(MapSource::default().insert("MY_PORT", "8080"));
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${MY_PORT}""#);
+    /// let r: u16 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, 8080);
+    /// ```
+    fn or<S>(self, source: S) -> ChainSource<Self, S>
+    where
+        Self: Sized,
+    {
+        ChainSource::new(self, source)
+    }
+
+    /// Rewrites the names of all variables with `f`, before they are expanded by this source.
+    ///
+    /// See [`MapNames`].
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use serde_vars::{source::Source, MapSource};
+    ///
+    /// let mut source = MapSource::default()
+    ///     .insert("db_host", "localhost")
+    ///     .map_names(|name| name.replace('.', "_"));
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${db.host}""#);
+    /// let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, "localhost");
+    /// ```
+    fn map_names<F>(self, f: F) -> MapNames<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&str) -> String,
+    {
+        MapNames::new(self, f)
+    }
+
+    /// Converts the names of all variables to the naming convention `case`, before they are
+    /// expanded by this source.
+    ///
+    /// See [`MapCase`].
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use serde_vars::{source::{Case, Source}, MapSource};
+    ///
+    /// let mut source = MapSource::default()
+    ///     .insert("MAX_CONNECTIONS", "16")
+    ///     .map_case(Case::ScreamingSnake);
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${maxConnections}""#);
+    /// let r: u32 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, 16);
+    /// ```
+    fn map_case(self, case: Case) -> MapCase<Self>
+    where
+        Self: Sized,
+    {
+        MapNames::new(self, case.into())
+    }
+
+    /// Prepends `namespace` to the names of all variables, before they are expanded by this
+    /// source.
+    ///
+    /// See [`Namespaced`].
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use serde_vars::{source::Source, EnvSource};
+    ///
+    /// let mut source = EnvSource::default().namespaced("MYLIB_");
+    /// # unsafe { std::env::set_var("MYLIB_HOST", "localhost"); }
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#""${HOST}""#);
+    /// let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, "localhost");
+    /// ```
+    fn namespaced(self, namespace: impl Into<String>) -> Namespaced<Self>
+    where
+        Self: Sized,
+    {
+        Namespaced::new(self, namespace)
+    }
+
+    /// Expands variables, which do not exist in this source, to the values in `defaults`.
+    ///
+    /// See [`DefaultsSource`].
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use serde_vars::{source::Source, MapSource};
+    ///
+    /// let mut source = MapSource::default()
+    ///     .insert("HOST", "db.internal")
+    ///     .with_defaults([("HOST", "localhost"), ("PORT", "5432")]);
+    ///
+    /// let mut de = serde_json::Deserializer::from_str(r#"["${HOST}", "${PORT}"]"#);
+    /// let r: (String, u16) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    /// assert_eq!(r, ("db.internal".to_owned(), 5432));
+    /// ```
+    fn with_defaults<I, K, V>(self, defaults: I) -> DefaultsSource<Self>
+    where
+        Self: Sized,
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        DefaultsSource::new(self, defaults)
+    }
+}
+
+impl<S> Source for &mut S
+where
+    S: Source,
+{
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_bool(v)
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_i8(v)
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_i16(v)
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_i32(v)
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_i64(v)
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_i128(v)
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_u8(v)
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_u16(v)
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_u32(v)
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_u64(v)
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_u128(v)
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_f32(v)
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_f64(v)
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_str(v)
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_bytes(v)
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_any(v)
+    }
+
+    fn expand_structured<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_structured(v)
+    }
+
+    fn expand_map<E>(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        (**self).expand_map(v)
+    }
+
+    fn expands_structured(&self) -> bool {
+        (**self).expands_structured()
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        (**self).contains_variable(v)
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        (**self).references_variable(v)
+    }
+
+    fn name(&self) -> String {
+        (**self).name()
+    }
 }
 
 /// Type returned by [`Source::expand_any`].
 ///
 /// Represents any primitive type that can be parsed by a [`Source`].
+#[derive(Debug, Clone)]
 pub enum Any<'a> {
     Bool(bool),
     I8(i8),
     I16(i16),
     I32(i32),
     I64(i64),
+    I128(i128),
     U8(u8),
     U16(u16),
     U32(u32),
     U64(u64),
+    U128(u128),
     F32(f32),
     F64(f64),
     Str(Cow<'a, str>),
     Bytes(Cow<'a, [u8]>),
+    /// A number, which cannot be represented by any of the other numeric variants
+    /// without a loss of precision.
+    ///
+    /// With the `arbitrary_precision` feature enabled, the number is passed on in the
+    /// arbitrary precision representation of `serde_json`, otherwise it is passed on as a `f64`.
+    Number(Cow<'a, str>),
 }
 
 impl<'a> Any<'a> {
+    /// Converts borrowed strings and bytes into owned values.
+    pub fn into_owned(self) -> Any<'static> {
+        match self {
+            Any::Bool(v) => Any::Bool(v),
+            Any::I8(v) => Any::I8(v),
+            Any::I16(v) => Any::I16(v),
+            Any::I32(v) => Any::I32(v),
+            Any::I64(v) => Any::I64(v),
+            Any::I128(v) => Any::I128(v),
+            Any::U8(v) => Any::U8(v),
+            Any::U16(v) => Any::U16(v),
+            Any::U32(v) => Any::U32(v),
+            Any::U64(v) => Any::U64(v),
+            Any::U128(v) => Any::U128(v),
+            Any::F32(v) => Any::F32(v),
+            Any::F64(v) => Any::F64(v),
+            Any::Str(v) => Any::Str(Cow::Owned(v.into_owned())),
+            Any::Bytes(v) => Any::Bytes(Cow::Owned(v.into_owned())),
+            Any::Number(v) => Any::Number(Cow::Owned(v.into_owned())),
+        }
+    }
+
     /// Turns this [`Self`] into a [`de::Unexpected`] for error handling.
     pub fn unexpected(&self) -> de::Unexpected<'_> {
         match self {
@@ -126,14 +600,26 @@ impl<'a> Any<'a> {
             Any::I16(v) => de::Unexpected::Signed(i64::from(*v)),
             Any::I32(v) => de::Unexpected::Signed(i64::from(*v)),
             Any::I64(v) => de::Unexpected::Signed(*v),
+            Any::I128(v) => match i64::try_from(*v) {
+                Ok(v) => de::Unexpected::Signed(v),
+                Err(_) => de::Unexpected::Other("i128"),
+            },
             Any::U8(v) => de::Unexpected::Unsigned(u64::from(*v)),
             Any::U16(v) => de::Unexpected::Unsigned(u64::from(*v)),
             Any::U32(v) => de::Unexpected::Unsigned(u64::from(*v)),
             Any::U64(v) => de::Unexpected::Unsigned(*v),
+            Any::U128(v) => match u64::try_from(*v) {
+                Ok(v) => de::Unexpected::Unsigned(v),
+                Err(_) => de::Unexpected::Other("u128"),
+            },
             Any::F32(v) => de::Unexpected::Float(f64::from(*v)),
             Any::F64(v) => de::Unexpected::Float(*v),
             Any::Str(v) => de::Unexpected::Str(v),
             Any::Bytes(v) => de::Unexpected::Bytes(v),
+            Any::Number(v) => match v.parse() {
+                Ok(v) => de::Unexpected::Float(v),
+                Err(_) => de::Unexpected::Other("number"),
+            },
         }
     }
 
@@ -160,16 +646,41 @@ impl<'a> Any<'a> {
             Any::I16(v) => visitor.visit_i16(v),
             Any::I32(v) => visitor.visit_i32(v),
             Any::I64(v) => visitor.visit_i64(v),
+            Any::I128(v) => visitor.visit_i128(v),
             Any::U8(v) => visitor.visit_u8(v),
             Any::U16(v) => visitor.visit_u16(v),
             Any::U32(v) => visitor.visit_u32(v),
             Any::U64(v) => visitor.visit_u64(v),
+            Any::U128(v) => visitor.visit_u128(v),
             Any::F32(v) => visitor.visit_f32(v),
             Any::F64(v) => visitor.visit_f64(v),
             Any::Str(Cow::Owned(v)) => visitor.visit_string(v),
             Any::Str(Cow::Borrowed(v)) => visitor.visit_str(v),
             Any::Bytes(Cow::Owned(v)) => visitor.visit_byte_buf(v),
             Any::Bytes(Cow::Borrowed(v)) => visitor.visit_bytes(v),
+            Any::Number(v) => visit_number(v, visitor),
         }
     }
 }
+
+#[cfg(feature = "arbitrary_precision")]
+fn visit_number<'de, V, E>(v: Cow<'_, str>, visitor: V) -> Result<V::Value, E>
+where
+    V: de::Visitor<'de>,
+    E: de::Error,
+{
+    let number = std::iter::once((crate::content::NUMBER_TOKEN, v.into_owned()));
+    visitor.visit_map(de::value::MapDeserializer::new(number))
+}
+
+#[cfg(not(feature = "arbitrary_precision"))]
+fn visit_number<'de, V, E>(v: Cow<'_, str>, visitor: V) -> Result<V::Value, E>
+where
+    V: de::Visitor<'de>,
+    E: de::Error,
+{
+    match v.parse() {
+        Ok(n) => visitor.visit_f64(n),
+        Err(_) => Any::Str(v).visit(visitor),
+    }
+}
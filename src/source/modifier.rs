@@ -0,0 +1,64 @@
+use std::{collections::HashMap, fmt};
+
+type Modifier = Box<dyn Fn(&str) -> String + Send + Sync>;
+
+/// A registry of named value modifiers, e.g. `trim` in `${VAR|trim}`.
+pub(crate) struct Modifiers {
+    modifiers: HashMap<String, Modifier>,
+}
+
+impl Modifiers {
+    /// Registers the modifier `f` as `name`, replacing a previous modifier with the same name.
+    pub fn insert<F>(&mut self, name: impl Into<String>, f: F)
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.modifiers.insert(name.into(), Box::new(f));
+    }
+
+    /// Applies the modifiers `names` in order to `value`.
+    ///
+    /// Returns the name of the first modifier which is not registered as an error.
+    pub fn apply<'a>(
+        &self,
+        value: String,
+        names: impl IntoIterator<Item = &'a str>,
+    ) -> Result<String, &'a str> {
+        names
+            .into_iter()
+            .try_fold(value, |value, name| match self.modifiers.get(name) {
+                Some(modifier) => Ok(modifier(&value)),
+                None => Err(name),
+            })
+    }
+}
+
+impl Default for Modifiers {
+    fn default() -> Self {
+        let mut modifiers = Self {
+            modifiers: HashMap::new(),
+        };
+        modifiers.insert("trim", |v| v.trim().to_owned());
+        modifiers.insert("lowercase", str::to_lowercase);
+        modifiers.insert("uppercase", str::to_uppercase);
+        modifiers.insert("unquote", unquote);
+        modifiers
+    }
+}
+
+impl fmt::Debug for Modifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names = self.modifiers.keys().collect::<Vec<_>>();
+        names.sort();
+        f.debug_set().entries(names).finish()
+    }
+}
+
+/// Strips a matching pair of `"` or `'` surrounding `v`.
+fn unquote(v: &str) -> String {
+    ['"', '\'']
+        .into_iter()
+        .find_map(|q| v.strip_prefix(q)?.strip_suffix(q))
+        .unwrap_or(v)
+        .to_owned()
+}
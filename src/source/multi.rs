@@ -0,0 +1,346 @@
+use std::borrow::Cow;
+
+use serde::de;
+
+use crate::source::{utils, Any, Source, Structured, Variable};
+
+/// A [`Source`] which routes variables to other sources based on their names.
+///
+/// Variables with a name matching the pattern, like `DB_*`, are expanded by the source
+/// registered for the pattern, all other variables are passed on unchanged to the fallback
+/// source. This allows enforcing where each class of values may come from, e.g. database
+/// credentials only from a secret store and feature flags only from the environment.
+///
+/// Patterns match the whole variable name, `*` matches any sequence of characters
+/// and `?` matches a single character.
+///
+/// Every [`MultiSource`] handles exactly one pattern, multiple patterns are registered by
+/// nesting sources using [`Self::with_rule`]. Rules registered later take precedence.
+///
+/// # Examples:
+///
+/// ```
+/// # let temp = tempfile::tempdir().unwrap();
+/// # std::fs::write(temp.path().join("LOG_LEVEL"), "info").unwrap();
+/// use serde_vars::{source::MultiSource, EnvSource, FileSource, MapSource};
+///
+/// let vault = MapSource::default().insert("DB_PASSWORD", "hunter2");
+///
+/// let mut source = MultiSource::new("DB_*", vault, FileSource::new())
+///     .with_rule("FEATURE_*", EnvSource::default());
+/// # let vault = MapSource::default().insert("DB_PASSWORD", "hunter2");
+/// # let mut source = MultiSource::new("DB_*", vault, FileSource::new().with_base_path(temp.path()))
+/// #     .with_rule("FEATURE_*", EnvSource::default());
+/// # unsafe { std::env::set_var("FEATURE_SEARCH", "true"); }
+///
+/// let mut de = serde_json::Deserializer::from_str(
+///     r#"["${DB_PASSWORD}", "${FEATURE_SEARCH}", "${LOG_LEVEL}"]"#,
+/// );
+/// let r: (String, bool, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, ("hunter2".to_owned(), true, "info".to_owned()));
+/// ```
+#[derive(Debug)]
+pub struct MultiSource<S, F> {
+    pattern: String,
+    source: S,
+    fallback: F,
+    variable: Variable,
+}
+
+impl<S, F> MultiSource<S, F> {
+    /// Creates a [`Self`], which routes variables with names matching `pattern` to `source`
+    /// and all other variables to `fallback`.
+    pub fn new(pattern: impl Into<String>, source: S, fallback: F) -> Self {
+        Self {
+            pattern: pattern.into(),
+            source,
+            fallback,
+            variable: Default::default(),
+        }
+    }
+
+    /// Routes variables with names matching `pattern` to `source`.
+    ///
+    /// The rule takes precedence over all previously registered rules.
+    pub fn with_rule<T>(self, pattern: impl Into<String>, source: T) -> MultiSource<T, Self> {
+        MultiSource {
+            pattern: pattern.into(),
+            source,
+            variable: self.variable.clone(),
+            fallback: self,
+        }
+    }
+
+    /// Changes the variable prefix.
+    pub fn with_variable_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.variable.prefix = prefix.into();
+        self
+    }
+
+    /// Changes the variable suffix.
+    pub fn with_variable_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.variable.suffix = suffix.into();
+        self
+    }
+
+    /// Returns the routed source and the fallback source.
+    pub fn into_inner(self) -> (S, F) {
+        (self.source, self.fallback)
+    }
+
+    /// Returns `true` if `v` is a variable with a name matching the pattern.
+    fn matches(&self, v: &str) -> bool {
+        self.variable
+            .parse_str(v)
+            .is_some_and(|var| glob(&self.pattern, var))
+    }
+
+    /// Returns `true` if `v` is a variable matching the pattern, map variables match by prefix.
+    fn matches_map(&self, v: &str) -> bool {
+        self.variable.parse_str(v).is_some_and(|var| {
+            let name = var.strip_prefix(utils::MAP_PREFIX).unwrap_or(var);
+            glob(&self.pattern, name)
+        })
+    }
+
+    fn matches_bytes(&self, v: &[u8]) -> bool {
+        // Only strings can reference variables.
+        std::str::from_utf8(v).is_ok_and(|v| self.matches(v))
+    }
+}
+
+/// Returns `true` if `name` matches the glob `pattern`.
+fn glob(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` in the pattern and the position in the name it matched up to.
+    let mut star = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    star = Some((star_p, star_n + 1));
+                    p = star_p + 1;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+impl<S, F> Source for MultiSource<S, F>
+where
+    S: Source,
+    F: Source,
+{
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        match self.matches(v) {
+            true => self.source.expand_bool(v),
+            false => self.fallback.expand_bool(v),
+        }
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        match self.matches(v) {
+            true => self.source.expand_i8(v),
+            false => self.fallback.expand_i8(v),
+        }
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        match self.matches(v) {
+            true => self.source.expand_i16(v),
+            false => self.fallback.expand_i16(v),
+        }
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        match self.matches(v) {
+            true => self.source.expand_i32(v),
+            false => self.fallback.expand_i32(v),
+        }
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        match self.matches(v) {
+            true => self.source.expand_i64(v),
+            false => self.fallback.expand_i64(v),
+        }
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        match self.matches(v) {
+            true => self.source.expand_i128(v),
+            false => self.fallback.expand_i128(v),
+        }
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        match self.matches(v) {
+            true => self.source.expand_u8(v),
+            false => self.fallback.expand_u8(v),
+        }
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        match self.matches(v) {
+            true => self.source.expand_u16(v),
+            false => self.fallback.expand_u16(v),
+        }
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        match self.matches(v) {
+            true => self.source.expand_u32(v),
+            false => self.fallback.expand_u32(v),
+        }
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        match self.matches(v) {
+            true => self.source.expand_u64(v),
+            false => self.fallback.expand_u64(v),
+        }
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        match self.matches(v) {
+            true => self.source.expand_u128(v),
+            false => self.fallback.expand_u128(v),
+        }
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        match self.matches(v) {
+            true => self.source.expand_f32(v),
+            false => self.fallback.expand_f32(v),
+        }
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        match self.matches(v) {
+            true => self.source.expand_f64(v),
+            false => self.fallback.expand_f64(v),
+        }
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        match self.matches(&v) {
+            true => self.source.expand_str(v),
+            false => self.fallback.expand_str(v),
+        }
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        match self.matches_bytes(&v) {
+            true => self.source.expand_bytes(v),
+            false => self.fallback.expand_bytes(v),
+        }
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        match self.matches(&v) {
+            true => self.source.expand_any(v),
+            false => self.fallback.expand_any(v),
+        }
+    }
+
+    fn expand_structured<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        match self.matches(&v) {
+            true => self.source.expand_structured(v),
+            false => self.fallback.expand_structured(v),
+        }
+    }
+
+    fn expand_map<E>(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        match self.matches_map(v) {
+            true => self.source.expand_map(v),
+            false => self.fallback.expand_map(v),
+        }
+    }
+
+    fn expands_structured(&self) -> bool {
+        self.source.expands_structured() || self.fallback.expands_structured()
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        match self.matches(v) {
+            true => self.source.contains_variable(v),
+            false => self.fallback.contains_variable(v),
+        }
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        match self.matches(v) {
+            true => self.source.references_variable(v),
+            false => self.fallback.references_variable(v),
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use std::borrow::Cow;
+
+use serde::de;
+
+use crate::source::{utils, Any, Source, Structured, Variable};
+
+/// A [`Source`] which scopes all variables to a namespace.
+///
+/// The namespace is prepended to the name of every variable, before it is expanded by the
+/// wrapped source, the variable `${HOST}` in the namespace `MYLIB_` expands the variable
+/// `${MYLIB_HOST}`. This allows libraries to scope their configuration, without requiring
+/// configuration authors to repeat the prefix, and reusing the same configuration for services
+/// with different namespaces. Strings which do not reference a variable are passed on unchanged.
+///
+/// The wrapped source should use the same variable prefix and suffix as the [`Namespaced`],
+/// by default `${` and `}`.
+///
+/// Usually created with [`Source::namespaced`], see also [`MapNames`](crate::source::MapNames)
+/// for arbitrary changes of variable names.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::{source::Source, MapSource};
+///
+/// let mut values = MapSource::default()
+///     .insert("BILLING_DATABASE_URL", "postgres://billing")
+///     .insert("SHIPPING_DATABASE_URL", "postgres://shipping");
+///
+/// let mut billing = (&mut values).namespaced("BILLING_");
+///
+/// let config = r#""${DATABASE_URL}""#;
+/// let mut de = serde_json::Deserializer::from_str(config);
+/// let r: String = serde_vars::deserialize(&mut de, &mut billing).unwrap();
+/// assert_eq!(r, "postgres://billing");
+///
+/// let mut de = serde_json::Deserializer::from_str(config);
+/// let r: String = serde_vars::deserialize(&mut de, &mut values.namespaced("SHIPPING_")).unwrap();
+/// assert_eq!(r, "postgres://shipping");
+/// ```
+#[derive(Debug)]
+pub struct Namespaced<S> {
+    source: S,
+    namespace: String,
+    variable: Variable,
+}
+
+impl<S> Namespaced<S> {
+    /// Creates a [`Namespaced`], which prepends `namespace` to the names of variables before
+    /// they are expanded by `source`.
+    pub fn new(source: S, namespace: impl Into<String>) -> Self {
+        Self {
+            source,
+            namespace: namespace.into(),
+            variable: Default::default(),
+        }
+    }
+
+    /// Changes the variable prefix.
+    pub fn with_variable_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.variable.prefix = prefix.into();
+        self
+    }
+
+    /// Changes the variable suffix.
+    pub fn with_variable_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.variable.suffix = suffix.into();
+        self
+    }
+
+    /// Returns the namespace.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Returns the wrapped source.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+
+    /// Returns the variable in the namespace, if `v` is a variable.
+    fn scope(&self, v: &str) -> Option<String> {
+        let var = self.variable.parse_str(v)?;
+        Some(
+            self.variable
+                .fmt(format!("{}{var}", self.namespace))
+                .to_string(),
+        )
+    }
+
+    /// Returns the variable in the namespace, only scoping the prefix of map variables.
+    fn scope_map(&self, v: &str) -> Option<String> {
+        let var = self.variable.parse_str(v)?;
+        let name = match var.strip_prefix(utils::MAP_PREFIX) {
+            Some(prefix) => format!("{}{}{prefix}", utils::MAP_PREFIX, self.namespace),
+            None => format!("{}{var}", self.namespace),
+        };
+        Some(self.variable.fmt(name).to_string())
+    }
+
+    fn scope_bytes(&self, v: &[u8]) -> Option<Vec<u8>> {
+        // Only strings can reference variables.
+        self.scope(std::str::from_utf8(v).ok()?)
+            .map(String::into_bytes)
+    }
+}
+
+impl<S> Source for Namespaced<S>
+where
+    S: Source,
+{
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        match self.scope(v) {
+            Some(v) => self.source.expand_bool(&v),
+            None => self.source.expand_bool(v),
+        }
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        match self.scope(v) {
+            Some(v) => self.source.expand_i8(&v),
+            None => self.source.expand_i8(v),
+        }
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        match self.scope(v) {
+            Some(v) => self.source.expand_i16(&v),
+            None => self.source.expand_i16(v),
+        }
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        match self.scope(v) {
+            Some(v) => self.source.expand_i32(&v),
+            None => self.source.expand_i32(v),
+        }
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        match self.scope(v) {
+            Some(v) => self.source.expand_i64(&v),
+            None => self.source.expand_i64(v),
+        }
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        match self.scope(v) {
+            Some(v) => self.source.expand_i128(&v),
+            None => self.source.expand_i128(v),
+        }
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        match self.scope(v) {
+            Some(v) => self.source.expand_u8(&v),
+            None => self.source.expand_u8(v),
+        }
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        match self.scope(v) {
+            Some(v) => self.source.expand_u16(&v),
+            None => self.source.expand_u16(v),
+        }
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        match self.scope(v) {
+            Some(v) => self.source.expand_u32(&v),
+            None => self.source.expand_u32(v),
+        }
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        match self.scope(v) {
+            Some(v) => self.source.expand_u64(&v),
+            None => self.source.expand_u64(v),
+        }
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        match self.scope(v) {
+            Some(v) => self.source.expand_u128(&v),
+            None => self.source.expand_u128(v),
+        }
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        match self.scope(v) {
+            Some(v) => self.source.expand_f32(&v),
+            None => self.source.expand_f32(v),
+        }
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        match self.scope(v) {
+            Some(v) => self.source.expand_f64(&v),
+            None => self.source.expand_f64(v),
+        }
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        match self.scope(&v) {
+            Some(v) => self.source.expand_str(Cow::Owned(v)),
+            None => self.source.expand_str(v),
+        }
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        match self.scope_bytes(&v) {
+            Some(v) => self.source.expand_bytes(Cow::Owned(v)),
+            None => self.source.expand_bytes(v),
+        }
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        match self.scope(&v) {
+            Some(v) => self.source.expand_any(Cow::Owned(v)),
+            None => self.source.expand_any(v),
+        }
+    }
+
+    fn expand_structured<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        match self.scope(&v) {
+            Some(v) => self.source.expand_structured(Cow::Owned(v)),
+            None => self.source.expand_structured(v),
+        }
+    }
+
+    fn expand_map<E>(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        match self.scope_map(v) {
+            Some(v) => self.source.expand_map(&v),
+            None => self.source.expand_map(v),
+        }
+    }
+
+    fn expands_structured(&self) -> bool {
+        self.source.expands_structured()
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        match self.scope(v) {
+            Some(v) => self.source.contains_variable(&v),
+            None => self.source.contains_variable(v),
+        }
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        match self.scope(v) {
+            Some(v) => self.source.references_variable(&v),
+            None => self.source.references_variable(v),
+        }
+    }
+
+    fn name(&self) -> String {
+        self.source.name()
+    }
+}
@@ -0,0 +1,262 @@
+use std::{borrow::Cow, collections::HashMap, fmt, path::Path};
+
+use crate::source::{StringLookup, StringSource};
+
+/// A [`StringLookup`] which uses properties parsed from a Java `.properties` file.
+///
+/// The file contains one `key=value` property per line:
+///
+/// ```text
+/// # Comments start with a `#` or a `!`.
+/// database.host = localhost
+/// database.port: 5432
+/// database.user admin
+/// greeting = grüße, line breaks \n and tabs \t are escaped
+/// certificate = -----BEGIN CERTIFICATE----- \
+///               lines ending with a backslash are continued \
+///               -----END CERTIFICATE-----
+/// key\ with\ spaces = escaped\=separators
+/// ```
+///
+/// The key ends at the first unescaped `=`, `:` or whitespace. Whitespace around the separator
+/// and at the start of continued lines is removed, whitespace at the end of a value is preserved.
+/// Keys and values support the escapes `\t`, `\n`, `\r`, `\f` and `\uXXXX`, any other escaped
+/// character stands for itself. If a key is assigned multiple times, the last assignment wins.
+///
+/// Generally used through [`PropertiesSource`].
+#[derive(Debug, Default, Clone)]
+pub struct PropertiesLookup {
+    values: HashMap<String, String>,
+}
+
+impl PropertiesLookup {
+    /// Parses the contents of a `.properties` file.
+    pub fn parse(contents: &str) -> Result<Self, PropertiesError> {
+        let lines: Vec<_> = contents.split('\n').collect();
+
+        let mut values = HashMap::new();
+        let mut index = 0;
+        while index < lines.len() {
+            let line = index + 1;
+            let mut natural = trim_start(lines[index]);
+            index += 1;
+
+            if natural.is_empty() || natural.starts_with(['#', '!']) {
+                continue;
+            }
+
+            let mut logical = String::new();
+            while let Some(continued) = strip_continuation(natural) {
+                logical.push_str(continued);
+                let Some(next) = lines.get(index) else {
+                    break;
+                };
+                natural = trim_start(next);
+                index += 1;
+            }
+            if strip_continuation(natural).is_none() {
+                logical.push_str(natural);
+            }
+
+            let (key, value) = split_property(&logical);
+            let error = |message: &str| PropertiesError {
+                line,
+                message: message.to_owned(),
+            };
+            values.insert(
+                unescape(key).map_err(error)?,
+                unescape(value).map_err(error)?,
+            );
+        }
+
+        Ok(Self { values })
+    }
+
+    /// Reads and parses the `.properties` file at `path`.
+    ///
+    /// The file must be encoded as UTF-8, characters outside of the Latin-1 range can
+    /// also be written as `\uXXXX` escapes.
+    ///
+    /// Syntax errors are reported as [`std::io::ErrorKind::InvalidData`],
+    /// with a [`PropertiesError`] as the inner error.
+    pub fn from_path(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Self::parse(&contents)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+}
+
+impl StringLookup for PropertiesLookup {
+    fn lookup(&mut self, v: &str) -> Option<Cow<'_, str>> {
+        self.values
+            .get(v)
+            .map(|value| Cow::Borrowed(value.as_str()))
+    }
+}
+
+/// A source which uses properties from a Java `.properties` file.
+///
+/// See [`PropertiesLookup`] for the supported syntax and the [`crate`] and [`StringSource`]
+/// documentation for more details.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::PropertiesSource;
+///
+/// let mut source = PropertiesSource::from_contents(
+///     r#"
+///     redis.host = localhost
+///     redis.port: 6379
+///     redis.greeting = grüß \
+///                      dich
+///     "#,
+/// )
+/// .unwrap();
+///
+/// let mut de = serde_json::Deserializer::from_str(
+///     r#"["${redis.host}", "${redis.port}", "${redis.greeting}"]"#,
+/// );
+/// let r: (String, u16, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, ("localhost".to_owned(), 6379, "grüß dich".to_owned()));
+/// ```
+pub type PropertiesSource = StringSource<PropertiesLookup>;
+
+impl PropertiesSource {
+    /// Creates a [`PropertiesSource`] from the contents of a `.properties` file.
+    ///
+    /// See [`PropertiesLookup::parse`].
+    pub fn from_contents(contents: &str) -> Result<Self, PropertiesError> {
+        PropertiesLookup::parse(contents).map(StringSource::new)
+    }
+
+    /// Creates a [`PropertiesSource`] from the `.properties` file at `path`.
+    ///
+    /// See [`PropertiesLookup::from_path`].
+    pub fn from_path(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        PropertiesLookup::from_path(path).map(StringSource::new)
+    }
+}
+
+/// A syntax error in a `.properties` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertiesError {
+    line: usize,
+    message: String,
+}
+
+impl PropertiesError {
+    /// Returns the line number of the error, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl fmt::Display for PropertiesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at line {}", self.message, self.line)
+    }
+}
+
+impl std::error::Error for PropertiesError {}
+
+fn is_blank(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\x0c')
+}
+
+/// Removes leading whitespace and a trailing carriage return of a line.
+fn trim_start(line: &str) -> &str {
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    line.trim_start_matches(is_blank)
+}
+
+/// Returns the line without the backslash, if the line ends with an unescaped backslash.
+fn strip_continuation(line: &str) -> Option<&str> {
+    let backslashes = line.len() - line.trim_end_matches('\\').len();
+    match backslashes % 2 {
+        1 => Some(&line[..line.len() - 1]),
+        _ => None,
+    }
+}
+
+/// Splits a logical line into the still escaped key and value.
+fn split_property(line: &str) -> (&str, &str) {
+    let mut chars = line.char_indices();
+    let mut end = line.len();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '=' | ':' => {
+                end = i;
+                break;
+            }
+            c if is_blank(c) => {
+                end = i;
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let key = &line[..end];
+    let rest = line[end..].trim_start_matches(is_blank);
+    let rest = rest.strip_prefix(['=', ':']).unwrap_or(rest);
+    (key, rest.trim_start_matches(is_blank))
+}
+
+fn unescape(v: &str) -> Result<String, &'static str> {
+    let mut result = String::with_capacity(v.len());
+    let mut high_surrogate = None;
+
+    let mut chars = v.chars();
+    while let Some(c) = chars.next() {
+        let c = match c {
+            '\\' => match chars.next() {
+                Some('t') => '\t',
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('f') => '\x0c',
+                Some('u') => {
+                    let hex = chars.as_str().get(..4).ok_or("malformed \\uXXXX escape")?;
+                    let unit = u16::from_str_radix(hex, 16)
+                        .ok()
+                        .filter(|_| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                        .ok_or("malformed \\uXXXX escape")?;
+                    chars = chars.as_str()[4..].chars();
+
+                    if let Some(high) = high_surrogate.take() {
+                        let c = char::decode_utf16([high, unit])
+                            .next()
+                            .and_then(Result::ok)
+                            .ok_or("invalid surrogate pair in \\uXXXX escape")?;
+                        result.push(c);
+                        continue;
+                    }
+                    match char::from_u32(unit.into()) {
+                        Some(c) => c,
+                        None if (0xd800..0xdc00).contains(&unit) => {
+                            high_surrogate = Some(unit);
+                            continue;
+                        }
+                        None => return Err("invalid surrogate pair in \\uXXXX escape"),
+                    }
+                }
+                Some(c) => c,
+                None => continue,
+            },
+            c => c,
+        };
+
+        if high_surrogate.is_some() {
+            return Err("invalid surrogate pair in \\uXXXX escape");
+        }
+        result.push(c);
+    }
+
+    match high_surrogate {
+        Some(_) => Err("invalid surrogate pair in \\uXXXX escape"),
+        None => Ok(result),
+    }
+}
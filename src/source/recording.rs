@@ -0,0 +1,297 @@
+use std::borrow::Cow;
+
+use serde::de;
+
+use crate::source::{Any, Source, Structured, Variable};
+
+/// A [`Source`] which records every variable expanded by another source.
+///
+/// Every expanded variable is recorded with the requested type and the outcome of the
+/// expansion, the recorded expansions are available through [`Self::records`] after
+/// deserialization. Mostly useful in tests, to assert exactly which variables a configuration
+/// uses and which types are expected.
+///
+/// Strings which do not reference a variable are not recorded.
+///
+/// The wrapped source should use the same variable prefix and suffix as the [`RecordingSource`],
+/// by default `${` and `}`.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::{source::{Outcome, Record, RecordingSource}, MapSource};
+///
+/// let mut source = RecordingSource::new(MapSource::default().insert("PORT", "8080"));
+///
+/// let mut de = serde_json::Deserializer::from_str(r#"["${PORT}", "${HOST}"]"#);
+/// let r: Result<(u16, String), _> = serde_vars::deserialize(&mut de, &mut source);
+/// assert!(r.is_err());
+///
+/// assert_eq!(source.records()[0], Record::new("PORT", "u16", Outcome::Hit));
+/// assert_eq!(source.records()[1].variable, "HOST");
+/// assert_eq!(source.records()[1].outcome, Outcome::Miss);
+/// ```
+#[derive(Debug)]
+pub struct RecordingSource<S> {
+    source: S,
+    variable: Variable,
+    records: Vec<Record>,
+}
+
+/// A variable expansion recorded by a [`RecordingSource`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// The name of the variable, e.g. `DATABASE_URL` for `${DATABASE_URL}`.
+    pub variable: String,
+    /// The requested type, e.g. `u16`, `str` or `any`.
+    pub ty: &'static str,
+    /// The outcome of the expansion.
+    pub outcome: Outcome,
+}
+
+impl Record {
+    /// Creates a new [`Record`].
+    pub fn new(variable: impl Into<String>, ty: &'static str, outcome: Outcome) -> Self {
+        Self {
+            variable: variable.into(),
+            ty,
+            outcome,
+        }
+    }
+}
+
+/// The outcome of a variable expansion recorded by a [`RecordingSource`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The variable was expanded successfully.
+    Hit,
+    /// The variable does not exist, see [`Source::contains_variable`].
+    Miss,
+    /// The expansion failed for another reason, contains the error message.
+    Error(String),
+}
+
+impl<S> RecordingSource<S> {
+    /// Creates a [`RecordingSource`], which records all variables expanded by `source`.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            variable: Default::default(),
+            records: Vec::new(),
+        }
+    }
+
+    /// Changes the variable prefix.
+    pub fn with_variable_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.variable.prefix = prefix.into();
+        self
+    }
+
+    /// Changes the variable suffix.
+    pub fn with_variable_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.variable.suffix = suffix.into();
+        self
+    }
+
+    /// Returns all recorded expansions, in the order they happened.
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// Removes and returns all recorded expansions.
+    pub fn take_records(&mut self) -> Vec<Record> {
+        std::mem::take(&mut self.records)
+    }
+
+    /// Returns the wrapped source.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S> RecordingSource<S>
+where
+    S: Source,
+{
+    /// Expands `v` to the type `ty` with `expand` and records the expansion, if `v` is a variable.
+    fn recorded<T, E>(
+        &mut self,
+        v: &str,
+        ty: &'static str,
+        expand: impl FnOnce(&mut S) -> Result<T, E>,
+    ) -> Result<T, E>
+    where
+        E: de::Error,
+    {
+        let result = expand(&mut self.source);
+        let Some(name) = self.variable.parse_str(v) else {
+            return result;
+        };
+
+        let outcome = match &result {
+            Ok(_) => Outcome::Hit,
+            Err(_) if !self.source.contains_variable(v) => Outcome::Miss,
+            Err(err) => Outcome::Error(err.to_string()),
+        };
+        self.records.push(Record::new(name, ty, outcome));
+        result
+    }
+}
+
+impl<S> Source for RecordingSource<S>
+where
+    S: Source,
+{
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        self.recorded(v, "bool", |source| source.expand_bool(v))
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        self.recorded(v, "i8", |source| source.expand_i8(v))
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        self.recorded(v, "i16", |source| source.expand_i16(v))
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        self.recorded(v, "i32", |source| source.expand_i32(v))
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        self.recorded(v, "i64", |source| source.expand_i64(v))
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        self.recorded(v, "i128", |source| source.expand_i128(v))
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        self.recorded(v, "u8", |source| source.expand_u8(v))
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        self.recorded(v, "u16", |source| source.expand_u16(v))
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        self.recorded(v, "u32", |source| source.expand_u32(v))
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        self.recorded(v, "u64", |source| source.expand_u64(v))
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        self.recorded(v, "u128", |source| source.expand_u128(v))
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        self.recorded(v, "f32", |source| source.expand_f32(v))
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        self.recorded(v, "f64", |source| source.expand_f64(v))
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        let key = v.clone();
+        self.recorded(&key, "str", |source| source.expand_str(v))
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        // Only strings can reference variables.
+        let Ok(key) = std::str::from_utf8(&v).map(str::to_owned) else {
+            return self.source.expand_bytes(v);
+        };
+        self.recorded(&key, "bytes", |source| source.expand_bytes(v))
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        let key = v.clone();
+        self.recorded(&key, "any", |source| source.expand_any(v))
+    }
+
+    fn expand_structured<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        let key = v.clone();
+        self.recorded(&key, "structured", |source| source.expand_structured(v))
+    }
+
+    fn expand_map<E>(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        let result = self.source.expand_map(v);
+        if matches!(result, Ok(None)) {
+            return result;
+        }
+        self.recorded(v, "map", |_| result)
+    }
+
+    fn expands_structured(&self) -> bool {
+        self.source.expands_structured()
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        self.source.contains_variable(v)
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        self.source.references_variable(v)
+    }
+
+    fn name(&self) -> String {
+        self.source.name()
+    }
+}
@@ -0,0 +1,281 @@
+use std::{borrow::Cow, time::Duration};
+
+use serde::de;
+
+use crate::source::{Any, DynError, Source, Structured};
+
+/// The default predicate of a [`RetrySource`], which retries all errors.
+type RetryAll = fn(&DynError) -> bool;
+
+/// A [`Source`] which retries failed expansions of another source.
+///
+/// Failed expansions are retried up to a maximum number of attempts, waiting between attempts
+/// with an exponential backoff, before the last error is returned. This keeps the retry policy
+/// for transient failures, like an unavailable remote secret store, out of the individual
+/// sources.
+///
+/// Variables which do not exist in the source, see [`Source::contains_variable`], are never
+/// retried. By default all other errors are retried, [`Self::with_retry_if`] restricts retries
+/// to the errors considered transient. Errors of the wrapped source are converted to errors of
+/// the deserializer with [`de::Error::custom`], like with a
+/// [`BoxedSource`](crate::source::BoxedSource).
+///
+/// # Examples:
+///
+/// ```
+/// use std::time::Duration;
+/// use serde_vars::{source::RetrySource, EnvSource};
+///
+/// let mut source = RetrySource::new(EnvSource::default())
+///     .with_max_attempts(5)
+///     .with_backoff(Duration::from_millis(50));
+/// # unsafe { std::env::set_var("MY_PORT", "8080"); }
+///
+/// let mut de = serde_json::Deserializer::from_str(r#""${MY_PORT}""#);
+/// let r: u16 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, 8080);
+/// ```
+#[derive(Debug)]
+pub struct RetrySource<S, P = RetryAll> {
+    source: S,
+    max_attempts: usize,
+    backoff: Duration,
+    retry_if: P,
+}
+
+impl<S> RetrySource<S> {
+    /// Creates a [`RetrySource`], which retries all failed expansions of `source`, except for
+    /// missing variables.
+    ///
+    /// By default, a variable is expanded up to 3 times, waiting 100ms before the first retry.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            max_attempts: 3,
+            backoff: Duration::from_millis(100),
+            retry_if: |_| true,
+        }
+    }
+}
+
+impl<S, P> RetrySource<S, P> {
+    /// Changes the maximum number of attempts to expand a variable, including the first one.
+    ///
+    /// A value of `0` is treated like `1` and disables retries.
+    pub fn with_max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Changes the time to wait before the first retry, the time doubles with every retry.
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Only retries errors for which `retry_if` returns `true`.
+    ///
+    /// Other errors are returned immediately.
+    pub fn with_retry_if<F>(self, retry_if: F) -> RetrySource<S, F>
+    where
+        F: FnMut(&DynError) -> bool,
+    {
+        RetrySource {
+            source: self.source,
+            max_attempts: self.max_attempts,
+            backoff: self.backoff,
+            retry_if,
+        }
+    }
+
+    /// Returns the wrapped source.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S, P> RetrySource<S, P>
+where
+    P: FnMut(&DynError) -> bool,
+{
+    /// Runs `f` for the variable `v` until it succeeds, the error is not retried or all attempts
+    /// are exhausted.
+    fn retry<T, E>(
+        &mut self,
+        v: &str,
+        mut f: impl FnMut(&mut S) -> Result<T, DynError>,
+    ) -> Result<T, E>
+    where
+        E: de::Error,
+        S: Source,
+    {
+        let mut backoff = self.backoff;
+        let mut attempt = 1;
+        loop {
+            match f(&mut self.source) {
+                Ok(value) => return Ok(value),
+                // Whether the variable exists is only checked once, before the first retry.
+                Err(err)
+                    if attempt < self.max_attempts
+                        && (self.retry_if)(&err)
+                        && (attempt > 1 || self.source.contains_variable(v)) =>
+                {
+                    std::thread::sleep(backoff);
+                    backoff = backoff.saturating_mul(2);
+                    attempt += 1;
+                }
+                Err(err) => return Err(E::custom(err)),
+            }
+        }
+    }
+}
+
+impl<S, P> Source for RetrySource<S, P>
+where
+    S: Source,
+    P: FnMut(&DynError) -> bool,
+{
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        self.retry(v, |source| source.expand_bool(v))
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        self.retry(v, |source| source.expand_i8(v))
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        self.retry(v, |source| source.expand_i16(v))
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        self.retry(v, |source| source.expand_i32(v))
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        self.retry(v, |source| source.expand_i64(v))
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        self.retry(v, |source| source.expand_i128(v))
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        self.retry(v, |source| source.expand_u8(v))
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        self.retry(v, |source| source.expand_u16(v))
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        self.retry(v, |source| source.expand_u32(v))
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        self.retry(v, |source| source.expand_u64(v))
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        self.retry(v, |source| source.expand_u128(v))
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        self.retry(v, |source| source.expand_f32(v))
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        self.retry(v, |source| source.expand_f64(v))
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        self.retry(&v, |source| source.expand_str(v.clone()))
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        // Bytes which are not valid UTF-8 do not reference a variable.
+        let key = String::from_utf8_lossy(&v).into_owned();
+        self.retry(&key, |source| source.expand_bytes(v.clone()))
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        self.retry(&v, |source| source.expand_any(v.clone()))
+    }
+
+    fn expand_structured<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        self.retry(&v, |source| source.expand_structured(v.clone()))
+    }
+
+    fn expand_map<E>(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        self.retry(v, |source| source.expand_map(v))
+    }
+
+    fn expands_structured(&self) -> bool {
+        self.source.expands_structured()
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        self.source.contains_variable(v)
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        self.source.references_variable(v)
+    }
+
+    fn name(&self) -> String {
+        self.source.name()
+    }
+}
@@ -0,0 +1,324 @@
+use std::borrow::Cow;
+
+use serde::de;
+
+use crate::source::{Any, Source, Structured, Variable};
+
+/// A [`Source`] which routes variables to other sources based on a scheme.
+///
+/// Variables with a scheme, like `${file:/run/secrets/token}`, are dispatched to the source
+/// registered for the scheme, with the scheme removed from the variable (`${/run/secrets/token}`).
+/// All other variables are passed on unchanged to the fallback source.
+///
+/// Every [`RoutingSource`] handles exactly one scheme, multiple schemes are registered by
+/// nesting sources using [`Self::with_scheme`]. Sources registered later take precedence.
+///
+/// The routed sources should use the same variable prefix and suffix as the [`RoutingSource`],
+/// by default `${` and `}`.
+///
+/// # Examples:
+///
+/// ```
+/// # let temp = tempfile::tempdir().unwrap();
+/// # std::fs::write(temp.path().join("password"), "hunter2").unwrap();
+/// use serde_vars::{EnvSource, FileSource, RoutingSource};
+///
+/// let mut source = RoutingSource::new("env", EnvSource::default(), EnvSource::default())
+///     .with_scheme("file", FileSource::new());
+/// # let mut source = RoutingSource::new("env", EnvSource::default(), EnvSource::default())
+/// #     .with_scheme("file", FileSource::new().with_base_path(temp.path()));
+/// # unsafe { std::env::set_var("DB_USER", "admin"); }
+///
+/// let mut de = serde_json::Deserializer::from_str(r#"["${env:DB_USER}", "${file:password}"]"#);
+/// let r: Vec<String> = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, ["admin", "hunter2"]);
+/// ```
+#[derive(Debug)]
+pub struct RoutingSource<S, F> {
+    scheme: String,
+    source: S,
+    fallback: F,
+    variable: Variable,
+}
+
+impl<S, F> RoutingSource<S, F> {
+    /// Creates a [`Self`], which routes variables with the scheme `scheme` to `source`
+    /// and all other variables to `fallback`.
+    pub fn new(scheme: impl Into<String>, source: S, fallback: F) -> Self {
+        Self {
+            scheme: scheme.into(),
+            source,
+            fallback,
+            variable: Default::default(),
+        }
+    }
+
+    /// Routes variables with the scheme `scheme` to `source`.
+    ///
+    /// The scheme takes precedence over all previously registered schemes.
+    pub fn with_scheme<T>(self, scheme: impl Into<String>, source: T) -> RoutingSource<T, Self> {
+        RoutingSource {
+            scheme: scheme.into(),
+            source,
+            variable: self.variable.clone(),
+            fallback: self,
+        }
+    }
+
+    /// Changes the variable prefix.
+    pub fn with_variable_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.variable.prefix = prefix.into();
+        self
+    }
+
+    /// Changes the variable suffix.
+    pub fn with_variable_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.variable.suffix = suffix.into();
+        self
+    }
+
+    /// Adds an alternative variable prefix and suffix pair.
+    ///
+    /// See [`StringSource::with_alternative_delimiters`](crate::StringSource::with_alternative_delimiters) for details.
+    pub fn with_alternative_delimiters(
+        mut self,
+        prefix: impl Into<String>,
+        suffix: impl Into<String>,
+    ) -> Self {
+        self.variable
+            .alternatives
+            .push((prefix.into(), suffix.into()));
+        self
+    }
+
+    /// Returns the routed source and the fallback source.
+    pub fn into_inner(self) -> (S, F) {
+        (self.source, self.fallback)
+    }
+
+    /// Returns the variable with the scheme removed, if the variable matches the scheme.
+    fn route(&self, v: &str) -> Option<String> {
+        let var = self.variable.parse_str(v)?;
+        let var = var.strip_prefix(&self.scheme)?.strip_prefix(':')?;
+        Some(self.variable.fmt(var).to_string())
+    }
+
+    fn route_bytes(&self, v: &[u8]) -> Option<Vec<u8>> {
+        let var = self.variable.parse_bytes(v)?;
+        let var = var
+            .strip_prefix(self.scheme.as_bytes())?
+            .strip_prefix(b":")?;
+        Some(
+            [
+                self.variable.prefix.as_bytes(),
+                var,
+                self.variable.suffix.as_bytes(),
+            ]
+            .concat(),
+        )
+    }
+}
+
+impl<S, F> Source for RoutingSource<S, F>
+where
+    S: Source,
+    F: Source,
+{
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        match self.route(v) {
+            Some(v) => self.source.expand_bool(&v),
+            None => self.fallback.expand_bool(v),
+        }
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        match self.route(v) {
+            Some(v) => self.source.expand_i8(&v),
+            None => self.fallback.expand_i8(v),
+        }
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        match self.route(v) {
+            Some(v) => self.source.expand_i16(&v),
+            None => self.fallback.expand_i16(v),
+        }
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        match self.route(v) {
+            Some(v) => self.source.expand_i32(&v),
+            None => self.fallback.expand_i32(v),
+        }
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        match self.route(v) {
+            Some(v) => self.source.expand_i64(&v),
+            None => self.fallback.expand_i64(v),
+        }
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        match self.route(v) {
+            Some(v) => self.source.expand_i128(&v),
+            None => self.fallback.expand_i128(v),
+        }
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        match self.route(v) {
+            Some(v) => self.source.expand_u8(&v),
+            None => self.fallback.expand_u8(v),
+        }
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        match self.route(v) {
+            Some(v) => self.source.expand_u16(&v),
+            None => self.fallback.expand_u16(v),
+        }
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        match self.route(v) {
+            Some(v) => self.source.expand_u32(&v),
+            None => self.fallback.expand_u32(v),
+        }
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        match self.route(v) {
+            Some(v) => self.source.expand_u64(&v),
+            None => self.fallback.expand_u64(v),
+        }
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        match self.route(v) {
+            Some(v) => self.source.expand_u128(&v),
+            None => self.fallback.expand_u128(v),
+        }
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        match self.route(v) {
+            Some(v) => self.source.expand_f32(&v),
+            None => self.fallback.expand_f32(v),
+        }
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        match self.route(v) {
+            Some(v) => self.source.expand_f64(&v),
+            None => self.fallback.expand_f64(v),
+        }
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        match self.route(&v) {
+            Some(v) => self.source.expand_str(Cow::Owned(v)),
+            None => self.fallback.expand_str(v),
+        }
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        match self.route_bytes(&v) {
+            Some(v) => self.source.expand_bytes(Cow::Owned(v)),
+            None => self.fallback.expand_bytes(v),
+        }
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        match self.route(&v) {
+            Some(v) => self.source.expand_any(Cow::Owned(v)),
+            None => self.fallback.expand_any(v),
+        }
+    }
+
+    fn expand_structured<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        match self.route(&v) {
+            Some(v) => self.source.expand_structured(Cow::Owned(v)),
+            None => self.fallback.expand_structured(v),
+        }
+    }
+
+    fn expand_map<E>(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        match self.route(v) {
+            Some(v) => self.source.expand_map(&v),
+            None => self.fallback.expand_map(v),
+        }
+    }
+
+    fn expands_structured(&self) -> bool {
+        self.source.expands_structured() || self.fallback.expands_structured()
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        match self.route(v) {
+            Some(v) => self.source.contains_variable(&v),
+            None => self.fallback.contains_variable(v),
+        }
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        match self.route(v) {
+            Some(v) => self.source.references_variable(&v),
+            None => self.fallback.references_variable(v),
+        }
+    }
+}
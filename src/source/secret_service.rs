@@ -0,0 +1,132 @@
+use std::{borrow::Cow, collections::HashMap, fmt};
+
+use ::secret_service::{blocking::SecretService, EncryptionType};
+
+use crate::source::{StringLookup, StringSource};
+
+/// A [`StringLookup`] which uses secrets stored in the Freedesktop Secret Service.
+///
+/// The Secret Service is the password store of Linux desktops, provided by GNOME Keyring,
+/// KWallet or KeePassXC, and accessed via D-Bus.
+///
+/// Secrets are looked up by their `service` and `username` attributes, the attributes used by
+/// the [`keyring`](https://docs.rs/keyring) crate and tools like `secret-tool`. Variables have
+/// the form `service/username`, e.g. `${my-app/db-password}`. With [`Self::with_service`]
+/// the service is fixed and variables only name the user, e.g. `${db-password}`.
+///
+/// Locked secrets are unlocked, which may prompt the user for the password of the keyring.
+/// Every secret is looked up only once and then cached, including failed lookups.
+///
+/// Generally used through [`SecretServiceSource`].
+#[derive(Default)]
+pub struct SecretServiceLookup {
+    service: Option<String>,
+    connection: Option<Result<SecretService<'static>, String>>,
+    secrets: HashMap<String, Result<Option<Vec<u8>>, String>>,
+}
+
+impl SecretServiceLookup {
+    /// Creates a [`SecretServiceLookup`].
+    ///
+    /// The connection to the Secret Service is established with the first lookup.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only looks up secrets of the service `service`, variables name the user.
+    pub fn with_service(mut self, service: impl Into<String>) -> Self {
+        self.service = Some(service.into());
+        self
+    }
+
+    /// Returns the secret of the variable `v`.
+    fn secret(&mut self, v: &str) -> Result<Option<&[u8]>, String> {
+        if !self.secrets.contains_key(v) {
+            let secret = self.request(v);
+            self.secrets.insert(v.to_owned(), secret);
+        }
+
+        match &self.secrets[v] {
+            Ok(secret) => Ok(secret.as_deref()),
+            Err(err) => Err(err.clone()),
+        }
+    }
+
+    fn request(&mut self, v: &str) -> Result<Option<Vec<u8>>, String> {
+        let (service, username) = match &self.service {
+            Some(service) => (service.as_str(), v),
+            None => v
+                .split_once('/')
+                .ok_or("expected a variable of the form `service/username`")?,
+        };
+
+        let connection = self.connection.get_or_insert_with(|| {
+            SecretService::connect(EncryptionType::Dh)
+                .map_err(|err| format!("failed to connect to the Secret Service: {err}"))
+        });
+        let connection = connection.as_ref().map_err(Clone::clone)?;
+
+        let attributes = HashMap::from([("service", service), ("username", username)]);
+        let items = connection
+            .search_items(attributes)
+            .map_err(|err| err.to_string())?;
+        let Some(item) = items.unlocked.first().or(items.locked.first()) else {
+            return Ok(None);
+        };
+
+        item.ensure_unlocked()
+            .and_then(|_| item.get_secret())
+            .map(Some)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl fmt::Debug for SecretServiceLookup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretServiceLookup")
+            .field("service", &self.service)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StringLookup for SecretServiceLookup {
+    fn lookup(&mut self, v: &str) -> Option<Cow<'_, str>> {
+        let secret = self.secret(v).ok()??;
+        std::str::from_utf8(secret).ok().map(Cow::Borrowed)
+    }
+
+    fn lookup_bytes(&mut self, v: &str) -> Option<Cow<'_, [u8]>> {
+        self.secret(v).ok()?.map(Cow::Borrowed)
+    }
+
+    fn lookup_error(&mut self, v: &str) -> Option<String> {
+        self.secret(v).err()
+    }
+}
+
+/// A source which uses secrets stored in the Freedesktop Secret Service.
+///
+/// See [`SecretServiceLookup`] for how secrets are looked up and the [`crate`] and
+/// [`StringSource`] documentation for more details.
+///
+/// # Examples:
+///
+/// ```no_run
+/// use serde_vars::source::SecretServiceSource;
+///
+/// // Stored with `secret-tool store --label="Database" service my-app username db-password`.
+/// let mut source = SecretServiceSource::for_service("my-app");
+///
+/// let mut de = serde_json::Deserializer::from_str(r#""${db-password}""#);
+/// let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// ```
+pub type SecretServiceSource = StringSource<SecretServiceLookup>;
+
+impl SecretServiceSource {
+    /// Creates a [`SecretServiceSource`] for the secrets of the service `service`.
+    ///
+    /// See [`SecretServiceLookup::with_service`].
+    pub fn for_service(service: impl Into<String>) -> Self {
+        StringSource::new(SecretServiceLookup::new().with_service(service))
+    }
+}
@@ -0,0 +1,168 @@
+use std::{borrow::Cow, collections::HashMap, fmt, path::Path};
+
+use rusqlite::{types::ValueRef, Connection, OpenFlags, OptionalExtension};
+
+use crate::source::{StringLookup, StringSource};
+
+/// A [`StringLookup`] which uses values stored in a SQLite table.
+///
+/// Variables are looked up by their name in a key-value table, by default with
+/// `SELECT "value" FROM "vars" WHERE "key" = ?`. The table and columns can be changed with
+/// [`Self::with_table`] and [`Self::with_columns`].
+///
+/// Text and blob values are used as is, integers and reals as their decimal representation.
+/// Rows which do not exist or have a `NULL` value are reported as missing variables, failing
+/// queries, e.g. because the table does not exist, are reported as errors. Every variable is
+/// looked up only once and then cached, including failed lookups.
+///
+/// Generally used through [`SqliteSource`].
+pub struct SqliteLookup {
+    connection: Connection,
+    table: String,
+    key: String,
+    value: String,
+    values: HashMap<String, Result<Option<Vec<u8>>, String>>,
+}
+
+impl SqliteLookup {
+    /// Creates a [`SqliteLookup`] for an open database connection.
+    pub fn new(connection: Connection) -> Self {
+        Self {
+            connection,
+            table: "vars".to_owned(),
+            key: "key".to_owned(),
+            value: "value".to_owned(),
+            values: HashMap::new(),
+        }
+    }
+
+    /// Opens the database at `path` read-only.
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        let flags = OpenFlags::SQLITE_OPEN_READ_ONLY
+            | OpenFlags::SQLITE_OPEN_URI
+            | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+        Connection::open_with_flags(path, flags).map(Self::new)
+    }
+
+    /// Changes the table the values are looked up in.
+    pub fn with_table(mut self, table: impl Into<String>) -> Self {
+        self.table = table.into();
+        self.values.clear();
+        self
+    }
+
+    /// Changes the columns containing the name of the variable and its value.
+    pub fn with_columns(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.key = key.into();
+        self.value = value.into();
+        self.values.clear();
+        self
+    }
+
+    /// Returns the value of the variable `v`.
+    fn value(&mut self, v: &str) -> Result<Option<&[u8]>, String> {
+        if !self.values.contains_key(v) {
+            let value = self.query(v).map_err(|err| err.to_string());
+            self.values.insert(v.to_owned(), value);
+        }
+
+        match &self.values[v] {
+            Ok(value) => Ok(value.as_deref()),
+            Err(err) => Err(err.clone()),
+        }
+    }
+
+    fn query(&self, v: &str) -> rusqlite::Result<Option<Vec<u8>>> {
+        let sql = format!(
+            "SELECT {} FROM {} WHERE {} = ?1",
+            quote(&self.value),
+            quote(&self.table),
+            quote(&self.key),
+        );
+
+        let mut statement = self.connection.prepare_cached(&sql)?;
+        let value = statement
+            .query_row([v], |row| {
+                Ok(match row.get_ref(0)? {
+                    ValueRef::Null => None,
+                    ValueRef::Integer(value) => Some(value.to_string().into_bytes()),
+                    ValueRef::Real(value) => Some(value.to_string().into_bytes()),
+                    ValueRef::Text(value) | ValueRef::Blob(value) => Some(value.to_vec()),
+                })
+            })
+            .optional()?;
+        Ok(value.flatten())
+    }
+}
+
+impl fmt::Debug for SqliteLookup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqliteLookup")
+            .field("path", &self.connection.path())
+            .field("table", &self.table)
+            .field("key", &self.key)
+            .field("value", &self.value)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StringLookup for SqliteLookup {
+    fn lookup(&mut self, v: &str) -> Option<Cow<'_, str>> {
+        let value = self.value(v).ok()??;
+        std::str::from_utf8(value).ok().map(Cow::Borrowed)
+    }
+
+    fn lookup_bytes(&mut self, v: &str) -> Option<Cow<'_, [u8]>> {
+        self.value(v).ok()?.map(Cow::Borrowed)
+    }
+
+    fn lookup_error(&mut self, v: &str) -> Option<String> {
+        self.value(v).err()
+    }
+}
+
+/// A source which uses values stored in a SQLite table.
+///
+/// Useful for applications which store their runtime settings in a local database.
+///
+/// See [`SqliteLookup`] for how values are looked up and the [`crate`] and [`StringSource`]
+/// documentation for more details.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::source::{SqliteLookup, SqliteSource};
+///
+/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+/// connection
+///     .execute_batch(
+///         "CREATE TABLE settings (name TEXT PRIMARY KEY, value);
+///          INSERT INTO settings VALUES ('db.host', 'localhost'), ('db.port', 5432);",
+///     )
+///     .unwrap();
+///
+/// let mut source = SqliteSource::new(
+///     SqliteLookup::new(connection)
+///         .with_table("settings")
+///         .with_columns("name", "value"),
+/// );
+///
+/// let mut de = serde_json::Deserializer::from_str(r#"["${db.host}", "${db.port}"]"#);
+/// let r: (String, u16) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, ("localhost".to_owned(), 5432));
+/// ```
+pub type SqliteSource = StringSource<SqliteLookup>;
+
+impl SqliteSource {
+    /// Creates a [`SqliteSource`] for the database at `path`, opened read-only.
+    ///
+    /// See [`SqliteLookup::open`].
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        SqliteLookup::open(path).map(StringSource::new)
+    }
+}
+
+/// Quotes an SQL identifier.
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
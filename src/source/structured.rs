@@ -0,0 +1,263 @@
+use std::{borrow::Cow, fmt, marker::PhantomData};
+
+use serde::de::{self, IntoDeserializer};
+
+use crate::source::Any;
+
+/// Type returned by [`Source::expand_structured`](crate::source::Source::expand_structured).
+///
+/// Represents a structured value, like a map or a sequence, which can be deserialized into
+/// nested data structures. Primitive values are represented using [`Any`].
+///
+/// [`Structured`] can be deserialized from any self-describing format, which allows sources
+/// to parse structured values from documents, e.g. JSON.
+#[derive(Debug, Clone)]
+pub enum Structured<'a> {
+    /// An empty value, e.g. `null`.
+    Unit,
+    /// A primitive value.
+    Any(Any<'a>),
+    /// A sequence of values.
+    Seq(Vec<Structured<'a>>),
+    /// A map of values.
+    Map(Vec<(Structured<'a>, Structured<'a>)>),
+}
+
+impl Structured<'_> {
+    /// Converts all borrowed strings and bytes into owned values.
+    pub fn into_owned(self) -> Structured<'static> {
+        match self {
+            Self::Unit => Structured::Unit,
+            Self::Any(v) => Structured::Any(v.into_owned()),
+            Self::Seq(v) => Structured::Seq(v.into_iter().map(Structured::into_owned).collect()),
+            Self::Map(v) => Structured::Map(
+                v.into_iter()
+                    .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Turns this [`Self`] into a [`de::Unexpected`] for error handling.
+    pub fn unexpected(&self) -> de::Unexpected<'_> {
+        match self {
+            Self::Unit => de::Unexpected::Unit,
+            Self::Any(v) => v.unexpected(),
+            Self::Seq(_) => de::Unexpected::Seq,
+            Self::Map(_) => de::Unexpected::Map,
+        }
+    }
+
+    pub(crate) fn visit<'de, V, E>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: de::Visitor<'de>,
+        E: de::Error,
+    {
+        match self {
+            Self::Unit => visitor.visit_unit(),
+            Self::Any(v) => v.visit(visitor),
+            Self::Seq(v) => visitor.visit_seq(de::value::SeqDeserializer::new(v.into_iter())),
+            Self::Map(v) => visitor.visit_map(de::value::MapDeserializer::new(v.into_iter())),
+        }
+    }
+}
+
+impl<'de, 'a, E> IntoDeserializer<'de, E> for Structured<'a>
+where
+    E: de::Error,
+{
+    type Deserializer = StructuredDeserializer<'a, E>;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        StructuredDeserializer {
+            value: self,
+            err: PhantomData,
+        }
+    }
+}
+
+/// A [`de::Deserializer`] for [`Structured`] values.
+pub struct StructuredDeserializer<'a, E> {
+    value: Structured<'a>,
+    err: PhantomData<E>,
+}
+
+impl<'de, E> de::Deserializer<'de> for StructuredDeserializer<'_, E>
+where
+    E: de::Error,
+{
+    type Error = E;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.value.visit(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            Structured::Unit => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            Structured::Any(Any::Str(v)) => {
+                let v: de::value::StringDeserializer<E> = v.into_owned().into_deserializer();
+                de::Deserializer::deserialize_enum(v, name, variants, visitor)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Structured<'_> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct Visitor<'a>(PhantomData<Structured<'a>>);
+
+        impl<'de, 'a> de::Visitor<'de> for Visitor<'a> {
+            type Value = Structured<'a>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("any value")
+            }
+
+            fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(Structured::Any(Any::Bool(v)))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(Structured::Any(Any::I64(v)))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(Structured::Any(Any::U64(v)))
+            }
+
+            fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(Structured::Any(Any::I128(v)))
+            }
+
+            fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(Structured::Any(Any::U128(v)))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(Structured::Any(Any::F64(v)))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(Structured::Any(Any::Str(Cow::Owned(v.to_owned()))))
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(Structured::Any(Any::Str(Cow::Owned(v))))
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(Structured::Any(Any::Bytes(Cow::Owned(v.to_vec()))))
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(Structured::Any(Any::Bytes(Cow::Owned(v))))
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(Structured::Unit)
+            }
+
+            fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: de::Deserializer<'de>,
+            {
+                de::Deserialize::deserialize(deserializer)
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(Structured::Unit)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let mut values = Vec::new();
+                while let Some(value) = seq.next_element()? {
+                    values.push(value);
+                }
+                Ok(Structured::Seq(values))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                let mut values = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    values.push(entry);
+                }
+                Ok(Structured::Map(values))
+            }
+        }
+
+        deserializer.deserialize_any(Visitor(PhantomData))
+    }
+}
@@ -0,0 +1,505 @@
+use std::{
+    borrow::Cow,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use serde::de;
+
+use crate::source::{utils, Any, Source, Structured};
+
+/// A [`Source`] which expands variables through a shared reference.
+///
+/// Every [`SyncSource`] can be used as a [`Source`] through a shared reference or an [`Arc`],
+/// which allows sharing one source between threads and repeated deserializations.
+///
+/// Any [`Source`] becomes a [`SyncSource`] by wrapping it in a [`Mutex`], expansions
+/// lock the mutex for the duration of the expansion.
+///
+/// # Examples:
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use serde_vars::EnvSource;
+///
+/// let source = Arc::new(Mutex::new(EnvSource::default()));
+/// # unsafe { std::env::set_var("MY_PORT", "8080"); }
+///
+/// let handles = (0..4).map(|_| {
+///     let mut source = Arc::clone(&source);
+///     std::thread::spawn(move || {
+///         let mut de = serde_json::Deserializer::from_str(r#""${MY_PORT}""#);
+///         serde_vars::deserialize::<_, _, u16>(&mut de, &mut source).unwrap()
+///     })
+/// });
+///
+/// for handle in handles.collect::<Vec<_>>() {
+///     assert_eq!(handle.join().unwrap(), 8080);
+/// }
+///
+/// let mut de = serde_json::Deserializer::from_str(r#""${MY_PORT}""#);
+/// let r: u16 = serde_vars::deserialize(&mut de, &mut &*source).unwrap();
+/// assert_eq!(r, 8080);
+/// ```
+pub trait SyncSource {
+    /// Expands a variable string to a boolean.
+    ///
+    /// See [`Source::expand_bool`].
+    fn expand_bool<E>(&self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error;
+
+    /// Expands a variable string to an `i8`.
+    fn expand_i8<E>(&self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error;
+
+    /// Expands a variable string to an `i16`.
+    fn expand_i16<E>(&self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error;
+
+    /// Expands a variable string to an `i32`.
+    fn expand_i32<E>(&self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error;
+
+    /// Expands a variable string to an `i64`.
+    fn expand_i64<E>(&self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error;
+
+    /// Expands a variable string to an `i128`.
+    ///
+    /// The default implementation expands the variable to an `i64`.
+    fn expand_i128<E>(&self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        self.expand_i64(v).map(i128::from)
+    }
+
+    /// Expands a variable string to an `u8`.
+    fn expand_u8<E>(&self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error;
+
+    /// Expands a variable string to an `u16`.
+    fn expand_u16<E>(&self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error;
+
+    /// Expands a variable string to an `u32`.
+    fn expand_u32<E>(&self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error;
+
+    /// Expands a variable string to an `u64`.
+    fn expand_u64<E>(&self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error;
+
+    /// Expands a variable string to an `u128`.
+    ///
+    /// The default implementation expands the variable to an `u64`.
+    fn expand_u128<E>(&self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        self.expand_u64(v).map(u128::from)
+    }
+
+    /// Expands a variable string to a `f32`.
+    fn expand_f32<E>(&self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error;
+
+    /// Expands a variable string to a `f64`.
+    fn expand_f64<E>(&self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error;
+
+    /// Expands a variable string to string.
+    ///
+    /// See [`Source::expand_str`].
+    fn expand_str<'a, E>(&self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error;
+
+    /// Expands bytes into other bytes.
+    ///
+    /// See [`Source::expand_bytes`].
+    fn expand_bytes<'a, E>(&self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error;
+
+    /// Expands a variable string to [`Any`].
+    ///
+    /// See [`Source::expand_any`].
+    fn expand_any<'a, E>(&self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error;
+
+    /// Expands a variable string to a [`Structured`] value, like a map or a sequence.
+    ///
+    /// See [`Source::expand_structured`].
+    fn expand_structured<'a, E>(&self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        self.expand_any(v).map(Structured::Any)
+    }
+
+    /// Expands a variable string to the entries of a map, e.g. `${prefix:FEATURE_}`.
+    ///
+    /// See [`Source::expand_map`].
+    fn expand_map<E>(&self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        let _ = v;
+        Ok(None)
+    }
+
+    /// Returns `true` if the source expands variables in place of maps, structs and sequences.
+    ///
+    /// See [`Source::expands_structured`].
+    fn expands_structured(&self) -> bool {
+        false
+    }
+
+    /// Returns `false` if `v` references a variable, which does not exist in this source.
+    ///
+    /// See [`Source::contains_variable`].
+    fn contains_variable(&self, v: &str) -> bool {
+        let _ = v;
+        true
+    }
+
+    /// Returns `true` if the string `v` references a variable, which is expanded by this source.
+    ///
+    /// See [`Source::references_variable`].
+    fn references_variable(&self, v: &str) -> Option<bool> {
+        let _ = v;
+        None
+    }
+
+    /// Returns a short, human readable name of the source, used in error messages.
+    ///
+    /// See [`Source::name`].
+    fn name(&self) -> String {
+        utils::type_name::<Self>()
+    }
+}
+
+/// Locks the mutex, a poisoned mutex is still used, sources stay usable after a panic.
+fn lock<S>(mutex: &Mutex<S>) -> MutexGuard<'_, S> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl<S> SyncSource for Mutex<S>
+where
+    S: Source,
+{
+    fn expand_bool<E>(&self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_bool(v)
+    }
+
+    fn expand_i8<E>(&self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_i8(v)
+    }
+
+    fn expand_i16<E>(&self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_i16(v)
+    }
+
+    fn expand_i32<E>(&self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_i32(v)
+    }
+
+    fn expand_i64<E>(&self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_i64(v)
+    }
+
+    fn expand_i128<E>(&self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_i128(v)
+    }
+
+    fn expand_u8<E>(&self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_u8(v)
+    }
+
+    fn expand_u16<E>(&self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_u16(v)
+    }
+
+    fn expand_u32<E>(&self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_u32(v)
+    }
+
+    fn expand_u64<E>(&self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_u64(v)
+    }
+
+    fn expand_u128<E>(&self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_u128(v)
+    }
+
+    fn expand_f32<E>(&self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_f32(v)
+    }
+
+    fn expand_f64<E>(&self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_f64(v)
+    }
+
+    fn expand_str<'a, E>(&self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_str(v)
+    }
+
+    fn expand_bytes<'a, E>(&self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_bytes(v)
+    }
+
+    fn expand_any<'a, E>(&self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_any(v)
+    }
+
+    fn expand_structured<'a, E>(&self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_structured(v)
+    }
+
+    fn expand_map<E>(&self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        lock(self).expand_map(v)
+    }
+
+    fn expands_structured(&self) -> bool {
+        lock(self).expands_structured()
+    }
+
+    fn contains_variable(&self, v: &str) -> bool {
+        lock(self).contains_variable(v)
+    }
+
+    fn references_variable(&self, v: &str) -> Option<bool> {
+        lock(self).references_variable(v)
+    }
+
+    fn name(&self) -> String {
+        lock(self).name()
+    }
+}
+
+macro_rules! impl_source_for_shared {
+    ($($ty:ty),*) => {
+        $(
+            impl<S> Source for $ty
+            where
+                S: SyncSource + ?Sized,
+            {
+                fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_bool(v)
+                }
+
+                fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_i8(v)
+                }
+
+                fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_i16(v)
+                }
+
+                fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_i32(v)
+                }
+
+                fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_i64(v)
+                }
+
+                fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_i128(v)
+                }
+
+                fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_u8(v)
+                }
+
+                fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_u16(v)
+                }
+
+                fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_u32(v)
+                }
+
+                fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_u64(v)
+                }
+
+                fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_u128(v)
+                }
+
+                fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_f32(v)
+                }
+
+                fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_f64(v)
+                }
+
+                fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_str(v)
+                }
+
+                fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_bytes(v)
+                }
+
+                fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_any(v)
+                }
+
+                fn expand_structured<'a, E>(
+                    &mut self,
+                    v: Cow<'a, str>,
+                ) -> Result<Structured<'a>, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_structured(v)
+                }
+
+                fn expand_map<E>(
+                    &mut self,
+                    v: &str,
+                ) -> Result<Option<Vec<(String, String)>>, E>
+                where
+                    E: de::Error,
+                {
+                    (**self).expand_map(v)
+                }
+
+                fn expands_structured(&self) -> bool {
+                    (**self).expands_structured()
+                }
+
+                fn contains_variable(&mut self, v: &str) -> bool {
+                    (**self).contains_variable(v)
+                }
+
+                fn references_variable(&mut self, v: &str) -> Option<bool> {
+                    (**self).references_variable(v)
+                }
+
+                fn name(&self) -> String {
+                    (**self).name()
+                }
+            }
+        )*
+    };
+}
+
+impl_source_for_shared!(&S, Arc<S>);
@@ -0,0 +1,302 @@
+use std::{
+    borrow::Cow,
+    fmt,
+    sync::mpsc::{self, RecvTimeoutError},
+    time::Duration,
+};
+
+use serde::de;
+
+use crate::source::{Any, DynError, Source, Structured};
+
+type Job<S> = Box<dyn FnOnce(&mut S) + Send>;
+
+/// A [`Source`] which bounds how long a single expansion of another source may take.
+///
+/// The wrapped source is moved to a dedicated thread, which expands all variables. Expansions,
+/// which do not finish within the timeout, fail with an error naming the variable, instead of
+/// blocking the deserialization indefinitely, e.g. on a hung network request.
+///
+/// An expansion, which timed out, keeps running in the background and delays all following
+/// expansions, which are still bounded by the timeout. If the wrapped source panics, all
+/// following expansions fail.
+///
+/// Errors of the wrapped source are converted to errors of the deserializer with
+/// [`de::Error::custom`], like with a [`BoxedSource`](crate::source::BoxedSource).
+///
+/// # Examples:
+///
+/// ```
+/// use std::time::Duration;
+/// use serde_vars::{source::TimeoutSource, EnvSource};
+///
+/// let mut source = TimeoutSource::new(EnvSource::default(), Duration::from_secs(5));
+/// # unsafe { std::env::set_var("MY_PORT", "8080"); }
+///
+/// let mut de = serde_json::Deserializer::from_str(r#""${MY_PORT}""#);
+/// let r: u16 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, 8080);
+/// ```
+pub struct TimeoutSource<S> {
+    jobs: mpsc::Sender<Job<S>>,
+    timeout: Duration,
+    name: String,
+    expands_structured: bool,
+}
+
+impl<S> TimeoutSource<S>
+where
+    S: Source + Send + 'static,
+{
+    /// Creates a [`TimeoutSource`], which fails expansions of `source` taking longer than
+    /// `timeout`.
+    pub fn new(source: S, timeout: Duration) -> Self {
+        let name = source.name();
+        let expands_structured = source.expands_structured();
+
+        let (jobs, receiver) = mpsc::channel::<Job<S>>();
+        std::thread::spawn(move || {
+            let mut source = source;
+            for job in receiver {
+                job(&mut source);
+            }
+        });
+
+        Self {
+            jobs,
+            timeout,
+            name,
+            expands_structured,
+        }
+    }
+
+    /// Runs `f` on the thread of the source and waits for its result until the timeout.
+    fn run<T, E>(
+        &self,
+        v: &str,
+        f: impl FnOnce(&mut S) -> Result<T, DynError> + Send + 'static,
+    ) -> Result<T, E>
+    where
+        T: Send + 'static,
+        E: de::Error,
+    {
+        let (result, receiver) = mpsc::sync_channel(1);
+        let job = Box::new(move |source: &mut S| {
+            // The receiver is gone, if the expansion timed out.
+            let _ = result.send(f(source));
+        });
+
+        if self.jobs.send(job).is_err() {
+            return Err(self.panicked(v));
+        }
+        match receiver.recv_timeout(self.timeout) {
+            Ok(result) => result.map_err(E::custom),
+            Err(RecvTimeoutError::Timeout) => Err(E::custom(format!(
+                "got variable `{v}`, but {} did not expand it within {:?}",
+                self.name, self.timeout
+            ))),
+            Err(RecvTimeoutError::Disconnected) => Err(self.panicked(v)),
+        }
+    }
+
+    fn panicked<E>(&self, v: &str) -> E
+    where
+        E: de::Error,
+    {
+        E::custom(format!(
+            "got variable `{v}`, but {} is unavailable after it panicked",
+            self.name
+        ))
+    }
+}
+
+impl<S> fmt::Debug for TimeoutSource<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TimeoutSource")
+            .field("source", &self.name)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl<S> Source for TimeoutSource<S>
+where
+    S: Source + Send + 'static,
+{
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        let var = v.to_owned();
+        self.run(v, move |source| source.expand_bool(&var))
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        let var = v.to_owned();
+        self.run(v, move |source| source.expand_i8(&var))
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        let var = v.to_owned();
+        self.run(v, move |source| source.expand_i16(&var))
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        let var = v.to_owned();
+        self.run(v, move |source| source.expand_i32(&var))
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        let var = v.to_owned();
+        self.run(v, move |source| source.expand_i64(&var))
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        let var = v.to_owned();
+        self.run(v, move |source| source.expand_i128(&var))
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        let var = v.to_owned();
+        self.run(v, move |source| source.expand_u8(&var))
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        let var = v.to_owned();
+        self.run(v, move |source| source.expand_u16(&var))
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        let var = v.to_owned();
+        self.run(v, move |source| source.expand_u32(&var))
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        let var = v.to_owned();
+        self.run(v, move |source| source.expand_u64(&var))
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        let var = v.to_owned();
+        self.run(v, move |source| source.expand_u128(&var))
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        let var = v.to_owned();
+        self.run(v, move |source| source.expand_f32(&var))
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        let var = v.to_owned();
+        self.run(v, move |source| source.expand_f64(&var))
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        let var = v.clone().into_owned();
+        self.run(&v, move |source| {
+            source.expand_str(Cow::Owned(var)).map(Cow::into_owned)
+        })
+        .map(Cow::Owned)
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        let var = v.clone().into_owned();
+        self.run(&String::from_utf8_lossy(&v), move |source| {
+            source.expand_bytes(Cow::Owned(var)).map(Cow::into_owned)
+        })
+        .map(Cow::Owned)
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        let var = v.clone().into_owned();
+        self.run(&v, move |source| {
+            source.expand_any(Cow::Owned(var)).map(Any::into_owned)
+        })
+    }
+
+    fn expand_structured<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        let var = v.clone().into_owned();
+        self.run(&v, move |source| {
+            source
+                .expand_structured(Cow::Owned(var))
+                .map(Structured::into_owned)
+        })
+    }
+
+    fn expand_map<E>(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        let var = v.to_owned();
+        self.run(v, move |source| source.expand_map(&var))
+    }
+
+    fn expands_structured(&self) -> bool {
+        self.expands_structured
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        let var = v.to_owned();
+        // Expansions report sources, which do not respond, with an appropriate error.
+        self.run::<_, DynError>(v, move |source| Ok(source.contains_variable(&var)))
+            .unwrap_or(true)
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        let var = v.to_owned();
+        self.run::<_, DynError>(v, move |source| Ok(source.references_variable(&var)))
+            .unwrap_or(None)
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
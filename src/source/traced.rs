@@ -0,0 +1,255 @@
+use std::borrow::Cow;
+
+use serde::de;
+
+use crate::source::{Any, Source, Structured, Variable};
+
+/// A [`Source`] which emits a [`tracing`] event for every variable expanded by another source.
+///
+/// Every event is emitted with the target `serde_vars` at the `DEBUG` level and contains
+/// the fields:
+///
+/// - `variable`: the name of the variable, e.g. `DATABASE_URL` for `${DATABASE_URL}`.
+/// - `type`: the requested type, e.g. `u16`, `str` or `any`.
+/// - `outcome`: `hit` if the variable was expanded, `miss` if the variable does not exist
+///   (see [`Source::contains_variable`]) or `error` if the expansion failed for other reasons.
+///
+/// Neither expanded values nor error messages, which may contain values, are ever recorded.
+/// Strings which do not reference a variable are passed on without an event.
+///
+/// The wrapped source should use the same variable prefix and suffix as the [`TracedSource`],
+/// by default `${` and `}`.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::{source::TracedSource, EnvSource};
+///
+/// let mut source = TracedSource::new(EnvSource::default());
+/// ```
+#[derive(Debug)]
+pub struct TracedSource<S> {
+    source: S,
+    variable: Variable,
+}
+
+impl<S> TracedSource<S> {
+    /// Creates a [`TracedSource`], which traces all variables expanded by `source`.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            variable: Default::default(),
+        }
+    }
+
+    /// Changes the variable prefix.
+    pub fn with_variable_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.variable.prefix = prefix.into();
+        self
+    }
+
+    /// Changes the variable suffix.
+    pub fn with_variable_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.variable.suffix = suffix.into();
+        self
+    }
+
+    /// Returns the wrapped source.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S> TracedSource<S>
+where
+    S: Source,
+{
+    /// Emits an event for the expansion of `v` to the type `ty`, if `v` is a variable.
+    fn trace<T, E>(&mut self, v: &str, ty: &str, result: &Result<T, E>) {
+        let Some(name) = self.variable.parse_str(v) else {
+            return;
+        };
+
+        let outcome = match result {
+            Ok(_) => "hit",
+            Err(_) if !self.source.contains_variable(v) => "miss",
+            Err(_) => "error",
+        };
+        tracing::debug!(
+            target: "serde_vars",
+            variable = name,
+            r#type = ty,
+            outcome,
+            "expanded variable"
+        );
+    }
+
+    fn traced<T, E>(
+        &mut self,
+        v: &str,
+        ty: &str,
+        expand: impl FnOnce(&mut S) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let result = expand(&mut self.source);
+        self.trace(v, ty, &result);
+        result
+    }
+}
+
+impl<S> Source for TracedSource<S>
+where
+    S: Source,
+{
+    fn expand_bool<E>(&mut self, v: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        self.traced(v, "bool", |source| source.expand_bool(v))
+    }
+
+    fn expand_i8<E>(&mut self, v: &str) -> Result<i8, E>
+    where
+        E: de::Error,
+    {
+        self.traced(v, "i8", |source| source.expand_i8(v))
+    }
+
+    fn expand_i16<E>(&mut self, v: &str) -> Result<i16, E>
+    where
+        E: de::Error,
+    {
+        self.traced(v, "i16", |source| source.expand_i16(v))
+    }
+
+    fn expand_i32<E>(&mut self, v: &str) -> Result<i32, E>
+    where
+        E: de::Error,
+    {
+        self.traced(v, "i32", |source| source.expand_i32(v))
+    }
+
+    fn expand_i64<E>(&mut self, v: &str) -> Result<i64, E>
+    where
+        E: de::Error,
+    {
+        self.traced(v, "i64", |source| source.expand_i64(v))
+    }
+
+    fn expand_i128<E>(&mut self, v: &str) -> Result<i128, E>
+    where
+        E: de::Error,
+    {
+        self.traced(v, "i128", |source| source.expand_i128(v))
+    }
+
+    fn expand_u8<E>(&mut self, v: &str) -> Result<u8, E>
+    where
+        E: de::Error,
+    {
+        self.traced(v, "u8", |source| source.expand_u8(v))
+    }
+
+    fn expand_u16<E>(&mut self, v: &str) -> Result<u16, E>
+    where
+        E: de::Error,
+    {
+        self.traced(v, "u16", |source| source.expand_u16(v))
+    }
+
+    fn expand_u32<E>(&mut self, v: &str) -> Result<u32, E>
+    where
+        E: de::Error,
+    {
+        self.traced(v, "u32", |source| source.expand_u32(v))
+    }
+
+    fn expand_u64<E>(&mut self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        self.traced(v, "u64", |source| source.expand_u64(v))
+    }
+
+    fn expand_u128<E>(&mut self, v: &str) -> Result<u128, E>
+    where
+        E: de::Error,
+    {
+        self.traced(v, "u128", |source| source.expand_u128(v))
+    }
+
+    fn expand_f32<E>(&mut self, v: &str) -> Result<f32, E>
+    where
+        E: de::Error,
+    {
+        self.traced(v, "f32", |source| source.expand_f32(v))
+    }
+
+    fn expand_f64<E>(&mut self, v: &str) -> Result<f64, E>
+    where
+        E: de::Error,
+    {
+        self.traced(v, "f64", |source| source.expand_f64(v))
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: de::Error,
+    {
+        let key = v.clone();
+        self.traced(&key, "str", |source| source.expand_str(v))
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: de::Error,
+    {
+        // Only strings can reference variables.
+        let Ok(key) = std::str::from_utf8(&v).map(str::to_owned) else {
+            return self.source.expand_bytes(v);
+        };
+        self.traced(&key, "bytes", |source| source.expand_bytes(v))
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: de::Error,
+    {
+        let key = v.clone();
+        self.traced(&key, "any", |source| source.expand_any(v))
+    }
+
+    fn expand_structured<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Structured<'a>, E>
+    where
+        E: de::Error,
+    {
+        let key = v.clone();
+        self.traced(&key, "structured", |source| source.expand_structured(v))
+    }
+
+    fn expand_map<E>(&mut self, v: &str) -> Result<Option<Vec<(String, String)>>, E>
+    where
+        E: de::Error,
+    {
+        let result = self.source.expand_map(v);
+        if matches!(result, Ok(None)) {
+            return result;
+        }
+        self.traced(v, "map", |_| result)
+    }
+
+    fn expands_structured(&self) -> bool {
+        self.source.expands_structured()
+    }
+
+    fn contains_variable(&mut self, v: &str) -> bool {
+        self.source.contains_variable(v)
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        self.source.references_variable(v)
+    }
+
+    fn name(&self) -> String {
+        self.source.name()
+    }
+}
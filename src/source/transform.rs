@@ -0,0 +1,79 @@
+use std::borrow::Cow;
+
+use crate::source::{StringLookup, StringSource};
+
+/// A [`StringLookup`] which transforms the values looked up by another lookup.
+///
+/// The transformation is applied to every value found by the wrapped lookup, before it is
+/// converted to the requested type, e.g. to strip a prefix a secret store adds to its values
+/// or to decode values. Values which are not valid UTF-8 are passed on unchanged.
+///
+/// Generally used through [`TransformSource`].
+#[derive(Debug, Clone)]
+pub struct TransformLookup<T, F> {
+    lookup: T,
+    f: F,
+}
+
+impl<T, F> TransformLookup<T, F>
+where
+    F: FnMut(&str) -> String,
+{
+    /// Creates a [`TransformLookup`], which transforms the values of `lookup` with `f`.
+    pub fn new(lookup: T, f: F) -> Self {
+        Self { lookup, f }
+    }
+
+    /// Returns the wrapped lookup.
+    pub fn into_inner(self) -> T {
+        self.lookup
+    }
+}
+
+impl<T, F> StringLookup for TransformLookup<T, F>
+where
+    T: StringLookup,
+    F: FnMut(&str) -> String,
+{
+    fn lookup(&mut self, v: &str) -> Option<Cow<'_, str>> {
+        let value = self.lookup.lookup(v)?;
+        Some(Cow::Owned((self.f)(&value)))
+    }
+
+    fn lookup_bytes(&mut self, v: &str) -> Option<Cow<'_, [u8]>> {
+        let value = self.lookup.lookup_bytes(v)?;
+        match std::str::from_utf8(&value) {
+            Ok(s) => Some(Cow::Owned((self.f)(s).into_bytes())),
+            Err(_) => Some(value),
+        }
+    }
+
+    fn lookup_error(&mut self, v: &str) -> Option<String> {
+        self.lookup.lookup_error(v)
+    }
+
+    fn lookup_names(&mut self, prefix: &str) -> Option<Vec<String>> {
+        self.lookup.lookup_names(prefix)
+    }
+}
+
+/// A source which transforms the values of another [`StringLookup`].
+///
+/// See [`TransformLookup`] and the [`crate`] and [`StringSource`] documentation for more details.
+///
+/// # Examples:
+///
+/// ```
+/// use std::collections::HashMap;
+/// use serde_vars::source::{TransformLookup, TransformSource};
+///
+/// let secrets = HashMap::from([("DB_PASSWORD", "secret:hunter2"), ("DB_PORT", "secret:5432")]);
+/// let mut source = TransformSource::new(TransformLookup::new(secrets, |value: &str| {
+///     value.strip_prefix("secret:").unwrap_or(value).to_owned()
+/// }));
+///
+/// let mut de = serde_json::Deserializer::from_str(r#"["${DB_PASSWORD}", "${DB_PORT}"]"#);
+/// let r: (String, u16) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+/// assert_eq!(r, ("hunter2".to_owned(), 5432));
+/// ```
+pub type TransformSource<T, F> = StringSource<TransformLookup<T, F>>;
@@ -1,52 +1,442 @@
 use std::{borrow::Cow, fmt};
 
-use crate::source::Any;
+use serde::de;
 
-#[derive(Debug)]
-pub struct Variable {
-    pub prefix: String,
-    pub suffix: String,
+use crate::source::{Any, Variable};
+
+/// The marker of a variable, which collects all variables sharing a prefix, e.g. `prefix:FEATURE_`.
+pub const MAP_PREFIX: &str = "prefix:";
+
+/// Merges the map entries `first` and `second`, entries of `first` take precedence.
+pub fn merge_entries(
+    first: Option<Vec<(String, String)>>,
+    second: Option<Vec<(String, String)>>,
+) -> Option<Vec<(String, String)>> {
+    match (first, second) {
+        (Some(mut first), Some(second)) => {
+            let second = second
+                .into_iter()
+                .filter(|(key, _)| !first.iter().any(|(k, _)| k == key))
+                .collect::<Vec<_>>();
+            first.extend(second);
+            Some(first)
+        }
+        (first, second) => first.or(second),
+    }
 }
 
-impl Variable {
-    pub fn parse_str<'a>(&self, v: &'a str) -> Option<&'a str> {
-        v.strip_prefix(&self.prefix)?.strip_suffix(&self.suffix)
+/// Creates an error for a malformed variable, see [`Variable::is_malformed`].
+pub fn malformed_variable<E>(variable: &Variable, unexpected: de::Unexpected<'_>) -> E
+where
+    E: de::Error,
+{
+    let var = variable.fmt("<var>");
+    E::invalid_value(
+        unexpected,
+        &format!("a well-formed variable `{var}`").as_str(),
+    )
+}
+
+/// Removes leading and trailing whitespace from `value`, if `trim` is set.
+///
+/// Borrowed values stay borrowed and owned values are trimmed in place.
+pub fn trim_if(trim: bool, value: Cow<'_, str>) -> Cow<'_, str> {
+    match (trim, value) {
+        (false, value) => value,
+        (true, Cow::Borrowed(value)) => Cow::Borrowed(value.trim()),
+        (true, Cow::Owned(mut value)) => {
+            value.truncate(value.trim_end().len());
+            let start = value.len() - value.trim_start().len();
+            value.drain(..start);
+            Cow::Owned(value)
+        }
     }
+}
 
-    pub fn parse_bytes<'a>(&self, v: &'a [u8]) -> Option<&'a [u8]> {
-        v.strip_prefix(self.prefix.as_bytes())?
-            .strip_suffix(self.suffix.as_bytes())
+/// Converts a raw value into a string, returns `None` if it is not valid UTF-8.
+///
+/// Borrowed values stay borrowed and owned values are converted without copying.
+pub fn into_utf8(value: Cow<'_, [u8]>) -> Option<Cow<'_, str>> {
+    match value {
+        Cow::Borrowed(value) => std::str::from_utf8(value).ok().map(Cow::Borrowed),
+        Cow::Owned(value) => String::from_utf8(value).ok().map(Cow::Owned),
     }
+}
+
+/// Selects the value addressed by `.` separated `keys` in a JSON document.
+///
+/// Keys select members of objects and, as indices, elements of arrays.
+#[cfg(feature = "json")]
+pub fn select<'a>(document: &'a serde_json::Value, keys: &str) -> Option<&'a serde_json::Value> {
+    keys.split('.')
+        .try_fold(document, |value, key| match value {
+            serde_json::Value::Object(map) => map.get(key),
+            serde_json::Value::Array(values) => values.get(key.parse::<usize>().ok()?),
+            _ => None,
+        })
+}
+
+/// Returns a selected JSON value as a variable value.
+///
+/// Strings are returned as is, all other values as JSON.
+#[cfg(feature = "json")]
+pub fn json_scalar(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Options which control how values are parsed into their target types.
+#[derive(Debug, Default)]
+pub struct Parsing {
+    /// Accepts `,` as the decimal separator for floating point values.
+    pub comma_decimals: bool,
+    /// Accepts hexadecimal, octal, binary and underscore separated integers, e.g. `0xFF`.
+    pub integer_literals: bool,
+    /// Accepts common boolean synonyms, e.g. `yes`, `on` and `1`.
+    pub relaxed_bools: bool,
+    /// Accepts durations, e.g. `1h30m`, for integers in the unit.
+    pub duration_unit: Option<DurationUnit>,
+    /// Accepts byte sizes, e.g. `10MB` or `512KiB`, for integers in bytes.
+    pub byte_sizes: bool,
+    /// Accepts numbers with an SI suffix, e.g. `10k` or `1.5M`.
+    pub si_suffixes: bool,
+    /// Accepts the special floating point values `NaN`, `inf` and `-inf`.
+    pub special_floats: bool,
+}
+
+/// The unit of integers parsed from durations.
+///
+/// See [`StringSource::with_duration_suffixes`](crate::StringSource::with_duration_suffixes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationUnit {
+    Nanoseconds,
+    Microseconds,
+    Milliseconds,
+    Seconds,
+    Minutes,
+    Hours,
+}
 
-    pub fn fmt<'a, T>(&'a self, v: T) -> impl fmt::Display + use<'a, T>
-    where
-        T: fmt::Display,
-    {
-        struct D<'a, T> {
-            this: &'a Variable,
-            v: T,
+impl DurationUnit {
+    fn nanos(self) -> u128 {
+        match self {
+            Self::Nanoseconds => 1,
+            Self::Microseconds => 1_000,
+            Self::Milliseconds => 1_000_000,
+            Self::Seconds => 1_000_000_000,
+            Self::Minutes => 60 * 1_000_000_000,
+            Self::Hours => 60 * 60 * 1_000_000_000,
         }
+    }
+}
+
+/// A custom parser for values of type `T`.
+pub type Parser<T> = Box<dyn Fn(&str) -> Result<T, String> + Send + Sync>;
+
+/// Custom parsers, which replace the default parsing of their type.
+#[derive(Default)]
+pub struct Parsers {
+    pub bool: Option<Parser<bool>>,
+    pub i8: Option<Parser<i8>>,
+    pub i16: Option<Parser<i16>>,
+    pub i32: Option<Parser<i32>>,
+    pub i64: Option<Parser<i64>>,
+    pub i128: Option<Parser<i128>>,
+    pub u8: Option<Parser<u8>>,
+    pub u16: Option<Parser<u16>>,
+    pub u32: Option<Parser<u32>>,
+    pub u64: Option<Parser<u64>>,
+    pub u128: Option<Parser<u128>>,
+    pub f32: Option<Parser<f32>>,
+    pub f64: Option<Parser<f64>>,
+}
 
-        impl<T> fmt::Display for D<'_, T>
-        where
-            T: fmt::Display,
-        {
-            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-                write!(f, "{}{}{}", self.this.prefix, self.v, self.this.suffix)
+impl fmt::Debug for Parsers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = [
+            ("bool", self.bool.is_some()),
+            ("i8", self.i8.is_some()),
+            ("i16", self.i16.is_some()),
+            ("i32", self.i32.is_some()),
+            ("i64", self.i64.is_some()),
+            ("i128", self.i128.is_some()),
+            ("u8", self.u8.is_some()),
+            ("u16", self.u16.is_some()),
+            ("u32", self.u32.is_some()),
+            ("u64", self.u64.is_some()),
+            ("u128", self.u128.is_some()),
+            ("f32", self.f32.is_some()),
+            ("f64", self.f64.is_some()),
+        ];
+        f.debug_set()
+            .entries(names.iter().filter(|(_, set)| *set).map(|(name, _)| name))
+            .finish()
+    }
+}
+
+/// A type which can be parsed from a variable value.
+///
+/// Parsing is locale independent and implemented on top of [`std::str::FromStr`].
+pub trait FromValue: Sized {
+    fn from_value(v: &str, parsing: &Parsing) -> Option<Self>;
+
+    /// Returns the custom parser for this type, if there is one.
+    fn parser(parsers: &Parsers) -> Option<&Parser<Self>>;
+
+    /// Returns a more specific expectation for errors, if `v` failed to parse.
+    fn expecting(_v: &str, _parsing: &Parsing) -> Option<&'static str> {
+        None
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(v: &str, parsing: &Parsing) -> Option<Self> {
+        v.parse().ok().or_else(|| match parsing.relaxed_bools {
+            true => parse_relaxed_bool(v),
+            false => None,
+        })
+    }
+
+    fn parser(parsers: &Parsers) -> Option<&Parser<Self>> {
+        parsers.bool.as_ref()
+    }
+}
+
+/// Parses common boolean synonyms, ignoring case.
+fn parse_relaxed_bool(v: &str) -> Option<bool> {
+    match v.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "y" | "on" | "1" => Some(true),
+        "false" | "no" | "n" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+macro_rules! impl_from_value_int {
+    ($($ty:ident),*) => {
+        $(
+            impl FromValue for $ty {
+                fn from_value(v: &str, parsing: &Parsing) -> Option<Self> {
+                    v.parse()
+                        .ok()
+                        .or_else(|| match parsing.integer_literals {
+                            true => {
+                                let (digits, radix) = parse_integer_literal(v)?;
+                                <$ty>::from_str_radix(&digits, radix).ok()
+                            }
+                            false => None,
+                        })
+                        .or_else(|| {
+                            let duration = parse_duration(v, parsing.duration_unit?)?;
+                            duration.try_into().ok()
+                        })
+                        .or_else(|| match parsing.byte_sizes {
+                            true => parse_byte_size(v)?.try_into().ok(),
+                            false => None,
+                        })
+                        .or_else(|| match parsing.si_suffixes {
+                            true => parse_si_integer(v)?.try_into().ok(),
+                            false => None,
+                        })
+                }
+
+                fn parser(parsers: &Parsers) -> Option<&Parser<Self>> {
+                    parsers.$ty.as_ref()
+                }
             }
-        }
+        )*
+    };
+}
 
-        D { this: self, v }
+impl_from_value_int!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+
+/// Parses an integer literal, e.g. `0xFF`, `0o755`, `0b1010` or `1_000_000`,
+/// into its sign and digits and its radix.
+fn parse_integer_literal(v: &str) -> Option<(String, u32)> {
+    let (sign, v) = match v.strip_prefix('-') {
+        Some(v) => ("-", v),
+        None => ("", v.strip_prefix('+').unwrap_or(v)),
+    };
+    let (radix, digits) = match v.get(..2) {
+        Some("0x" | "0X") => (16, &v[2..]),
+        Some("0o" | "0O") => (8, &v[2..]),
+        Some("0b" | "0B") => (2, &v[2..]),
+        _ => (10, v),
+    };
+
+    // Underscores are only allowed between digits.
+    let is_digit = |c: char| c.is_digit(radix);
+    if !digits.starts_with(is_digit) || !digits.ends_with(is_digit) || digits.contains("__") {
+        return None;
     }
+
+    Some((format!("{sign}{}", digits.replace('_', "")), radix))
 }
 
-impl Default for Variable {
-    fn default() -> Self {
-        Self {
-            prefix: "${".to_owned(),
-            suffix: "}".to_owned(),
-        }
+/// Parses a duration, e.g. `5s` or `1h30m`, as an integer number of `unit`.
+///
+/// Durations which are not a whole number of `unit` are rejected.
+fn parse_duration(v: &str, unit: DurationUnit) -> Option<u128> {
+    if v.is_empty() {
+        return None;
     }
+
+    let mut nanos = 0u128;
+    let mut rest = v;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let (value, suffix) = rest.split_at(digits);
+        let value = value.parse::<u128>().ok()?;
+
+        let len = suffix
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(suffix.len());
+        let factor = match &suffix[..len] {
+            "ns" => DurationUnit::Nanoseconds,
+            "us" | "µs" => DurationUnit::Microseconds,
+            "ms" => DurationUnit::Milliseconds,
+            "s" => DurationUnit::Seconds,
+            "m" => DurationUnit::Minutes,
+            "h" => DurationUnit::Hours,
+            _ => return None,
+        };
+
+        nanos = nanos.checked_add(value.checked_mul(factor.nanos())?)?;
+        rest = &suffix[len..];
+    }
+
+    nanos
+        .is_multiple_of(unit.nanos())
+        .then(|| nanos / unit.nanos())
+}
+
+/// Parses a byte size, e.g. `10MB` or `512KiB`, as a number of bytes.
+///
+/// Units are case insensitive, decimal units (`KB`) are multiples of 1000,
+/// binary units (`KiB`) multiples of 1024.
+fn parse_byte_size(v: &str) -> Option<u128> {
+    let digits = v.find(|c: char| !c.is_ascii_digit())?;
+    let (value, unit) = v.split_at(digits);
+    let value = value.parse::<u128>().ok()?;
+
+    let factor: u128 = match unit.trim_start().to_ascii_lowercase().as_str() {
+        "b" => 1,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "tb" => 1_000_000_000_000,
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        "tib" => 1 << 40,
+        _ => return None,
+    };
+
+    value.checked_mul(factor)
+}
+
+/// Splits a number with an SI suffix, e.g. `1.5M`, into the number and its decimal exponent.
+///
+/// The number is made up of an optional sign, digits and an optional decimal point.
+fn split_si_suffix(v: &str) -> Option<(&str, u32)> {
+    let exponent = match v.chars().next_back()? {
+        'k' | 'K' => 3,
+        'M' => 6,
+        'G' => 9,
+        'T' => 12,
+        'P' => 15,
+        _ => return None,
+    };
+    let mantissa = &v[..v.len() - 1];
+
+    let digits = mantissa.strip_prefix(['-', '+']).unwrap_or(mantissa);
+    let is_number = digits.bytes().any(|b| b.is_ascii_digit())
+        && digits.bytes().all(|b| b.is_ascii_digit() || b == b'.')
+        && digits.matches('.').count() <= 1;
+
+    is_number.then_some((mantissa, exponent))
+}
+
+/// Parses an integer with an SI suffix, e.g. `10k` or `1.5M`.
+///
+/// Numbers which are not a whole number after applying the suffix are rejected.
+fn parse_si_integer(v: &str) -> Option<i128> {
+    let (mantissa, exponent) = split_si_suffix(v)?;
+
+    let (negative, digits) = match mantissa.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, mantissa.strip_prefix('+').unwrap_or(mantissa)),
+    };
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    let fraction = fraction.trim_end_matches('0');
+    let scale = exponent.checked_sub(u32::try_from(fraction.len()).ok()?)?;
+
+    let value = format!("{integer}{fraction}").parse::<i128>().ok()?;
+    let value = value.checked_mul(10i128.checked_pow(scale)?)?;
+
+    Some(if negative { -value } else { value })
+}
+
+macro_rules! impl_from_value_float {
+    ($($ty:ident),*) => {
+        $(
+            impl FromValue for $ty {
+                fn from_value(v: &str, parsing: &Parsing) -> Option<Self> {
+                    v.parse()
+                        .ok()
+                        .or_else(|| match parsing.comma_decimals {
+                            true => parse_comma_decimal(v),
+                            false => None,
+                        })
+                        .or_else(|| match parsing.si_suffixes {
+                            true => {
+                                let (mantissa, exponent) = split_si_suffix(v)?;
+                                let mantissa = mantissa.parse::<$ty>().ok()?;
+                                Some(mantissa * <$ty>::powi(10.0, exponent as i32))
+                            }
+                            false => None,
+                        })
+                        // Values which overflow to infinity are never accepted.
+                        .filter(|r: &$ty| {
+                            r.is_finite() || (parsing.special_floats && is_special_float(v))
+                        })
+                }
+
+                fn parser(parsers: &Parsers) -> Option<&Parser<Self>> {
+                    parsers.$ty.as_ref()
+                }
+
+                fn expecting(v: &str, parsing: &Parsing) -> Option<&'static str> {
+                    (!parsing.special_floats && is_special_float(v))
+                        .then_some("a finite floating point")
+                }
+            }
+        )*
+    };
+}
+
+impl_from_value_float!(f32, f64);
+
+/// Returns `true` if `v` is one of the special floating point values `NaN`, `inf` or `infinity`.
+///
+/// Like [`str::parse`], the values are matched case-insensitively with an optional sign.
+fn is_special_float(v: &str) -> bool {
+    let v = v.strip_prefix(['-', '+']).unwrap_or(v);
+    ["nan", "inf", "infinity"]
+        .iter()
+        .any(|special| v.eq_ignore_ascii_case(special))
+}
+
+/// Parses a floating point value with a `,` as the decimal separator, e.g. `3,14`.
+fn parse_comma_decimal<T>(v: &str) -> Option<T>
+where
+    T: std::str::FromStr,
+{
+    if v.contains('.') || v.matches(',').count() != 1 {
+        return None;
+    }
+    v.replacen(',', ".", 1).parse().ok()
 }
 
 pub fn parse(s: Cow<'_, str>) -> Any<'_> {
@@ -54,14 +444,169 @@ pub fn parse(s: Cow<'_, str>) -> Any<'_> {
         "true" => Any::Bool(true),
         "false" => Any::Bool(false),
         // Try in order:
-        //  - parse f64
         //  - parse u64
+        //  - parse i64
+        //  - parse u128 and i128, unless numbers are passed on with arbitrary precision
+        //  - keep all remaining integers as an arbitrary precision number
+        //  - parse f64
         //  - use the literal string
-        v => v
-            .parse()
-            .map(Any::U64)
-            .or_else(|_| v.parse().map(Any::I64))
-            .or_else(|_| v.parse().map(Any::F64))
-            .unwrap_or(Any::Str(s)),
+        v => match parse_integer(v) {
+            Some(v) => v,
+            None if is_integer(v) => Any::Number(s),
+            None => v.parse().map(Any::F64).unwrap_or(Any::Str(s)),
+        },
+    }
+}
+
+fn parse_integer(v: &str) -> Option<Any<'static>> {
+    let r = v.parse().map(Any::U64).or_else(|_| v.parse().map(Any::I64));
+    #[cfg(not(feature = "arbitrary_precision"))]
+    let r = r
+        .or_else(|_| v.parse().map(Any::U128))
+        .or_else(|_| v.parse().map(Any::I128));
+    r.ok()
+}
+
+/// Decodes base64 encoded data, accepting the standard and the URL safe alphabet.
+///
+/// Padding is optional and whitespace is ignored.
+pub fn decode_base64(v: &str) -> Option<Vec<u8>> {
+    fn decode(b: u8) -> Option<u32> {
+        let v = match b {
+            b'A'..=b'Z' => b - b'A',
+            b'a'..=b'z' => b - b'a' + 26,
+            b'0'..=b'9' => b - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        Some(u32::from(v))
+    }
+
+    let v = v.trim_end_matches(|c: char| c == '=' || c.is_ascii_whitespace());
+
+    let mut result = Vec::with_capacity(v.len() / 4 * 3);
+    let (mut buf, mut bits, mut len) = (0u32, 0, 0);
+    for b in v.bytes().filter(|b| !b.is_ascii_whitespace()) {
+        buf = (buf << 6) | decode(b)?;
+        bits += 6;
+        len += 1;
+        if bits >= 8 {
+            bits -= 8;
+            result.push((buf >> bits) as u8);
+            buf &= (1 << bits) - 1;
+        }
+    }
+
+    (len % 4 != 1).then_some(result)
+}
+
+/// Encodes data as base64 using the standard alphabet with padding.
+pub fn encode_base64(v: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut result = String::with_capacity(v.len().div_ceil(3) * 4);
+    for chunk in v.chunks(3) {
+        let buf = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |buf, (i, &b)| buf | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => result.push(char::from(ALPHABET[(buf >> (18 - 6 * i) & 0x3f) as usize])),
+                false => result.push('='),
+            }
+        }
     }
+    result
+}
+
+/// Percent encodes all characters of `v`, except unreserved characters and the characters in `keep`.
+#[cfg(feature = "http")]
+pub fn encode_percent(v: &str, keep: &[u8]) -> String {
+    let mut result = String::with_capacity(v.len());
+    for &b in v.as_bytes() {
+        match b.is_ascii_alphanumeric() || b"-._~".contains(&b) || keep.contains(&b) {
+            true => result.push(char::from(b)),
+            false => result.push_str(&format!("%{b:02X}")),
+        }
+    }
+    result
+}
+
+/// Decodes percent encoded data, e.g. `my%20file`.
+///
+/// Invalid escape sequences are kept as is.
+pub fn decode_percent(v: &str) -> Vec<u8> {
+    let v = v.as_bytes();
+
+    let mut result = Vec::with_capacity(v.len());
+    let mut i = 0;
+    while i < v.len() {
+        let escaped = v
+            .get(i + 1..i + 3)
+            .filter(|_| v[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(b) => {
+                result.push(b);
+                i += 3;
+            }
+            None => {
+                result.push(v[i]);
+                i += 1;
+            }
+        }
+    }
+    result
+}
+
+/// Decodes hex encoded data, e.g. `deadbeef`.
+///
+/// Both lowercase and uppercase digits are accepted, an optional `0x` prefix is ignored.
+pub fn decode_hex(v: &str) -> Option<Vec<u8>> {
+    let v = v.strip_prefix("0x").unwrap_or(v);
+    if !v.len().is_multiple_of(2) {
+        return None;
+    }
+
+    v.as_bytes()
+        .chunks_exact(2)
+        .map(|pair| {
+            let hi = char::from(pair[0]).to_digit(16)?;
+            let lo = char::from(pair[1]).to_digit(16)?;
+            Some((hi << 4 | lo) as u8)
+        })
+        .collect()
+}
+
+/// Returns the name of the type `T` without module paths, e.g. `StringSource<EnvLookup>`.
+pub fn type_name<T: ?Sized>() -> String {
+    let name = std::any::type_name::<T>();
+
+    let mut result = String::with_capacity(name.len());
+    let mut segment = 0;
+    for (i, c) in name.char_indices() {
+        if c.is_alphanumeric() || c == '_' {
+            continue;
+        }
+        // Module paths are dropped, everything else, like generic arguments, is kept.
+        match name[i..].starts_with("::") {
+            true => segment = i + 2,
+            false if c == ':' => {}
+            false => {
+                result.push_str(&name[segment.min(i)..i]);
+                result.push(c);
+                segment = i + c.len_utf8();
+            }
+        }
+    }
+    result.push_str(&name[segment.min(name.len())..]);
+    result
+}
+
+fn is_integer(v: &str) -> bool {
+    let digits = v.strip_prefix(['-', '+']).unwrap_or(v);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
 }
@@ -0,0 +1,300 @@
+use std::{fmt, sync::Arc};
+
+/// The placeholder grammar of variables, e.g. `${VAR}`.
+///
+/// A [`Variable`] recognizes variables by their prefix and suffix and is used by all sources
+/// of this crate. Custom [`Source`](crate::source::Source) implementations can use it to
+/// recognize exactly the same variables.
+///
+/// By default variables use `${` and `}` as prefix and suffix.
+///
+/// # Examples:
+///
+/// ```
+/// use serde_vars::source::Variable;
+///
+/// let variable = Variable::new("{{", "}}").with_alternative("${", "}");
+///
+/// assert_eq!(variable.parse_str("{{HOST}}"), Some("HOST"));
+/// assert_eq!(variable.parse_str("${HOST}"), Some("HOST"));
+/// assert_eq!(variable.parse_str("HOST"), None);
+/// assert_eq!(variable.find("http://${HOST}/"), Some(("http://", "HOST", "/")));
+/// assert_eq!(variable.fmt("HOST").to_string(), "{{HOST}}");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Variable {
+    pub(crate) prefix: String,
+    pub(crate) suffix: String,
+    /// Additional prefix and suffix pairs, tried in order after the prefix and suffix.
+    pub(crate) alternatives: Vec<(String, String)>,
+    /// Characters allowed in variable names, names are not validated if unset.
+    pub(crate) charset: Option<Charset>,
+    /// Variables without a suffix, which end before the first character not allowed
+    /// in a variable name, e.g. `$VAR`.
+    pub(crate) bare: bool,
+}
+
+impl Variable {
+    /// Creates a [`Self`] with the specified prefix and suffix.
+    pub fn new(prefix: impl Into<String>, suffix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            suffix: suffix.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a [`Self`] for brace-less variables, e.g. `$VAR`.
+    ///
+    /// Brace-less variables end before the first character, which is not allowed in a variable
+    /// name, see [`Self::with_charset`]. Names must not start with a digit.
+    pub fn braceless(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            suffix: String::new(),
+            bare: true,
+            ..Default::default()
+        }
+    }
+
+    /// Adds an alternative prefix and suffix pair, which is tried after all previous pairs.
+    pub fn with_alternative(
+        mut self,
+        prefix: impl Into<String>,
+        suffix: impl Into<String>,
+    ) -> Self {
+        self.alternatives.push((prefix.into(), suffix.into()));
+        self
+    }
+
+    /// Restricts variable names to the characters accepted by `charset`.
+    ///
+    /// Without a charset, all names are valid and brace-less variable names
+    /// are made up of `[A-Za-z0-9_]`.
+    pub fn with_charset<F>(mut self, charset: F) -> Self
+    where
+        F: Fn(char) -> bool + Send + Sync + 'static,
+    {
+        self.charset = Some(Charset::new(charset));
+        self
+    }
+
+    /// Returns the variable prefix.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Returns the variable suffix.
+    pub fn suffix(&self) -> &str {
+        &self.suffix
+    }
+
+    /// Returns `true` if `name` is a valid variable name.
+    ///
+    /// A valid name is not empty and only contains characters of the configured charset,
+    /// all names are valid if no charset is configured.
+    pub fn is_valid_name(&self, name: &str) -> bool {
+        match &self.charset {
+            Some(charset) => !name.is_empty() && name.chars().all(|c| charset.contains(c)),
+            None => true,
+        }
+    }
+
+    /// Returns the length of the bare variable name at the start of `v`.
+    fn bare_name_len(&self, v: &str) -> Option<usize> {
+        let is_name_char = |c: char| match &self.charset {
+            Some(charset) => charset.contains(c),
+            None => is_default_name_char(c),
+        };
+
+        if v.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        let len = v.find(|c| !is_name_char(c)).unwrap_or(v.len());
+        (len > 0).then_some(len)
+    }
+
+    /// Returns all prefix and suffix pairs in order, excluding the prefix of bare variables.
+    fn delimiters(&self) -> impl Iterator<Item = (&str, &str)> {
+        std::iter::once((self.prefix.as_str(), self.suffix.as_str()))
+            .chain(
+                self.alternatives
+                    .iter()
+                    .map(|(prefix, suffix)| (prefix.as_str(), suffix.as_str())),
+            )
+            .skip(usize::from(self.bare))
+    }
+
+    /// Returns the variable name, if the entire string `v` is a variable.
+    pub fn parse_str<'a>(&self, v: &'a str) -> Option<&'a str> {
+        if self.bare {
+            let name = v.strip_prefix(&self.prefix);
+            if let Some(name) = name.filter(|name| self.bare_name_len(name) == Some(name.len())) {
+                return Some(name);
+            }
+        }
+
+        self.delimiters()
+            .find_map(|(prefix, suffix)| v.strip_prefix(prefix)?.strip_suffix(suffix))
+    }
+
+    /// Like [`Self::parse_str`], but for bytes.
+    pub fn parse_bytes<'a>(&self, v: &'a [u8]) -> Option<&'a [u8]> {
+        if self.bare {
+            if let Some(name) = std::str::from_utf8(v).ok().and_then(|v| self.parse_str(v)) {
+                return Some(name.as_bytes());
+            }
+        }
+
+        self.delimiters().find_map(|(prefix, suffix)| {
+            v.strip_prefix(prefix.as_bytes())?
+                .strip_suffix(suffix.as_bytes())
+        })
+    }
+
+    /// Returns `true` if `v` resembles a variable, but is not a well-formed variable.
+    ///
+    /// For example a variable with a missing suffix, `${FOO`, or with stray whitespace, `$ {FOO}`.
+    pub fn is_malformed(&self, v: &str) -> bool {
+        if self.parse_str(v).is_some() {
+            return false;
+        }
+        if self
+            .delimiters()
+            .any(|(prefix, _)| !prefix.is_empty() && v.starts_with(prefix))
+        {
+            return true;
+        }
+
+        let compact = v.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+        compact.len() != v.len() && self.parse_str(&compact).is_some()
+    }
+
+    /// Like [`Self::is_malformed`], but for bytes.
+    pub fn is_malformed_bytes(&self, v: &[u8]) -> bool {
+        match std::str::from_utf8(v) {
+            Ok(v) => self.is_malformed(v),
+            Err(_) => {
+                self.parse_bytes(v).is_none()
+                    && self
+                        .delimiters()
+                        .any(|(prefix, _)| !prefix.is_empty() && v.starts_with(prefix.as_bytes()))
+            }
+        }
+    }
+
+    /// Finds the first variable contained in `v`.
+    ///
+    /// Returns the text preceding the variable, the variable and the remaining text.
+    pub fn find<'a>(&self, v: &'a str) -> Option<(&'a str, &'a str, &'a str)> {
+        // Among all delimiters, the earliest variable wins, ties are resolved in order.
+        self.find_bare(v)
+            .into_iter()
+            .chain(
+                self.delimiters()
+                    .filter_map(|(prefix, suffix)| find(v, prefix, suffix)),
+            )
+            .min_by_key(|(before, _, _)| before.len())
+    }
+
+    fn find_bare<'a>(&self, v: &'a str) -> Option<(&'a str, &'a str, &'a str)> {
+        if !self.bare || self.prefix.is_empty() {
+            return None;
+        }
+
+        let mut offset = 0;
+        while let Some(start) = v[offset..].find(&self.prefix).map(|i| offset + i) {
+            let rest = &v[start + self.prefix.len()..];
+            if let Some(len) = self.bare_name_len(rest) {
+                return Some((&v[..start], &rest[..len], &rest[len..]));
+            }
+            offset = start + self.prefix.len();
+        }
+
+        None
+    }
+
+    /// Formats `v` as a variable, e.g. `VAR` as `${VAR}`.
+    pub fn fmt<'a, T>(&'a self, v: T) -> impl fmt::Display + use<'a, T>
+    where
+        T: fmt::Display,
+    {
+        struct D<'a, T> {
+            this: &'a Variable,
+            v: T,
+        }
+
+        impl<T> fmt::Display for D<'_, T>
+        where
+            T: fmt::Display,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}{}{}", self.this.prefix, self.v, self.this.suffix)
+            }
+        }
+
+        D { this: self, v }
+    }
+}
+
+fn find<'a>(v: &'a str, prefix: &str, suffix: &str) -> Option<(&'a str, &'a str, &'a str)> {
+    if prefix.is_empty() {
+        return None;
+    }
+
+    let (before, rest) = v.split_at(v.find(prefix)?);
+    let rest = &rest[prefix.len()..];
+    if suffix.is_empty() {
+        return Some((before, rest, ""));
+    }
+
+    let end = rest.find(suffix)?;
+    Some((before, &rest[..end], &rest[end + suffix.len()..]))
+}
+
+/// Returns `true` if `c` is part of the default variable name charset, `[A-Za-z0-9_]`.
+fn is_default_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// A set of characters allowed in variable names.
+#[derive(Clone)]
+pub(crate) struct Charset(Arc<dyn Fn(char) -> bool + Send + Sync>);
+
+impl Charset {
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(char) -> bool + Send + Sync + 'static,
+    {
+        Self(Arc::new(f))
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        (self.0)(c)
+    }
+}
+
+impl Default for Charset {
+    /// The default charset, `[A-Za-z0-9_]`.
+    fn default() -> Self {
+        Self::new(is_default_name_char)
+    }
+}
+
+impl fmt::Debug for Charset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Charset").finish_non_exhaustive()
+    }
+}
+
+impl Default for Variable {
+    fn default() -> Self {
+        Self {
+            prefix: "${".to_owned(),
+            suffix: "}".to_owned(),
+            alternatives: Vec::new(),
+            charset: None,
+            bare: false,
+        }
+    }
+}
@@ -1,60 +1,86 @@
+use std::borrow::Cow;
+
 use serde::de;
 
 pub fn deserialize_byte_buf<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
 where
     D: de::Deserializer<'de>,
 {
-    struct Visitor;
+    deserializer
+        .deserialize_byte_buf(BytesVisitor)
+        .map(Cow::into_owned)
+}
 
-    impl<'de> de::Visitor<'de> for Visitor {
-        type Value = Vec<u8>;
+/// Deserializes bytes, borrowing them from the deserializer if possible.
+pub fn deserialize_bytes<'de, D>(deserializer: D) -> Result<Cow<'de, [u8]>, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    deserializer.deserialize_bytes(BytesVisitor)
+}
 
-        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-            formatter.write_str("byte array")
-        }
+struct BytesVisitor;
 
-        fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
-        where
-            E: de::Error,
-        {
-            Ok(v.to_vec())
-        }
+impl<'de> de::Visitor<'de> for BytesVisitor {
+    type Value = Cow<'de, [u8]>;
 
-        fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
-        where
-            E: de::Error,
-        {
-            Ok(v)
-        }
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("byte array")
+    }
 
-        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
-        where
-            E: de::Error,
-        {
-            Ok(v.as_bytes().to_vec())
-        }
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Cow::Owned(v.to_vec()))
+    }
 
-        fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
-        where
-            E: de::Error,
-        {
-            Ok(v.into_bytes())
-        }
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Cow::Borrowed(v))
+    }
 
-        fn visit_seq<V>(self, mut visitor: V) -> Result<Self::Value, V::Error>
-        where
-            V: de::SeqAccess<'de>,
-        {
-            let len = std::cmp::min(visitor.size_hint().unwrap_or(0), 4096);
-            let mut bytes = Vec::with_capacity(len);
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Cow::Owned(v))
+    }
 
-            while let Some(b) = visitor.next_element()? {
-                bytes.push(b);
-            }
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Cow::Owned(v.as_bytes().to_vec()))
+    }
 
-            Ok(bytes)
-        }
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Cow::Borrowed(v.as_bytes()))
     }
 
-    deserializer.deserialize_byte_buf(Visitor)
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Cow::Owned(v.into_bytes()))
+    }
+
+    fn visit_seq<V>(self, mut visitor: V) -> Result<Self::Value, V::Error>
+    where
+        V: de::SeqAccess<'de>,
+    {
+        let len = std::cmp::min(visitor.size_hint().unwrap_or(0), 4096);
+        let mut bytes = Vec::with_capacity(len);
+
+        while let Some(b) = visitor.next_element()? {
+            bytes.push(b);
+        }
+
+        Ok(Cow::Owned(bytes))
+    }
 }
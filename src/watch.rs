@@ -0,0 +1,208 @@
+//! Notifications for changes of files read by a [`FileSource`].
+//!
+//! Requires the `notify` feature.
+//!
+//! After deserializing a configuration, [`watch`] observes all files the source accessed and
+//! invokes a callback whenever one of them changes. Applications can use this to deserialize the
+//! configuration again, for example to pick up rotated secrets.
+//!
+//! Instead of the files themselves, their directories are watched. This also detects files which
+//! are replaced atomically, files which are created after they were referenced and changes of
+//! symbolic links, like the links Kubernetes uses for mounted secrets.
+//!
+//! A [`FileSource`] can also discard cached contents of changed files itself,
+//! see [`FileSource::with_cache_invalidation`].
+//!
+//! # Examples:
+//!
+//! ```
+//! # let temp = tempfile::tempdir().unwrap();
+//! # std::fs::write(temp.path().join("token"), "secret").unwrap();
+//! #
+//! use serde_vars::FileSource;
+//!
+//! let mut source = FileSource::new();
+//! # let mut source = source.with_base_path(temp.path());
+//!
+//! let mut de = serde_json::Deserializer::from_str(r#""${token}""#);
+//! let token: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+//!
+//! let (tx, rx) = std::sync::mpsc::channel();
+//! let _watcher = serde_vars::watch::watch(&source, move |changed| {
+//!     let _ = tx.send(changed.to_vec());
+//! })
+//! .unwrap();
+//!
+//! # std::fs::write(temp.path().join("token"), "rotated").unwrap();
+//! // Blocks until one of the files changed.
+//! let changed = rx.recv().unwrap();
+//! # assert_eq!(changed, [temp.path().join("token")]);
+//! ```
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::SystemTime,
+};
+
+use notify::Watcher as _;
+
+use crate::FileSource;
+
+/// Watches files for changes, created by [`watch`].
+///
+/// Files are watched until the watcher is dropped.
+pub struct Watcher {
+    _watcher: notify::RecommendedWatcher,
+}
+
+impl fmt::Debug for Watcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Watcher").finish_non_exhaustive()
+    }
+}
+
+/// Watches all files accessed by `source`, see [`FileSource::files`].
+///
+/// The `callback` is invoked from a background thread with the paths of all changed files.
+/// Files accessed by the source after the watcher was created are not watched.
+pub fn watch<F>(source: &FileSource, mut callback: F) -> notify::Result<Watcher>
+where
+    F: FnMut(&[PathBuf]) + Send + 'static,
+{
+    let mut snapshots: HashMap<PathBuf, Option<Snapshot>> = source
+        .files()
+        .map(|path| (path.to_owned(), Snapshot::of(path)))
+        .collect();
+    let directories: BTreeSet<PathBuf> = snapshots.keys().map(|path| directory(path)).collect();
+
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if event.is_ok_and(|event| event.kind.is_access()) {
+            return;
+        }
+
+        let mut changed: Vec<PathBuf> = snapshots
+            .iter_mut()
+            .filter_map(|(path, snapshot)| {
+                let current = Snapshot::of(path);
+                (current != *snapshot).then(|| {
+                    *snapshot = current;
+                    path.clone()
+                })
+            })
+            .collect();
+
+        if !changed.is_empty() {
+            changed.sort();
+            callback(&changed);
+        }
+    })?;
+
+    for directory in directories {
+        watcher.watch(&directory, notify::RecursiveMode::NonRecursive)?;
+    }
+
+    Ok(Watcher { _watcher: watcher })
+}
+
+/// Discards the cached contents of a [`FileSource`] when files change,
+/// see [`FileSource::with_cache_invalidation`].
+pub(crate) struct Invalidation {
+    watcher: Option<notify::RecommendedWatcher>,
+    directories: BTreeSet<PathBuf>,
+    changed: Arc<AtomicBool>,
+}
+
+impl Invalidation {
+    pub(crate) fn new() -> Self {
+        Self {
+            watcher: None,
+            directories: BTreeSet::new(),
+            changed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Watches the directory of the file `path` for changes.
+    ///
+    /// Returns `false` if the directory cannot be watched, contents of the file must not be
+    /// cached in this case.
+    pub(crate) fn watch(&mut self, path: &Path) -> bool {
+        let directory = directory(path);
+        if self.directories.contains(&directory) {
+            return true;
+        }
+
+        let watcher = match &mut self.watcher {
+            Some(watcher) => watcher,
+            None => {
+                let changed = Arc::clone(&self.changed);
+                let watcher =
+                    notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                        if !event.is_ok_and(|event| event.kind.is_access()) {
+                            changed.store(true, Ordering::Release);
+                        }
+                    });
+                match watcher {
+                    Ok(watcher) => self.watcher.insert(watcher),
+                    Err(_) => return false,
+                }
+            }
+        };
+
+        if watcher
+            .watch(&directory, notify::RecursiveMode::NonRecursive)
+            .is_err()
+        {
+            return false;
+        }
+        self.directories.insert(directory);
+        true
+    }
+
+    /// Returns `true` if a watched directory changed since the last call.
+    pub(crate) fn take_changed(&self) -> bool {
+        self.changed.swap(false, Ordering::AcqRel)
+    }
+}
+
+impl fmt::Debug for Invalidation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Invalidation")
+            .field("directories", &self.directories)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the directory containing the file `path`.
+fn directory(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_owned(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// State of a file used to detect changes.
+#[derive(Debug, PartialEq)]
+struct Snapshot {
+    /// The target of the path, after resolving all symbolic links.
+    target: PathBuf,
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl Snapshot {
+    fn of(path: &Path) -> Option<Self> {
+        let target = path.canonicalize().ok()?;
+        let metadata = std::fs::metadata(&target).ok()?;
+        Some(Self {
+            target,
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use serde_vars::{
+    source::{BoxedSource, DynSource, RoutingSource},
+    FileSource, MapSource,
+};
+
+fn map(values: &[(&str, &str)]) -> MapSource {
+    let values = values
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect::<HashMap<_, _>>();
+    MapSource::new(values)
+}
+
+#[test]
+fn test_boxed() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(tempdir.path().join("secret"), "hunter2").unwrap();
+
+    let sources: Vec<(&str, Box<dyn DynSource>)> = vec![
+        ("cfg", Box::new(map(&[("USER", "admin"), ("PORT", "8080")]))),
+        (
+            "file",
+            Box::new(FileSource::new().with_base_path(tempdir.path())),
+        ),
+    ];
+    assert_eq!(sources[1].1.erased_name(), "FileSource");
+
+    let mut source = sources.into_iter().fold(
+        BoxedSource::new(map(&[("USER", "default")])),
+        |fallback, (scheme, source)| {
+            BoxedSource::new(RoutingSource::new(
+                scheme,
+                BoxedSource::from(source),
+                fallback,
+            ))
+        },
+    );
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${cfg:USER}", "${file:secret}", "${USER}", "${cfg:PORT}", "plain"]"#,
+    );
+    let r: (String, String, String, u16, String) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(
+        r,
+        (
+            "admin".to_owned(),
+            "hunter2".to_owned(),
+            "default".to_owned(),
+            8080,
+            "plain".to_owned()
+        )
+    );
+
+    let mut de = serde_json::Deserializer::from_str(r#""${file:secret}""#);
+    let r: serde_bytes::ByteBuf = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.as_slice(), b"hunter2");
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${cfg:PORT}", "plain"]"#);
+    let r: serde_json::Value = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, serde_json::json!([8080, "plain"]));
+}
+
+#[test]
+fn test_boxed_errors() {
+    let mut source = BoxedSource::new(map(&[("USER", "admin")]));
+    insta::assert_debug_snapshot!(source, @r#"
+    BoxedSource(
+        "StringSource<HashMap<String, String>>",
+    )
+    "#);
+
+    let mut de = serde_json::Deserializer::from_str(r#"{"port": "${USER}"}"#);
+    let err =
+        serde_vars::deserialize::<_, _, HashMap<String, u16>>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"invalid value: string "admin", expected variable `${USER}` to be an unsigned integer (i16) at line 1 column 19"#);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${PORT}""#);
+    let err = serde_vars::deserialize::<_, _, u16>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${PORT}`, but it does not exist"#);
+}
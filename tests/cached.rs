@@ -0,0 +1,148 @@
+use std::{borrow::Cow, cell::Cell, rc::Rc, time::Duration};
+
+use serde_vars::{
+    source::{CachedSource, StringLookup},
+    StringSource,
+};
+
+/// A lookup which counts how often variables are looked up.
+struct Counting(Rc<Cell<usize>>);
+
+impl StringLookup for Counting {
+    fn lookup(&mut self, v: &str) -> Option<Cow<'_, str>> {
+        self.0.set(self.0.get() + 1);
+        match v {
+            "PORT" => Some(Cow::Borrowed("5432")),
+            "HOST" => Some(Cow::Borrowed("localhost")),
+            "RATIO" => Some(Cow::Borrowed("0.5")),
+            "FLAGS" => Some(Cow::Borrowed("1,2")),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_cached() {
+    let lookups = Rc::new(Cell::new(0));
+    let mut source =
+        CachedSource::new(StringSource::new(Counting(Rc::clone(&lookups))).with_seq_delimiter(","));
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${PORT}", "${PORT}", "${HOST}", "${HOST}", "plain", "${PORT}"]"#,
+    );
+    let r: (u16, u16, String, String, String, u32) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.0, 5432);
+    assert_eq!(r.1, 5432);
+    assert_eq!(r.2, "localhost");
+    assert_eq!(r.3, "localhost");
+    assert_eq!(r.4, "plain");
+    // The same variable is cached separately for every target type.
+    assert_eq!(r.5, 5432);
+    assert_eq!(lookups.get(), 3);
+
+    let mut de =
+        serde_json::Deserializer::from_str(r#"[["${FLAGS}", "${FLAGS}"], "${HOST}", "${HOST}"]"#);
+    let r: (Vec<Vec<u8>>, serde_json::Value, serde_json::Value) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.0, [[1, 2], [1, 2]]);
+    assert_eq!(r.1, "localhost");
+    assert_eq!(r.2, "localhost");
+    assert_eq!(lookups.get(), 5);
+
+    // Failed expansions are not cached.
+    for _ in 0..2 {
+        let mut de = serde_json::Deserializer::from_str(r#""${MISSING}""#);
+        let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+        assert!(r.is_err());
+    }
+    assert_eq!(lookups.get(), 7);
+
+    source.clear();
+    let mut de = serde_json::Deserializer::from_str(r#""${PORT}""#);
+    let r: u16 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, 5432);
+    assert_eq!(lookups.get(), 8);
+}
+
+#[test]
+fn test_warm_up() {
+    let lookups = Rc::new(Cell::new(0));
+    let mut source = CachedSource::new(StringSource::new(Counting(Rc::clone(&lookups))));
+
+    source
+        .warm_up::<serde_json::Error, _>(["${PORT}", "${HOST}", "${RATIO}", "plain"])
+        .unwrap();
+    assert_eq!(lookups.get(), 3);
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${PORT}", "${PORT}", "${PORT}", "${HOST}", "${HOST}", "${RATIO}", "plain"]"#,
+    );
+    let r: (u16, i64, f64, String, serde_bytes::ByteBuf, f64, String) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.0, 5432);
+    assert_eq!(r.1, 5432);
+    assert_eq!(r.2, 5432.0);
+    assert_eq!(r.3, "localhost");
+    assert_eq!(r.4.as_slice(), b"localhost");
+    assert_eq!(r.5, 0.5);
+    assert_eq!(r.6, "plain");
+    assert_eq!(lookups.get(), 3);
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${PORT}", "${HOST}"]"#);
+    let r: (serde_json::Value, serde_json::Value) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.0, 5432);
+    assert_eq!(r.1, "localhost");
+    assert_eq!(lookups.get(), 3);
+
+    // Values which cannot be represented in the target type are expanded by the source.
+    let mut de = serde_json::Deserializer::from_str(r#"["${PORT}", "${HOST}"]"#);
+    let r: Result<(u8, String), _> = serde_vars::deserialize(&mut de, &mut source);
+    insta::assert_debug_snapshot!(r.unwrap_err(), @r#"Error("invalid value: string \"5432\", expected variable `${PORT}` to be an unsigned integer (i8)", line: 1, column: 12)"#);
+    assert_eq!(lookups.get(), 4);
+}
+
+#[test]
+fn test_warm_up_error() {
+    let lookups = Rc::new(Cell::new(0));
+    let mut source = CachedSource::new(StringSource::new(Counting(Rc::clone(&lookups))));
+
+    let err = source
+        .warm_up::<serde_json::Error, _>(["${MISSING}", "${PORT}"])
+        .unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${MISSING}`, but it does not exist"#);
+
+    // Expansion stops at the first error, `PORT` is not cached.
+    let before = lookups.get();
+    let mut de = serde_json::Deserializer::from_str(r#""${PORT}""#);
+    let r: u16 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, 5432);
+    assert_eq!(lookups.get(), before + 1);
+}
+
+#[test]
+fn test_cached_ttl() {
+    let lookups = Rc::new(Cell::new(0));
+    let mut source = CachedSource::new(StringSource::new(Counting(Rc::clone(&lookups))))
+        .with_ttl(Duration::from_millis(100));
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${PORT}", "${PORT}", "${HOST}"]"#);
+    let r: (u16, u16, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, (5432, 5432, "localhost".to_owned()));
+    assert_eq!(lookups.get(), 2);
+
+    std::thread::sleep(Duration::from_millis(150));
+
+    // Expired values are expanded again and cached for another period.
+    let mut de = serde_json::Deserializer::from_str(r#"["${PORT}", "${PORT}"]"#);
+    let r: (u16, u16) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, (5432, 5432));
+    assert_eq!(lookups.get(), 3);
+
+    source.invalidate("${PORT}");
+    let mut de = serde_json::Deserializer::from_str(r#"["${PORT}", "${PORT}"]"#);
+    let r: (u16, u16) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, (5432, 5432));
+    assert_eq!(lookups.get(), 4);
+}
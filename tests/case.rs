@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use serde_vars::{
+    source::{Case, MapCase, Source},
+    MapSource,
+};
+
+fn map(values: &[(&str, &str)]) -> MapSource {
+    let values = values
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect::<HashMap<_, _>>();
+    MapSource::new(values)
+}
+
+#[test]
+fn test_case_convert() {
+    let names = [
+        "database-url",
+        "database_url",
+        "DATABASE_URL",
+        "databaseUrl",
+        "DatabaseURL",
+        "database.url",
+        "httpServerURL2",
+        "v2Api",
+        "  spaced  name ",
+        "",
+    ];
+
+    let converted = [
+        Case::Snake,
+        Case::ScreamingSnake,
+        Case::Kebab,
+        Case::Camel,
+        Case::Pascal,
+    ]
+    .map(|case| {
+        let names = names.map(|name| case.convert(name));
+        (case, names)
+    });
+
+    insta::assert_debug_snapshot!(converted, @r#"
+    [
+        (
+            Snake,
+            [
+                "database_url",
+                "database_url",
+                "database_url",
+                "database_url",
+                "database_url",
+                "database_url",
+                "http_server_url2",
+                "v2_api",
+                "spaced_name",
+                "",
+            ],
+        ),
+        (
+            ScreamingSnake,
+            [
+                "DATABASE_URL",
+                "DATABASE_URL",
+                "DATABASE_URL",
+                "DATABASE_URL",
+                "DATABASE_URL",
+                "DATABASE_URL",
+                "HTTP_SERVER_URL2",
+                "V2_API",
+                "SPACED_NAME",
+                "",
+            ],
+        ),
+        (
+            Kebab,
+            [
+                "database-url",
+                "database-url",
+                "database-url",
+                "database-url",
+                "database-url",
+                "database-url",
+                "http-server-url2",
+                "v2-api",
+                "spaced-name",
+                "",
+            ],
+        ),
+        (
+            Camel,
+            [
+                "databaseUrl",
+                "databaseUrl",
+                "databaseUrl",
+                "databaseUrl",
+                "databaseUrl",
+                "databaseUrl",
+                "httpServerUrl2",
+                "v2Api",
+                "spacedName",
+                "",
+            ],
+        ),
+        (
+            Pascal,
+            [
+                "DatabaseUrl",
+                "DatabaseUrl",
+                "DatabaseUrl",
+                "DatabaseUrl",
+                "DatabaseUrl",
+                "DatabaseUrl",
+                "HttpServerUrl2",
+                "V2Api",
+                "SpacedName",
+                "",
+            ],
+        ),
+    ]
+    "#);
+}
+
+#[test]
+fn test_map_case() {
+    let mut source = map(&[("DATABASE_URL", "postgres://db"), ("MAX_CONNECTIONS", "16")])
+        .map_case(Case::ScreamingSnake);
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${database-url}", "${maxConnections}", "${DATABASE_URL}", "plain"]"#,
+    );
+    let r: (String, u32, String, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(
+        r,
+        (
+            "postgres://db".to_owned(),
+            16,
+            "postgres://db".to_owned(),
+            "plain".to_owned()
+        )
+    );
+
+    let mut de = serde_json::Deserializer::from_str(r#""${database-url}""#);
+    let r: serde_bytes::ByteBuf = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.as_slice(), b"postgres://db");
+
+    let mut de = serde_json::Deserializer::from_str(r#"{"timeout": "${request-timeout}"}"#);
+    let err =
+        serde_vars::deserialize::<_, _, HashMap<String, u32>>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${REQUEST_TIMEOUT}`, but it does not exist at line 1 column 33"#);
+}
+
+#[test]
+fn test_map_case_delimiters() {
+    let inner = map(&[("redisHost", "localhost")])
+        .with_variable_prefix("{{")
+        .with_variable_suffix("}}");
+    let mut source = MapCase::new(inner, Case::Camel.into())
+        .with_variable_prefix("{{")
+        .with_variable_suffix("}}");
+
+    let mut de = serde_json::Deserializer::from_str(r#"["{{REDIS_HOST}}", "${REDIS_HOST}"]"#);
+    let r: Vec<String> = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ["localhost", "${REDIS_HOST}"]);
+}
@@ -0,0 +1,91 @@
+use serde_vars::{
+    source::{ChainSource, EmptyValues, Source},
+    DirSource, DotenvSource, MapSource,
+};
+
+#[test]
+fn test_chain() {
+    let overrides = MapSource::default()
+        .insert("HOST", "example.com")
+        .insert("PORT", "not a port")
+        .insert("EMPTY", "")
+        .with_empty_values(EmptyValues::Missing);
+    let dotenv = DotenvSource::from_contents("PORT=8080\nUSER=admin\nEMPTY=dotenv").unwrap();
+    let defaults = MapSource::default()
+        .insert("USER", "root")
+        .insert("TIMEOUT", "30");
+
+    let mut source = overrides.or(dotenv).or(defaults);
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${HOST}", "${USER}", "${TIMEOUT}", "${EMPTY}", "plain"]"#,
+    );
+    let r: (String, String, u32, String, String) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(
+        r,
+        (
+            "example.com".to_owned(),
+            "admin".to_owned(),
+            30,
+            "dotenv".to_owned(),
+            "plain".to_owned()
+        )
+    );
+
+    // Only missing variables fall back, other errors are reported.
+    let errors = [r#""${PORT}""#, r#""${MISSING}""#].map(|json| {
+        let mut de = serde_json::Deserializer::from_str(json);
+        let r: Result<u16, _> = serde_vars::deserialize(&mut de, &mut source);
+        r.unwrap_err().to_string()
+    });
+    insta::assert_debug_snapshot!(errors, @r#"
+    [
+        "invalid value: string \"not a port\", expected variable `${PORT}` to be an unsigned integer (i16)",
+        "got variable `${MISSING}`, but it does not exist in any of the sources: StringSource<HashMap<String, String>>, StringSource<DotenvLookup>, StringSource<HashMap<String, String>>",
+    ]
+    "#);
+}
+
+#[test]
+fn test_chain_files() {
+    let temp = tempfile::tempdir().unwrap();
+    std::fs::write(temp.path().join("password"), "hunter2\n").unwrap();
+
+    let mut source = ChainSource::new(
+        DirSource::new(temp.path()),
+        MapSource::default()
+            .insert("password", "default")
+            .insert("user", "admin"),
+    );
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${password}", "${user}"]"#);
+    let r: (String, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ("hunter2".to_owned(), "admin".to_owned()));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${token}""#);
+    let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+    insta::assert_snapshot!(r.unwrap_err(), @r#"got variable `${token}`, but it does not exist in any of the sources: DirSource, StringSource<HashMap<String, String>>"#);
+}
+
+#[test]
+fn test_chain_prefix_maps() {
+    let overrides = MapSource::default()
+        .insert("FEATURE_BETA", "on")
+        .with_prefix_maps(true);
+    let dotenv = DotenvSource::from_contents("FEATURE_BETA=off\nFEATURE_DARK_MODE=on\nPORT=8080")
+        .unwrap()
+        .with_prefix_maps(true);
+
+    let mut source = overrides.or(dotenv);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${prefix:FEATURE_}""#);
+    let r: std::collections::BTreeMap<String, String> =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    insta::assert_debug_snapshot!(r, @r#"
+    {
+        "BETA": "on",
+        "DARK_MODE": "on",
+    }
+    "#);
+}
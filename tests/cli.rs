@@ -0,0 +1,76 @@
+#![cfg(feature = "clap")]
+
+use clap::{Arg, ArgAction, Command};
+use serde_vars::source::{ClapLookup, ClapSource};
+
+fn command() -> Command {
+    Command::new("server")
+        .arg(Arg::new("listen-port").long("listen-port"))
+        .arg(Arg::new("host").long("host").default_value("localhost"))
+        .arg(Arg::new("tag").long("tag").action(ArgAction::Append))
+        .arg(
+            Arg::new("verbose")
+                .long("verbose")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(Arg::new("quiet").long("quiet").action(ArgAction::SetTrue))
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Config {
+    listen_port: u16,
+    tag: String,
+    verbose: bool,
+}
+
+#[test]
+fn test_clap() {
+    let matches = command().get_matches_from([
+        "server",
+        "--listen-port",
+        "8080",
+        "--tag",
+        "a",
+        "--tag",
+        "b",
+        "--verbose",
+    ]);
+    let mut source = ClapSource::from_matches(matches);
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"{"listen_port": "${listen_port}", "tag": "${tag}", "verbose": "${verbose}"}"#,
+    );
+    let config: Config = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(
+        config,
+        Config {
+            listen_port: 8080,
+            tag: "b".to_owned(),
+            verbose: true,
+        }
+    );
+
+    // Unset flags, default values and unknown arguments are missing.
+    let errors = [r#""${quiet}""#, r#""${host}""#, r#""${unknown}""#].map(|json| {
+        let mut de = serde_json::Deserializer::from_str(json);
+        let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+        r.unwrap_err().to_string()
+    });
+    insta::assert_debug_snapshot!(errors, @r#"
+    [
+        "got variable `${quiet}`, but it does not exist",
+        "got variable `${host}`, but it does not exist",
+        "got variable `${unknown}`, but it does not exist",
+    ]
+    "#);
+}
+
+#[test]
+fn test_clap_default_values() {
+    let matches = command().get_matches_from(["server"]);
+    let mut source = ClapSource::new(ClapLookup::new(matches).with_default_values(true));
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${host}", "${quiet}"]"#);
+    let r: (String, bool) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ("localhost".to_owned(), false));
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use serde_vars::{source::CompositeSource, FileSource, MapSource};
+
+fn map(values: &[(&str, &str)]) -> MapSource {
+    let values = values
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect::<HashMap<_, _>>();
+    MapSource::new(values)
+}
+
+#[test]
+fn test_composite() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(tempdir.path().join("secret"), "hunter2").unwrap();
+
+    let mut source = CompositeSource::new()
+        .register("cfg", map(&[("USER", "admin"), ("PORT", "8080")]))
+        .register("file", FileSource::new().with_base_path(tempdir.path()));
+    assert_eq!(source.schemes().collect::<Vec<_>>(), ["cfg", "file"]);
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${cfg:USER}", "${file:secret}", "${cfg:PORT}", "plain"]"#,
+    );
+    let r: (String, String, u16, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(
+        r,
+        (
+            "admin".to_owned(),
+            "hunter2".to_owned(),
+            8080,
+            "plain".to_owned()
+        )
+    );
+
+    let mut de = serde_json::Deserializer::from_str(r#""${file:secret}""#);
+    let r: serde_bytes::ByteBuf = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.as_slice(), b"hunter2");
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${cfg:PORT}", "plain", 1]"#);
+    let r: serde_json::Value = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, serde_json::json!([8080, "plain", 1]));
+}
+
+#[test]
+fn test_composite_unregistered() {
+    let mut source = CompositeSource::new()
+        .register("env", map(&[("USER", "admin")]))
+        .register("vault", map(&[("password", "hunter2")]));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${file:/run/secrets/token}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${file:/run/secrets/token}`, but the scheme `file` is not registered, expected one of `env`, `vault`"#);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${USER}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${USER}`, but it has no scheme, expected one of `env`, `vault`"#);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${cfg:PORT}""#);
+    let err = serde_vars::deserialize::<_, _, u16>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${cfg:PORT}`, but the scheme `cfg` is not registered, expected one of `env`, `vault`"#);
+
+    let mut de = serde_json::Deserializer::from_str(r#""8080""#);
+    let err = serde_vars::deserialize::<_, _, u16>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"invalid value: string "8080", expected a variable `${<scheme>:<var>}`"#);
+
+    let mut source = CompositeSource::new();
+    let mut de = serde_json::Deserializer::from_str(r#""${env:USER}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @"got variable `${env:USER}`, but no schemes are registered");
+}
+
+#[test]
+fn test_composite_delimiters() {
+    let mut source = CompositeSource::new()
+        .with_variable_prefix("{{")
+        .with_variable_suffix("}}")
+        .register(
+            "cfg",
+            map(&[("USER", "admin")])
+                .with_variable_prefix("{{")
+                .with_variable_suffix("}}"),
+        );
+
+    let mut de = serde_json::Deserializer::from_str(r#"["{{cfg:USER}}", "${cfg:USER}"]"#);
+    let r: Vec<String> = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ["admin", "${cfg:USER}"]);
+
+    let mut de = serde_json::Deserializer::from_str(r#""{{env:USER}}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `{{env:USER}}`, but the scheme `env` is not registered, expected one of `cfg`"#);
+}
@@ -1,4 +1,9 @@
-use serde_vars::MapSource;
+use std::{borrow::Cow, collections::HashMap};
+
+use serde_vars::{
+    source::{Any, Source},
+    MapSource, Masked,
+};
 
 /// Yaml allows deserializing numbers (scalars) as strings.
 /// This behaviour should not be broken by wrapping the deserializer with `serde-vars`.
@@ -24,3 +29,647 @@ fn test_yaml_scalar_number_as_number() {
     let s: i32 = serde_vars::deserialize(de, &mut source).unwrap();
     assert_eq!(s, 300);
 }
+
+#[test]
+fn test_masked() {
+    #[derive(Debug, serde::Deserialize)]
+    #[expect(dead_code, reason = "debug impl is used to assert")]
+    struct Config {
+        host: Masked<String>,
+        port: Masked<u16>,
+        password: Masked<String>,
+    }
+
+    let config = r#"{"host": "localhost", "port": "${PORT}", "password": "${PASSWORD}"}"#;
+
+    let mut source = MapSource::new(HashMap::from([
+        ("PORT".to_owned(), "6379".to_owned()),
+        ("PASSWORD".to_owned(), "hunter2".to_owned()),
+    ]));
+    let mut de = serde_json::Deserializer::from_str(config);
+    let r: Config = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    insta::assert_debug_snapshot!(r, @r#"
+    Config {
+        host: "localhost",
+        port: ***,
+        password: ***,
+    }
+    "#);
+
+    // Without `serde-vars`, nothing is expanded and nothing is masked.
+    let r: Config =
+        serde_json::from_str(r#"{"host": "localhost", "port": 6379, "password": "${PASSWORD}"}"#)
+            .unwrap();
+    insta::assert_debug_snapshot!(r, @r#"
+    Config {
+        host: "localhost",
+        port: 6379,
+        password: "${PASSWORD}",
+    }
+    "#);
+}
+
+/// Unescapes `$${VAR}` to the literal `${VAR}`, all other strings are expanded by `MapSource`.
+struct Escaping(MapSource);
+
+macro_rules! forward {
+    ($($method:ident -> $ty:ty,)*) => {
+        $(
+            fn $method<E>(&mut self, v: &str) -> Result<$ty, E>
+            where
+                E: serde::de::Error,
+            {
+                self.0.$method(v)
+            }
+        )*
+    };
+}
+
+impl Source for Escaping {
+    forward! {
+        expand_bool -> bool,
+        expand_i8 -> i8,
+        expand_i16 -> i16,
+        expand_i32 -> i32,
+        expand_i64 -> i64,
+        expand_u8 -> u8,
+        expand_u16 -> u16,
+        expand_u32 -> u32,
+        expand_u64 -> u64,
+        expand_f32 -> f32,
+        expand_f64 -> f64,
+    }
+
+    fn expand_str<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Cow<'a, str>, E>
+    where
+        E: serde::de::Error,
+    {
+        match v.strip_prefix('$') {
+            Some(escaped) if escaped.starts_with("${") => Ok(Cow::Owned(escaped.to_owned())),
+            _ => self.0.expand_str(v),
+        }
+    }
+
+    fn expand_bytes<'a, E>(&mut self, v: Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, E>
+    where
+        E: serde::de::Error,
+    {
+        self.0.expand_bytes(v)
+    }
+
+    fn expand_any<'a, E>(&mut self, v: Cow<'a, str>) -> Result<Any<'a>, E>
+    where
+        E: serde::de::Error,
+    {
+        self.0.expand_any(v)
+    }
+
+    fn references_variable(&mut self, v: &str) -> Option<bool> {
+        match v.starts_with("$${") {
+            true => Some(false),
+            false => self.0.references_variable(v),
+        }
+    }
+}
+
+#[test]
+fn test_masked_references() {
+    #[derive(Debug, serde::Deserialize)]
+    #[expect(dead_code, reason = "debug impl is used to assert")]
+    struct Config {
+        escaped: Masked<String>,
+        unchanged: Masked<String>,
+    }
+
+    let mut source = Escaping(MapSource::new(HashMap::from([(
+        "TOKEN".to_owned(),
+        "${TOKEN}".to_owned(),
+    )])));
+
+    // Escaped variables are not expanded, even though the value changes. Variables with
+    // their own placeholder as value are expanded, even though the value does not change.
+    let config = r#"{"escaped": "$${TOKEN}", "unchanged": "${TOKEN}"}"#;
+    let mut de = serde_json::Deserializer::from_str(config);
+    let r: Config = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    insta::assert_debug_snapshot!(r, @r#"
+    Config {
+        escaped: "${TOKEN}",
+        unchanged: ***,
+    }
+    "#);
+}
+
+#[test]
+fn test_deserialize_batch() {
+    #[derive(Debug, serde::Deserialize)]
+    #[expect(dead_code, reason = "debug impl is used to assert")]
+    struct Config {
+        name: String,
+        port: u16,
+    }
+
+    let document: serde_yaml::Value = serde_yaml::from_str("name: ${NAME}\nport: ${PORT}").unwrap();
+
+    let mut a = MapSource::new(HashMap::from([
+        ("NAME".to_owned(), "a".to_owned()),
+        ("PORT".to_owned(), "1".to_owned()),
+    ]));
+    let mut b = MapSource::new(HashMap::from([("NAME".to_owned(), "b".to_owned())]));
+
+    let r = serde_vars::deserialize_batch(&document, [&mut a, &mut b])
+        .unwrap()
+        .collect::<Vec<Result<Config, _>>>();
+    insta::assert_debug_snapshot!(r, @r#"
+    [
+        Ok(
+            Config {
+                name: "a",
+                port: 1,
+            },
+        ),
+        Err(
+            Error("got variable `${PORT}`, but it does not exist"),
+        ),
+    ]
+    "#);
+}
+
+#[test]
+fn test_deserialize_batch_streaming() {
+    #[derive(Debug, serde::Deserialize)]
+    #[expect(dead_code, reason = "debug impl is used to assert")]
+    enum Backend {
+        Memory,
+        Redis { db: u8 },
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    #[expect(dead_code, reason = "debug impl is used to assert")]
+    struct Config {
+        url: String,
+        port: Option<u16>,
+        backends: Vec<Backend>,
+    }
+
+    let sources = || {
+        ["a", "b"].map(|tenant| {
+            MapSource::new(HashMap::from([(
+                "URL".to_owned(),
+                format!("redis://{tenant}"),
+            )]))
+        })
+    };
+
+    let input = r#"{"url": "${URL}", "port": null, "backends": ["Memory", {"Redis": {"db": 1}}]}"#;
+    let mut de = serde_json::Deserializer::from_str(input);
+    let json = serde_vars::deserialize_batch(&mut de, sources())
+        .unwrap()
+        .collect::<Result<Vec<Config>, _>>()
+        .unwrap();
+
+    let input = "url: ${URL}\nport: ~\nbackends: [Memory, !Redis {db: 1}]";
+    let de = serde_yaml::Deserializer::from_str(input);
+    let yaml = serde_vars::deserialize_batch(de, sources())
+        .unwrap()
+        .collect::<Result<Vec<Config>, _>>()
+        .unwrap();
+
+    assert_eq!(format!("{json:?}"), format!("{yaml:?}"));
+    insta::assert_debug_snapshot!(json, @r#"
+    [
+        Config {
+            url: "redis://a",
+            port: None,
+            backends: [
+                Memory,
+                Redis {
+                    db: 1,
+                },
+            ],
+        },
+        Config {
+            url: "redis://b",
+            port: None,
+            backends: [
+                Memory,
+                Redis {
+                    db: 1,
+                },
+            ],
+        },
+    ]
+    "#);
+
+    let mut de = serde_json::Deserializer::from_str(r#"{"url": "#);
+    let err = serde_vars::deserialize_batch::<_, _, Config, _>(&mut de, sources()).err();
+    insta::assert_debug_snapshot!(err, @r#"
+    Some(
+        Error("EOF while parsing a value", line: 1, column: 8),
+    )
+    "#);
+}
+
+/// A format wrapper, which is not human readable.
+struct Compact<D>(D);
+
+impl<'de, D> serde::Deserializer<'de> for Compact<D>
+where
+    D: serde::Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.0.deserialize_any(visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct HumanReadable(bool);
+
+impl<'de> serde::Deserialize<'de> for HumanReadable {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let human_readable = deserializer.is_human_readable();
+        serde::de::IgnoredAny::deserialize(deserializer)?;
+        Ok(Self(human_readable))
+    }
+}
+
+#[test]
+fn test_is_human_readable() {
+    let mut source = MapSource::default();
+
+    let mut de = serde_json::Deserializer::from_str("[1]");
+    let r: Vec<HumanReadable> = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, [HumanReadable(true)]);
+
+    let mut de = serde_json::Deserializer::from_str("1");
+    let r: HumanReadable = serde_vars::deserialize(Compact(&mut de), &mut source).unwrap();
+    assert_eq!(r, HumanReadable(false));
+}
+
+const NUMBER_TOKEN: &str = "$serde_json::private::Number";
+
+/// Captures numbers like `serde_json::Number` does with `arbitrary_precision` enabled.
+#[derive(Debug, PartialEq)]
+enum Number {
+    Arbitrary(String),
+    U128(u128),
+    F64(f64),
+}
+
+impl<'de> serde::Deserialize<'de> for Number {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = Number;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a number")
+            }
+
+            fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E> {
+                Ok(Number::U128(v))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+                Ok(Number::F64(v))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                assert_eq!(map.next_key::<String>()?.as_deref(), Some(NUMBER_TOKEN));
+                map.next_value().map(Number::Arbitrary)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+/// `serde_json` with `arbitrary_precision` yields numbers as a map with a private key.
+#[test]
+fn test_arbitrary_precision_passthrough() {
+    let mut source = MapSource::default();
+
+    let de = serde::de::value::MapDeserializer::<_, serde::de::value::Error>::new(
+        [(NUMBER_TOKEN, "1.5")].into_iter(),
+    );
+    let r: f64 = serde_vars::deserialize(de, &mut source).unwrap();
+    assert_eq!(r, 1.5);
+
+    let de = serde::de::value::MapDeserializer::<_, serde::de::value::Error>::new(
+        [(NUMBER_TOKEN, "-42")].into_iter(),
+    );
+    let r: i32 = serde_vars::deserialize(de, &mut source).unwrap();
+    assert_eq!(r, -42);
+
+    let de = serde::de::value::MapDeserializer::<_, serde::de::value::Error>::new(
+        [(NUMBER_TOKEN, "340282366920938463463374607431768211455")].into_iter(),
+    );
+    let r: u128 = serde_vars::deserialize(de, &mut source).unwrap();
+    assert_eq!(r, u128::MAX);
+}
+
+#[test]
+fn test_arbitrary_precision_variable() {
+    let mut source = MapSource::new(HashMap::from([(
+        "BIG".to_owned(),
+        "123456789012345678901234567890".to_owned(),
+    )]));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${BIG}""#);
+    let r: Number = serde_vars::deserialize(&mut de, &mut source).unwrap();
+
+    #[cfg(feature = "arbitrary_precision")]
+    assert_eq!(
+        r,
+        Number::Arbitrary("123456789012345678901234567890".to_owned())
+    );
+    #[cfg(not(feature = "arbitrary_precision"))]
+    assert_eq!(r, Number::U128(123456789012345678901234567890));
+}
+
+#[test]
+fn test_arbitrary_precision_untagged() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Number(Number),
+        Text(String),
+    }
+
+    let mut source = MapSource::new(HashMap::from([
+        ("BIG".to_owned(), "18446744073709551616".to_owned()),
+        ("HUGE".to_owned(), "1".repeat(50)),
+    ]));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${BIG}""#);
+    let r: Result<Value, _> = serde_vars::deserialize(&mut de, &mut source);
+    // Untagged enums buffer the value with serde, which cannot hold 128 bit integers,
+    // with arbitrary precision the number is buffered in its map representation instead.
+    #[cfg(feature = "arbitrary_precision")]
+    assert_eq!(
+        r.unwrap(),
+        Value::Number(Number::Arbitrary("18446744073709551616".to_owned()))
+    );
+    #[cfg(not(feature = "arbitrary_precision"))]
+    insta::assert_debug_snapshot!(r.unwrap_err(), @r#"Error("invalid type: integer `18446744073709551616` as u128, expected any value", line: 1, column: 8)"#);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${HUGE}""#);
+    let r: Value = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    #[cfg(feature = "arbitrary_precision")]
+    assert_eq!(r, Value::Number(Number::Arbitrary("1".repeat(50))));
+    #[cfg(not(feature = "arbitrary_precision"))]
+    assert_eq!(r, Value::Number(Number::F64(1.1111111111111111e49)));
+}
+
+#[test]
+fn test_borrowed_bytes() {
+    #[derive(Debug, serde::Deserialize)]
+    struct Config<'a> {
+        #[serde(borrow)]
+        plain: &'a [u8],
+        #[serde(borrow, with = "serde_bytes")]
+        borrowed: Cow<'a, [u8]>,
+        #[serde(borrow, with = "serde_bytes")]
+        expanded: Cow<'a, [u8]>,
+    }
+
+    let config = r#"{"plain": "abc", "borrowed": "def", "expanded": "${SECRET}"}"#;
+
+    let mut source = MapSource::new(HashMap::from([("SECRET".to_owned(), "hunter2".to_owned())]));
+    let mut de = serde_json::Deserializer::from_str(config);
+    let r: Config<'_> = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.plain, b"abc");
+    assert!(matches!(r.borrowed, Cow::Borrowed(b"def")));
+    assert!(matches!(r.expanded, Cow::Owned(ref v) if v == b"hunter2"));
+
+    // Expanded values cannot be borrowed.
+    let mut de = serde_json::Deserializer::from_str(r#""${SECRET}""#);
+    let err = serde_vars::deserialize::<_, _, &[u8]>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"invalid type: byte array, expected a borrowed byte array"#);
+}
+
+#[test]
+fn test_missing_as_none() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Database {
+        port: Option<u16>,
+        user: Option<String>,
+    }
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Config {
+        port: Option<u16>,
+        host: Option<String>,
+        log_level: Option<String>,
+        timeout: Option<u64>,
+        name: Option<String>,
+        database: Option<Database>,
+    }
+
+    let config = r#"{
+        "port": "${PORT}",
+        "host": "${HOST}",
+        "log_level": null,
+        "timeout": 30,
+        "name": "plain",
+        "database": {"port": "${DB_PORT}", "user": "${USER}"}
+    }"#;
+
+    let mut source = MapSource::new(HashMap::from([
+        ("PORT".to_owned(), "8080".to_owned()),
+        ("USER".to_owned(), "admin".to_owned()),
+    ]));
+    let mut de = serde_json::Deserializer::from_str(config);
+    let de = serde_vars::Deserializer::new(&mut de, &mut source).with_missing_as_none(true);
+    let r = <Config as serde::Deserialize>::deserialize(de).unwrap();
+    assert_eq!(
+        r,
+        Config {
+            port: Some(8080),
+            host: None,
+            log_level: None,
+            timeout: Some(30),
+            name: Some("plain".to_owned()),
+            database: Some(Database {
+                port: None,
+                user: Some("admin".to_owned()),
+            }),
+        }
+    );
+
+    // Missing variables outside of optional values are still an error.
+    let mut de = serde_json::Deserializer::from_str(r#"["${PORT}", "${HOST}"]"#);
+    let de = serde_vars::Deserializer::new(&mut de, &mut source).with_missing_as_none(true);
+    let err = <(u16, String) as serde::Deserialize>::deserialize(de).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${HOST}`, but it does not exist at line 1 column 22"#);
+
+    // By default, missing variables are an error, even for optional values.
+    let mut de = serde_json::Deserializer::from_str(config);
+    let err = serde_vars::deserialize::<_, _, Config>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${HOST}`, but it does not exist at line 3 column 25"#);
+}
+
+#[test]
+fn test_variants() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    #[serde(tag = "v", rename_all = "lowercase")]
+    enum LogFormat {
+        Json,
+        Text { color: bool },
+    }
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Level {
+        Debug,
+        Info,
+    }
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Config {
+        format: LogFormat,
+        level: Level,
+    }
+
+    let mut source = MapSource::new(HashMap::from([
+        ("LOG_FORMAT".to_owned(), "text".to_owned()),
+        ("LOG_LEVEL".to_owned(), "debug".to_owned()),
+        ("COLOR".to_owned(), "true".to_owned()),
+    ]));
+
+    let config = r#"{
+        "format": {"v": "${LOG_FORMAT}", "color": "${COLOR}"},
+        "level": "${LOG_LEVEL}"
+    }"#;
+
+    let expected = Config {
+        format: LogFormat::Text { color: true },
+        level: Level::Debug,
+    };
+
+    // Variant names are expanded by default.
+    let mut de = serde_json::Deserializer::from_str(config);
+    let r: Config = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, expected);
+
+    let de = serde_yaml::Deserializer::from_str(config);
+    let r: Config = serde_vars::deserialize(de, &mut source).unwrap();
+    assert_eq!(r, expected);
+
+    let mut de = serde_json::Deserializer::from_str(config);
+    let de = serde_vars::Deserializer::new(&mut de, &mut source).with_expand_variants(true);
+    let r = <Config as serde::Deserialize>::deserialize(de).unwrap();
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn test_unit_variant() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    enum E {
+        A,
+        B,
+    }
+
+    let mut source = MapSource::new(HashMap::from([("X".to_owned(), "B".to_owned())]));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${X}""#);
+    let r: E = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, E::B);
+}
+
+#[test]
+fn test_expand_variants() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Storage {
+        s3: Option<String>,
+        gcs: Option<String>,
+    }
+
+    let mut source = MapSource::new(HashMap::from([("STORAGE".to_owned(), "gcs".to_owned())]));
+
+    let config = r#"{"${STORAGE}": "bucket"}"#;
+
+    let mut de = serde_json::Deserializer::from_str(config);
+    let de = serde_vars::Deserializer::new(&mut de, &mut source).with_expand_variants(true);
+    let r = <Storage as serde::Deserialize>::deserialize(de).unwrap();
+    assert_eq!(
+        r,
+        Storage {
+            s3: None,
+            gcs: Some("bucket".to_owned()),
+        }
+    );
+
+    // Keys, which are not identifiers, are not expanded.
+    let mut de = serde_json::Deserializer::from_str(config);
+    let de = serde_vars::Deserializer::new(&mut de, &mut source).with_expand_variants(true);
+    let r = <HashMap<String, String> as serde::Deserialize>::deserialize(de).unwrap();
+    assert_eq!(
+        r,
+        HashMap::from([("${STORAGE}".to_owned(), "bucket".to_owned())])
+    );
+
+    // By default, field names are not expanded.
+    let mut de = serde_json::Deserializer::from_str(config);
+    let err = serde_vars::deserialize::<_, _, Storage>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"unknown field `${STORAGE}`, expected `s3` or `gcs` at line 1 column 13"#);
+}
+
+#[test]
+fn test_expand_variants_optional() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Level {
+        Debug,
+        Info,
+    }
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Config {
+        level: Option<Level>,
+        fallback: Option<Level>,
+    }
+
+    let mut source = MapSource::new(HashMap::from([("LOG_LEVEL".to_owned(), "info".to_owned())]));
+
+    let config = r#"{"level": "${LOG_LEVEL}", "fallback": null}"#;
+    for missing_as_none in [false, true] {
+        let mut de = serde_json::Deserializer::from_str(config);
+        let de = serde_vars::Deserializer::new(&mut de, &mut source)
+            .with_expand_variants(true)
+            .with_missing_as_none(missing_as_none);
+        let r = <Config as serde::Deserialize>::deserialize(de).unwrap();
+        assert_eq!(
+            r,
+            Config {
+                level: Some(Level::Info),
+                fallback: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use serde_vars::{
+    source::{DefaultsSource, Source},
+    MapSource,
+};
+
+fn map(values: &[(&str, &str)]) -> MapSource {
+    let values = values
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect::<HashMap<_, _>>();
+    MapSource::new(values)
+}
+
+#[test]
+fn test_defaults() {
+    let mut source = map(&[("HOST", "db.internal"), ("DEBUG", "")]).with_defaults([
+        ("HOST", "localhost"),
+        ("PORT", "5432"),
+        ("DEBUG", "true"),
+        ("TOKEN", "secret"),
+    ]);
+
+    let mut de =
+        serde_json::Deserializer::from_str(r#"["${HOST}", "${PORT}", "${DEBUG}", "plain"]"#);
+    let r: (String, u16, String, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(
+        r,
+        (
+            "db.internal".to_owned(),
+            5432,
+            String::new(),
+            "plain".to_owned()
+        )
+    );
+
+    let mut de = serde_json::Deserializer::from_str(r#""${TOKEN}""#);
+    let r: serde_bytes::ByteBuf = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.as_slice(), b"secret");
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${PORT}", "${HOST}"]"#);
+    let r: serde_json::Value = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, serde_json::json!([5432, "db.internal"]));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${USER}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${USER}`, but it does not exist"#);
+}
+
+#[test]
+fn test_defaults_chain() {
+    let mut source = DefaultsSource::new(
+        map(&[("HOST", "db.internal")]).or(map(&[("PORT", "6543")])),
+        [("PORT", "5432"), ("USER", "postgres")],
+    )
+    .or(map(&[("USER", "unused"), ("PASSWORD", "hunter2")]));
+
+    let mut de =
+        serde_json::Deserializer::from_str(r#"["${HOST}", "${PORT}", "${USER}", "${PASSWORD}"]"#);
+    let r: (String, u16, String, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(
+        r,
+        (
+            "db.internal".to_owned(),
+            6543,
+            "postgres".to_owned(),
+            "hunter2".to_owned()
+        )
+    );
+}
+
+#[test]
+fn test_defaults_delimiters() {
+    let mut source = map(&[])
+        .with_variable_prefix("{{")
+        .with_variable_suffix("}}")
+        .with_defaults([("PORT", "5432")])
+        .with_variable_prefix("{{")
+        .with_variable_suffix("}}");
+
+    let mut de = serde_json::Deserializer::from_str(r#""{{PORT}}""#);
+    let r: u16 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, 5432);
+}
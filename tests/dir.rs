@@ -0,0 +1,118 @@
+use serde_vars::DirSource;
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Config {
+    user: String,
+    password: String,
+    port: u16,
+    key: serde_bytes::ByteBuf,
+}
+
+#[test]
+fn test_mounted_secrets() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(tempdir.path().join("db-user"), "admin\n").unwrap();
+    std::fs::write(tempdir.path().join("db-password"), " hunter2 \r\n").unwrap();
+    std::fs::write(tempdir.path().join("db-port"), "5432\n").unwrap();
+    std::fs::write(tempdir.path().join("tls.key"), b"\xff\x00\n").unwrap();
+
+    let mut source = DirSource::new(tempdir.path());
+    let mut de = serde_json::Deserializer::from_str(
+        r#"{"user": "${db-user}", "password": "${db-password}", "port": "${db-port}", "key": "${tls.key}"}"#,
+    );
+    let config: Config = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(
+        config,
+        Config {
+            user: "admin".to_owned(),
+            password: " hunter2 ".to_owned(),
+            port: 5432,
+            key: serde_bytes::ByteBuf::from(b"\xff\x00".to_vec()),
+        }
+    );
+}
+
+#[test]
+fn test_path_traversal() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let dir = tempdir.path().join("secrets");
+    std::fs::create_dir_all(dir.join("nested")).unwrap();
+    std::fs::write(dir.join("nested").join("inner"), "inner").unwrap();
+    std::fs::write(tempdir.path().join("outside"), "outside").unwrap();
+
+    let mut source = DirSource::new(&dir);
+
+    let outside = tempdir.path().join("outside");
+    let mut names = vec![
+        "../outside".to_owned(),
+        "nested/inner".to_owned(),
+        "./nested/../../outside".to_owned(),
+        outside.display().to_string(),
+    ];
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(&outside, dir.join("link")).unwrap();
+        names.push("link".to_owned());
+    }
+    for name in names {
+        let json = format!("{:?}", format!("${{{name}}}"));
+        let mut de = serde_json::Deserializer::from_str(&json);
+        let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+        assert!(err.to_string().contains("base path"), "{name}: {err}");
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_kubernetes_symlinks() {
+    // Kubernetes mounts secrets as symbolic links into a hidden, versioned directory.
+    let tempdir = tempfile::tempdir().unwrap();
+    let data = tempdir.path().join("..2024_01_01_00_00_00.000000000");
+    std::fs::create_dir(&data).unwrap();
+    std::fs::write(data.join("token"), "secret-token\n").unwrap();
+    std::os::unix::fs::symlink(&data, tempdir.path().join("..data")).unwrap();
+    std::os::unix::fs::symlink("..data/token", tempdir.path().join("token")).unwrap();
+
+    let mut source = DirSource::new(tempdir.path());
+    let mut de = serde_json::Deserializer::from_str(r#""${token}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "secret-token");
+}
+
+#[test]
+fn test_fallback_dir() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let dev = tempdir.path().join("dev-secrets");
+    std::fs::create_dir(&dev).unwrap();
+    std::fs::write(dev.join("token"), "dev-token\n").unwrap();
+    std::fs::write(tempdir.path().join("outside"), "outside").unwrap();
+
+    let mut source = DirSource::new(tempdir.path().join("run-secrets")).with_fallback_dir(&dev);
+    let mut de = serde_json::Deserializer::from_str(r#""${token}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "dev-token");
+
+    let mut de = serde_json::Deserializer::from_str(r#""${../outside}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    assert!(err.to_string().contains("base path"), "{err}");
+}
+
+#[test]
+fn test_systemd_credentials() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(tempdir.path().join("db-password"), "hunter2\n").unwrap();
+
+    unsafe { std::env::remove_var("CREDENTIALS_DIRECTORY") };
+    let err = DirSource::systemd_credentials().err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+
+    unsafe { std::env::set_var("CREDENTIALS_DIRECTORY", tempdir.path()) };
+    let mut source = DirSource::systemd_credentials().unwrap();
+    let mut de = serde_json::Deserializer::from_str(r#"["${db-password}", "${../db-password}"]"#);
+    let r: Result<(String, String), _> = serde_vars::deserialize(&mut de, &mut source);
+    assert!(r.unwrap_err().to_string().contains("base path"));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${db-password}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "hunter2");
+}
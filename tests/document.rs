@@ -0,0 +1,340 @@
+#![cfg(feature = "json")]
+
+#[cfg(feature = "plist")]
+use serde_vars::source::PlistSource;
+#[cfg(feature = "toml")]
+use serde_vars::source::TomlSource;
+#[cfg(feature = "yaml")]
+use serde_vars::source::YamlSource;
+use serde_vars::source::{JsonSource, ValueSource};
+
+const JSON: &str = r#"{
+    "database": {"host": "localhost", "port": 5432, "tls": true, "password": "5432"},
+    "servers": [{"name": "alpha"}],
+    "a/b": {"m~n": -3.5}
+}"#;
+
+#[test]
+fn test_json_native_types() {
+    let mut source = JsonSource::new(serde_json::from_str(JSON).unwrap());
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${/database/host}", "${/database/port}", "${/database/tls}", "${/a~1b/m~0n}", "${/servers/0/name}"]"#,
+    );
+    let r: (String, u16, bool, f32, String) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(
+        r,
+        ("localhost".to_owned(), 5432, true, -3.5, "alpha".to_owned())
+    );
+
+    // Self-describing formats keep the native type.
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${/database/port}", "${/database/password}", "${/a~1b/m~0n}", "${/database/tls}"]"#,
+    );
+    let r: serde_json::Value = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, serde_json::json!([5432, "5432", -3.5, true]));
+}
+
+#[test]
+fn test_json_errors() {
+    let mut source = JsonSource::new(serde_json::from_str(JSON).unwrap());
+
+    let errors = [
+        r#""${/database/password}""#,
+        r#""${/database/missing}""#,
+        r#""${database.port}""#,
+        r#""${/servers}""#,
+    ]
+    .map(|json| {
+        let mut de = serde_json::Deserializer::from_str(json);
+        let r: Result<u16, _> = serde_vars::deserialize(&mut de, &mut source);
+        r.unwrap_err().to_string()
+    });
+    insta::assert_debug_snapshot!(errors, @r#"
+    [
+        "invalid value: string \"5432\", expected variable `${/database/password}` to be an unsigned integer (u16)",
+        "got variable `${/database/missing}`, but it does not exist",
+        "got variable `${database.port}`, but it does not exist",
+        "invalid value: sequence, expected variable `${/servers}` to be an unsigned integer (u16)",
+    ]
+    "#);
+}
+
+#[test]
+fn test_json_from_path() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("secrets.json");
+    std::fs::write(&path, JSON).unwrap();
+
+    let mut source = JsonSource::from_path(&path).unwrap();
+    let mut de = serde_json::Deserializer::from_str(r#""${/database/host}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "localhost");
+
+    std::fs::write(&path, "{").unwrap();
+    let err = JsonSource::from_path(&path).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_value() {
+    let values = std::collections::BTreeMap::from([
+        ("port", serde_json::json!(5432)),
+        ("big", serde_json::json!(u64::MAX)),
+        ("enabled", serde_json::json!(true)),
+        ("servers", serde_json::json!([{"host": "localhost"}])),
+    ]);
+    let mut source = ValueSource::from_serialize(&values).unwrap();
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${port}", "${big}", "${enabled}", "${servers.0.host}"]"#,
+    );
+    let r: (u16, u128, bool, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, (5432, u64::MAX.into(), true, "localhost".to_owned()));
+
+    let mut de =
+        serde_json::Deserializer::from_str(r#"{"port": "${port}", "servers": "${servers}"}"#);
+    let err = serde_vars::deserialize::<_, _, serde_json::Value>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"invalid value: sequence, expected variable `${servers}` to be a scalar value at line 1 column 43"#);
+}
+
+#[cfg(feature = "toml")]
+const TOML: &str = r#"
+[database]
+host = "localhost"
+port = 5432
+tls = true
+timeout = 1.5
+password = "5432"
+offset = -3
+
+[[servers]]
+name = "alpha"
+"#;
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_toml_native_types() {
+    let mut source = TomlSource::new(toml::from_str(TOML).unwrap());
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${database.host}", "${database.port}", "${database.tls}", "${database.timeout}", "${database.offset}", "${servers.0.name}"]"#,
+    );
+    let r: (String, u16, bool, f64, i8, String) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(
+        r,
+        (
+            "localhost".to_owned(),
+            5432,
+            true,
+            1.5,
+            -3,
+            "alpha".to_owned()
+        )
+    );
+
+    // Self-describing formats keep the native type.
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${database.port}", "${database.password}", "${database.tls}", "plain"]"#,
+    );
+    let r: serde_json::Value = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, serde_json::json!([5432, "5432", true, "plain"]));
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_toml_errors() {
+    let mut source = TomlSource::new(toml::from_str(TOML).unwrap());
+
+    let errors = [
+        r#""${database.password}""#,
+        r#""${database.missing}""#,
+        r#""${database.port}""#,
+        r#""${database}""#,
+        r#""5432""#,
+    ]
+    .map(|json| {
+        let mut de = serde_json::Deserializer::from_str(json);
+        let r: Result<u8, _> = serde_vars::deserialize(&mut de, &mut source);
+        r.unwrap_err().to_string()
+    });
+    insta::assert_debug_snapshot!(errors, @r#"
+    [
+        "invalid value: string \"5432\", expected variable `${database.password}` to be an unsigned integer (u8)",
+        "got variable `${database.missing}`, but it does not exist",
+        "invalid value: integer `5432`, expected variable `${database.port}` to be an unsigned integer (u8)",
+        "invalid value: map, expected variable `${database}` to be an unsigned integer (u8)",
+        "invalid value: string \"5432\", expected an unsigned integer (u8) or a variable `${<key>}`",
+    ]
+    "#);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${database.port}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_debug_snapshot!(err, @r#"Error("invalid value: integer `5432`, expected variable `${database.port}` to be a string", line: 0, column: 0)"#);
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_toml_from_path() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("secrets.toml");
+    std::fs::write(&path, TOML).unwrap();
+
+    let mut source = TomlSource::from_path(&path).unwrap();
+    let mut de = serde_json::Deserializer::from_str(r#""${database.port}""#);
+    let r: u32 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, 5432);
+
+    std::fs::write(&path, "invalid = ").unwrap();
+    let err = TomlSource::from_path(&path).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("secrets.yaml");
+    std::fs::write(
+        &path,
+        r#"
+database:
+  credentials:
+    user: admin
+    password: "5432"
+  port: 5432
+  ratio: 0.5
+  tls: yes
+servers:
+  - name: alpha
+"#,
+    )
+    .unwrap();
+
+    let mut source = YamlSource::from_path(&path).unwrap();
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${database.credentials.user}", "${database.port}", "${database.ratio}", "${servers.0.name}"]"#,
+    );
+    let r: (String, u16, f64, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ("admin".to_owned(), 5432, 0.5, "alpha".to_owned()));
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${database.port}", "${database.credentials.password}", "${database.tls}"]"#,
+    );
+    let r: serde_json::Value = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, serde_json::json!([5432, "5432", "yes"]));
+
+    let errors = [
+        r#""${database.credentials.password}""#,
+        r#""${database.credentials.missing}""#,
+        r#""${database.credentials}""#,
+    ]
+    .map(|json| {
+        let mut de = serde_json::Deserializer::from_str(json);
+        let r: Result<u16, _> = serde_vars::deserialize(&mut de, &mut source);
+        r.unwrap_err().to_string()
+    });
+    insta::assert_debug_snapshot!(errors, @r#"
+    [
+        "invalid value: string \"5432\", expected variable `${database.credentials.password}` to be an unsigned integer (u16)",
+        "got variable `${database.credentials.missing}`, but it does not exist",
+        "invalid value: map, expected variable `${database.credentials}` to be an unsigned integer (u16)",
+    ]
+    "#);
+
+    std::fs::write(&path, "database: [").unwrap();
+    let err = YamlSource::from_path(&path).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[cfg(feature = "plist")]
+#[test]
+fn test_plist() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("com.example.app.plist");
+    std::fs::write(
+        &path,
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Port</key>
+    <integer>5432</integer>
+    <key>Ratio</key>
+    <real>0.5</real>
+    <key>Enabled</key>
+    <true/>
+    <key>Key</key>
+    <data>/wA=</data>
+    <key>Expires</key>
+    <date>2024-01-01T00:00:00Z</date>
+    <key>Servers</key>
+    <array>
+        <dict>
+            <key>Host</key>
+            <string>localhost</string>
+        </dict>
+    </array>
+</dict>
+</plist>"#,
+    )
+    .unwrap();
+
+    let mut source = PlistSource::from_path(&path).unwrap();
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${Port}", "${Ratio}", "${Enabled}", "${Key}", "${Expires}", "${Servers.0.Host}"]"#,
+    );
+    let r: serde_json::Value = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    insta::assert_debug_snapshot!(r, @r#"
+    Array [
+        Number(5432),
+        Number(0.5),
+        Bool(true),
+        String("/wA="),
+        String("2024-01-01T00:00:00Z"),
+        String("localhost"),
+    ]
+    "#);
+
+    // Binary property lists, as written by `defaults`.
+    let mut binary = Vec::new();
+    plist::Value::from_file(&path)
+        .unwrap()
+        .to_writer_binary(&mut binary)
+        .unwrap();
+    std::fs::write(&path, binary).unwrap();
+
+    let mut source = PlistSource::from_path(&path).unwrap();
+    let mut de = serde_json::Deserializer::from_str(r#"["${Port}", "${Servers.0.Host}"]"#);
+    let r: (u16, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, (5432, "localhost".to_owned()));
+
+    std::fs::write(&path, "<plist>").unwrap();
+    let err = PlistSource::from_path(&path).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[cfg(feature = "plist")]
+#[test]
+fn test_plist_domain() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let preferences = tempdir.path().join("Library").join("Preferences");
+    std::fs::create_dir_all(&preferences).unwrap();
+    std::fs::write(
+        preferences.join("com.example.app.plist"),
+        r#"<plist version="1.0"><dict><key>Host</key><string>localhost</string></dict></plist>"#,
+    )
+    .unwrap();
+
+    unsafe { std::env::set_var("HOME", tempdir.path()) };
+    let mut source = PlistSource::from_domain("com.example.app").unwrap();
+    let mut de = serde_json::Deserializer::from_str(r#""${Host}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "localhost");
+
+    let err = PlistSource::from_domain("com.example.missing").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
@@ -0,0 +1,96 @@
+use serde_vars::{source::DotenvLookup, DotenvSource};
+
+#[test]
+fn test_syntax() {
+    let contents = concat!(
+        "# A comment\n",
+        "\n",
+        "   PLAIN = value with spaces   # trailing comment\n",
+        "export EXPORTED=1\r\n",
+        "exported_name=export\n",
+        "HASH=a#b\n",
+        "EMPTY=\n",
+        "SINGLE='literal \\n ${X} # not a comment'\n",
+        "DOUBLE=\"tab\\tnew\\nline \\\"quoted\\\" \\\\ \\x\" # comment\n",
+        "MULTI=\"-----BEGIN-----\n",
+        "line\n",
+        "-----END-----\"\n",
+        "MULTI_SINGLE='a\n",
+        "b'\n",
+        "PLAIN=overridden\n",
+        "DOTTED.NAME-1=dotted",
+    );
+
+    let mut source = DotenvSource::from_contents(contents).unwrap();
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${PLAIN}", "${EXPORTED}", "${exported_name}", "${HASH}", "${SINGLE}", "${DOUBLE}", "${MULTI}", "${MULTI_SINGLE}", "${DOTTED.NAME-1}"]"#,
+    );
+    let r: (
+        String,
+        u8,
+        String,
+        String,
+        String,
+        String,
+        String,
+        String,
+        String,
+    ) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    insta::assert_debug_snapshot!(r, @r#"
+    (
+        "overridden",
+        1,
+        "export",
+        "a#b",
+        "literal \\n ${X} # not a comment",
+        "tab\tnew\nline \"quoted\" \\ \\x",
+        "-----BEGIN-----\nline\n-----END-----",
+        "a\nb",
+        "dotted",
+    )
+    "#);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${EMPTY}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "");
+}
+
+#[test]
+fn test_errors() {
+    let errors = [
+        "VALID=1\nNO_EQUALS\n",
+        "1NAME=value",
+        "NAME='unterminated\n\nline",
+        "A=1\nNAME=\"unterminated\n",
+        "NAME=\"quoted\" trailing",
+        "=value",
+    ]
+    .map(|contents| DotenvLookup::parse(contents).unwrap_err().to_string());
+    insta::assert_debug_snapshot!(errors, @r#"
+    [
+        "expected `=` after `NO_EQUALS` at line 2",
+        "expected a variable name at line 1",
+        "unterminated single quoted value at line 1",
+        "unterminated double quoted value at line 2",
+        "unexpected characters after a quoted value at line 1",
+        "expected a variable name at line 1",
+    ]
+    "#);
+}
+
+#[test]
+fn test_from_path() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join(".env");
+    std::fs::write(&path, "REDIS_HOST=localhost\nREDIS_PORT=6379\n").unwrap();
+
+    let mut source = DotenvSource::from_path(&path).unwrap();
+    let mut de = serde_json::Deserializer::from_str(r#"["${REDIS_HOST}", "${REDIS_PORT}"]"#);
+    let r: (String, u16) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ("localhost".to_owned(), 6379));
+
+    std::fs::write(&path, "INVALID").unwrap();
+    let err = DotenvSource::from_path(&path).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "expected `=` after `INVALID` at line 1");
+}
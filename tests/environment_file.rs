@@ -0,0 +1,91 @@
+use serde_vars::{source::EnvironmentFileLookup, EnvironmentFileSource};
+
+#[test]
+fn test_syntax() {
+    let contents = concat!(
+        "# A comment\n",
+        "; Another comment \\\n",
+        "CONTINUED_COMMENT=ignored\n",
+        "\n",
+        "   PLAIN  =  value with  spaces   # not a comment\n",
+        "export EXPORTED=ignored\n",
+        "NO_ASSIGNMENT\n",
+        "1INVALID=ignored\n",
+        "EMPTY=\n",
+        "SINGLE='literal \\n ${X}'\n",
+        "DOUBLE=\"\\\"quoted\\\" \\\\ \\$ \\` \\n\"\r\n",
+        "CONCATENATED='a' \"b\" c'd'\n",
+        "ESCAPED=escaped\\ \\ \\\"\\\\\n",
+        "CONTINUED=first \\\n",
+        "second\n",
+        "MULTI=\"-----BEGIN-----\n",
+        "line\n",
+        "-----END-----\"\n",
+        "PLAIN=overridden\n",
+        "UNTERMINATED='end of file",
+    );
+
+    let mut source = EnvironmentFileSource::from_contents(contents);
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${PLAIN}", "${EMPTY}", "${SINGLE}", "${DOUBLE}", "${CONCATENATED}", "${ESCAPED}", "${CONTINUED}", "${MULTI}", "${UNTERMINATED}"]"#,
+    );
+    let r: (
+        String,
+        String,
+        String,
+        String,
+        String,
+        String,
+        String,
+        String,
+        String,
+    ) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    insta::assert_debug_snapshot!(r, @r#"
+    (
+        "overridden",
+        "",
+        "literal \\n ${X}",
+        "\"quoted\" \\ $ ` \\n",
+        "abc'd'",
+        "escaped  \"\\",
+        "first second",
+        "-----BEGIN-----\nline\n-----END-----",
+        "end of file",
+    )
+    "#);
+
+    let errors = [
+        r#""${CONTINUED_COMMENT}""#,
+        r#""${EXPORTED}""#,
+        r#""${NO_ASSIGNMENT}""#,
+        r#""${1INVALID}""#,
+    ]
+    .map(|json| {
+        let mut de = serde_json::Deserializer::from_str(json);
+        let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+        r.unwrap_err().to_string()
+    });
+    insta::assert_debug_snapshot!(errors, @r#"
+    [
+        "got variable `${CONTINUED_COMMENT}`, but it does not exist",
+        "got variable `${EXPORTED}`, but it does not exist",
+        "got variable `${NO_ASSIGNMENT}`, but it does not exist",
+        "got variable `${1INVALID}`, but it does not exist",
+    ]
+    "#);
+}
+
+#[test]
+fn test_from_path() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("service.env");
+    std::fs::write(&path, "PORT=8080\n").unwrap();
+
+    let mut source = EnvironmentFileSource::from_path(&path).unwrap();
+    let mut de = serde_json::Deserializer::from_str(r#""${PORT}""#);
+    let r: u16 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, 8080);
+
+    let r = EnvironmentFileLookup::from_path(temp.path().join("missing.env"));
+    assert_eq!(r.unwrap_err().kind(), std::io::ErrorKind::NotFound);
+}
@@ -124,3 +124,609 @@ test_any!(test_any_integer, 123, 123);
 test_any!(test_any_negative_integer, -123, -123);
 test_any!(test_any_float, 123.45, 123.45);
 test_any!(test_any_string, "foobar", "foobar");
+
+#[test]
+fn test_max_value_length() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(tempdir.path().join("my_test.file"), "foobar").unwrap();
+
+    let mut source = FileSource::new()
+        .with_base_path(tempdir.path())
+        .with_max_value_length(6);
+    let mut de = serde_json::Deserializer::from_str("\"${my_test.file}\"");
+    let s: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(s, "foobar");
+
+    let mut source = source.with_max_value_length(5);
+    let mut de = serde_json::Deserializer::from_str("\"${my_test.file}\"");
+    let err: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+    assert_eq!(
+        format!("{:?}", err.unwrap_err()),
+        format!(
+            r#"Error("failed to read file `{}` from variable `${{my_test.file}}`: file of 6 bytes exceeds the maximum length of 5 bytes", line: 0, column: 0)"#,
+            tempdir.path().join("my_test.file").display()
+        )
+    );
+    let mut source = source.with_max_value_length(6);
+    let mut de = serde_json::Deserializer::from_str("\"${my_test.file}\"");
+    let s: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(s, "foobar");
+}
+
+#[test]
+fn test_comma_decimals() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(tempdir.path().join("my_test.file"), "2,5").unwrap();
+
+    let mut source = FileSource::new()
+        .with_base_path(tempdir.path())
+        .with_comma_decimals(true);
+    let mut de = serde_json::Deserializer::from_str("\"${my_test.file}\"");
+    let r: f64 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, 2.5);
+}
+
+#[test]
+fn test_strict_variables() {
+    let mut source = FileSource::new().with_strict_variables(true);
+
+    let mut de = serde_json::Deserializer::from_str(r#""plain""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "plain");
+
+    let mut de = serde_json::Deserializer::from_str(r#""${/run/secrets/token""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source);
+    insta::assert_debug_snapshot!(err.unwrap_err(), @r#"Error("invalid value: string \"${/run/secrets/token\", expected a well-formed variable `${<var>}`", line: 0, column: 0)"#);
+}
+
+#[test]
+fn test_alternative_delimiters() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(tempdir.path().join("my_test.file"), "foobar").unwrap();
+
+    let mut source = FileSource::new()
+        .with_base_path(tempdir.path())
+        .with_alternative_delimiters("{{", "}}");
+    let mut de = serde_json::Deserializer::from_str(r#"["${my_test.file}", "{{my_test.file}}"]"#);
+    let r: Vec<String> = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ["foobar", "foobar"]);
+}
+
+#[test]
+fn test_integer_literals() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(tempdir.path().join("my_test.file"), "0o755").unwrap();
+
+    let mut source = FileSource::new()
+        .with_base_path(tempdir.path())
+        .with_integer_literals(true);
+    let mut de = serde_json::Deserializer::from_str("\"${my_test.file}\"");
+    let r: u32 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, 0o755);
+}
+
+#[test]
+fn test_relaxed_bools() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(tempdir.path().join("my_test.file"), "yes").unwrap();
+
+    let mut source = FileSource::new()
+        .with_base_path(tempdir.path())
+        .with_relaxed_bools(true);
+    let mut de = serde_json::Deserializer::from_str("\"${my_test.file}\"");
+    let r: bool = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert!(r);
+}
+
+#[test]
+fn test_special_floats() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(tempdir.path().join("my_test.file"), "-inf").unwrap();
+
+    let mut source = FileSource::new().with_base_path(tempdir.path());
+    let mut de = serde_json::Deserializer::from_str("\"${my_test.file}\"");
+    let err: Result<f64, _> = serde_vars::deserialize(&mut de, &mut source);
+    insta::assert_debug_snapshot!(err.unwrap_err(), @r#"Error("invalid value: string \"-inf\", expected file contents of variable `${my_test.file}` to be a finite floating point", line: 0, column: 0)"#);
+
+    let mut source = source.with_special_floats(true);
+    let mut de = serde_json::Deserializer::from_str("\"${my_test.file}\"");
+    let r: f64 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, f64::NEG_INFINITY);
+}
+
+#[test]
+fn test_trim() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(tempdir.path().join("my_test.file"), " 42\n").unwrap();
+
+    let mut source = FileSource::new()
+        .with_base_path(tempdir.path())
+        .with_trim(true);
+    let mut de = serde_json::Deserializer::from_str(r#"["${my_test.file}", "${my_test.file}"]"#);
+    let r: (u32, serde_json::Value) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, (42, serde_json::json!(42)));
+
+    let mut de = serde_json::Deserializer::from_str("\"${my_test.file}\"");
+    let r: serde_bytes::ByteBuf = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.as_slice(), b" 42\n");
+}
+
+#[test]
+fn test_confine_to_base_path() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let base = tempdir.path().join("base");
+    std::fs::create_dir_all(base.join("nested")).unwrap();
+    std::fs::write(base.join("nested/inside"), "inside").unwrap();
+    std::fs::write(tempdir.path().join("outside"), "outside").unwrap();
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(tempdir.path().join("outside"), base.join("link")).unwrap();
+
+    let mut source = FileSource::new().with_base_path(&base);
+    let mut de = serde_json::Deserializer::from_str(r#""${../outside}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "outside");
+
+    let mut source = source.with_confine_to_base_path(true);
+    let mut de = serde_json::Deserializer::from_str(r#""${nested/../nested/inside}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "inside");
+
+    let outside = tempdir.path().join("outside");
+    let mut escapes = vec![
+        "${../outside}".to_owned(),
+        format!("${{{}}}", outside.display()),
+    ];
+    if cfg!(unix) {
+        escapes.push("${link}".to_owned());
+    }
+    for escape in escapes {
+        let json = format!("{escape:?}");
+        let mut de = serde_json::Deserializer::from_str(&json);
+        let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+        assert!(
+            err.to_string().contains("path is outside of the base path"),
+            "{escape}: {err}"
+        );
+    }
+}
+
+#[test]
+fn test_trim_trailing_newline() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(tempdir.path().join("password"), " hunter2 \r\n").unwrap();
+    std::fs::write(tempdir.path().join("port"), "5432\n").unwrap();
+    std::fs::write(tempdir.path().join("lines"), "a\n\n").unwrap();
+
+    let mut source = FileSource::new().with_base_path(tempdir.path());
+    let mut de = serde_json::Deserializer::from_str(r#""${port}""#);
+    let err: Result<u16, _> = serde_vars::deserialize(&mut de, &mut source);
+    insta::assert_debug_snapshot!(err.unwrap_err(), @r#"Error("invalid value: string \"5432\\n\", expected file contents of variable `${port}` to be an unsigned integer (i16)", line: 0, column: 0)"#);
+
+    let mut source = source.with_trim_trailing_newline(true);
+    let mut de = serde_json::Deserializer::from_str(r#"["${password}", "${port}", "${lines}"]"#);
+    let r: (String, u16, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, (" hunter2 ".to_owned(), 5432, "a\n".to_owned()));
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${password}", "${port}"]"#);
+    let r: (serde_bytes::ByteBuf, serde_json::Value) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.0.as_slice(), b" hunter2 ");
+    assert_eq!(r.1, serde_json::json!(5432));
+}
+
+#[test]
+fn test_base64_binary() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(tempdir.path().join("key.bin"), [0xff, 0x00, 0xfe, 0x01]).unwrap();
+    std::fs::write(tempdir.path().join("name"), "serde").unwrap();
+
+    let mut source = FileSource::new().with_base_path(tempdir.path());
+    let mut de = serde_json::Deserializer::from_str(r#""${key.bin}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    assert!(
+        err.to_string()
+            .ends_with("stream did not contain valid UTF-8"),
+        "{err}"
+    );
+
+    let mut source = source.with_base64_binary(true);
+    let mut de = serde_json::Deserializer::from_str(r#"["${key.bin}", "${name}", "${key.bin}"]"#);
+    let r: (String, String, serde_bytes::ByteBuf) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.0, "/wD+AQ==");
+    assert_eq!(r.1, "serde");
+    assert_eq!(r.2.as_slice(), [0xff, 0x00, 0xfe, 0x01]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_file_uri() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(tempdir.path().join("my token"), "secret").unwrap();
+    std::fs::write(tempdir.path().join("port"), "8080").unwrap();
+    let dir = tempdir.path().display();
+
+    let mut source = FileSource::new();
+    let template =
+        format!(r#"["${{file://{dir}/my%20token}}", "${{file://localhost{dir}/port}}"]"#);
+    let mut de = serde_json::Deserializer::from_str(&template);
+    let r: (String, u16) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ("secret".to_owned(), 8080));
+
+    // URIs are always absolute, the base path does not apply.
+    let mut source = FileSource::new().with_base_path("/does/not/exist");
+    let template = format!(r#""${{file://{dir}/port}}""#);
+    let mut de = serde_json::Deserializer::from_str(&template);
+    let r: u16 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, 8080);
+}
+
+#[test]
+fn test_fallback_base_paths() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let prod = tempdir.path().join("prod");
+    let dev = tempdir.path().join("dev");
+    std::fs::create_dir_all(&dev).unwrap();
+    std::fs::write(dev.join("token"), "dev-token").unwrap();
+    std::fs::write(dev.join("port"), "8080").unwrap();
+
+    let mut source = FileSource::new()
+        .with_base_path(&prod)
+        .with_fallback_base_path(tempdir.path().join("missing"))
+        .with_fallback_base_path(&dev)
+        .with_confine_to_base_path(true);
+    let mut de = serde_json::Deserializer::from_str(r#"["${token}", "${port}"]"#);
+    let r: (String, u16) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ("dev-token".to_owned(), 8080));
+
+    // The first existing file wins.
+    std::fs::create_dir_all(&prod).unwrap();
+    std::fs::write(prod.join("port"), "443").unwrap();
+    let mut de = serde_json::Deserializer::from_str(r#""${port}""#);
+    let r: u16 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, 443);
+
+    // Missing files are reported with the primary base path.
+    let mut de = serde_json::Deserializer::from_str(r#""${missing}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    let expected = prod.join("missing");
+    assert!(
+        err.to_string()
+            .contains(&format!("`{}`", expected.display())),
+        "{err}"
+    );
+
+    std::fs::write(tempdir.path().join("outside"), "outside").unwrap();
+    let mut de = serde_json::Deserializer::from_str(r#""${../outside}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    assert!(
+        err.to_string()
+            .ends_with("path is outside of the base paths"),
+        "{err}"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_secure_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("token");
+    std::fs::write(&path, "secret").unwrap();
+
+    let mut source = FileSource::new()
+        .with_base_path(tempdir.path())
+        .with_secure_permissions(true);
+    for (mode, ok) in [(0o600, true), (0o640, true), (0o604, false), (0o666, false)] {
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        let mut de = serde_json::Deserializer::from_str(r#""${token}""#);
+        let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+        match ok {
+            true => assert_eq!(r.unwrap(), "secret"),
+            false => assert!(
+                r.unwrap_err().to_string().contains(&format!(
+                    "file with permissions {mode:04o} is accessible by other users"
+                )),
+                "{mode:o}"
+            ),
+        }
+    }
+}
+
+#[test]
+fn test_file_system() {
+    use std::{collections::BTreeMap, path::PathBuf};
+
+    let files = BTreeMap::from([
+        (PathBuf::from("/secrets/token"), "secret".as_bytes()),
+        (PathBuf::from("/secrets/port"), b"8080"),
+        (PathBuf::from("/secrets/key.bin"), b"\xff\x00"),
+        (PathBuf::from("/etc/passwd"), b"root"),
+    ]);
+
+    let mut source = FileSource::new()
+        .with_file_system(&files)
+        .with_base_path("/secrets")
+        .with_confine_to_base_path(true)
+        .with_max_value_length(6);
+    let mut de = serde_json::Deserializer::from_str(r#"["${token}", "${port}", "${key.bin}"]"#);
+    let r: (String, u16, serde_bytes::ByteBuf) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.0, "secret");
+    assert_eq!(r.1, 8080);
+    assert_eq!(r.2.as_slice(), b"\xff\x00");
+
+    let mut de = serde_json::Deserializer::from_str(r#""${missing}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_debug_snapshot!(err, @r#"Error("failed to read file `/secrets/missing` from variable `${missing}`: entity not found", line: 0, column: 0)"#);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${../etc/passwd}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_debug_snapshot!(err, @r#"Error("failed to read file `/secrets/../etc/passwd` from variable `${../etc/passwd}`: path is outside of the base path `/secrets`", line: 0, column: 0)"#);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_secure_permissions_unknown() {
+    use std::{collections::BTreeMap, path::PathBuf};
+
+    let files = BTreeMap::from([(PathBuf::from("/secrets/token"), "secret".as_bytes())]);
+
+    let mut source = FileSource::new()
+        .with_file_system(&files)
+        .with_base_path("/secrets")
+        .with_secure_permissions(true);
+    let mut de = serde_json::Deserializer::from_str(r#""${token}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_debug_snapshot!(err, @r#"Error("failed to read file `/secrets/token` from variable `${token}`: the mode of the file is unknown, unable to verify its permissions", line: 0, column: 0)"#);
+}
+
+#[test]
+fn test_file_sensitive() {
+    use std::{collections::BTreeMap, path::PathBuf};
+
+    let files = BTreeMap::from([(PathBuf::from("/secrets/password"), "hunter2".as_bytes())]);
+
+    let mut source = FileSource::new()
+        .with_file_system(&files)
+        .with_base_path("/secrets")
+        .with_sensitive(true);
+    let mut de = serde_json::Deserializer::from_str(r#""${password}""#);
+    let err = serde_vars::deserialize::<_, _, u64>(&mut de, &mut source).unwrap_err();
+    insta::assert_debug_snapshot!(err, @r#"Error("invalid value: <redacted>, expected file contents of variable `${password}` to be an unsigned integer (i64)", line: 0, column: 0)"#);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${password}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "hunter2");
+}
+
+#[test]
+fn test_file_system_metadata() {
+    use std::path::{Path, PathBuf};
+
+    use serde_vars::source::{FileSystem, Metadata};
+
+    /// Serves the path as the file contents with insecure permissions.
+    struct Insecure;
+
+    impl FileSystem for Insecure {
+        fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+            Ok(path.as_os_str().as_encoded_bytes().to_vec())
+        }
+
+        fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+            let len = path.as_os_str().len() as u64;
+            Ok(Metadata::new(len).with_mode(0o644))
+        }
+    }
+
+    let mut source = FileSource::new().with_file_system(Insecure);
+    let mut de = serde_json::Deserializer::from_str(r#""${/a/b/c}""#);
+    let r: PathBuf = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, Path::new("/a/b/c"));
+
+    let mut source = source.with_secure_permissions(true);
+    let mut de = serde_json::Deserializer::from_str(r#""${/a/b/c}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_debug_snapshot!(err, @r#"Error("failed to read file `/a/b/c` from variable `${/a/b/c}`: file with permissions 0644 is accessible by other users, restrict the permissions, e.g. with `chmod o-rwx`", line: 0, column: 0)"#);
+}
+
+#[test]
+fn test_encoding() {
+    use serde_vars::source::Encoding;
+
+    fn utf16(s: &str, to_bytes: fn(u16) -> [u8; 2]) -> Vec<u8> {
+        s.encode_utf16().flat_map(to_bytes).collect()
+    }
+
+    let tempdir = tempfile::tempdir().unwrap();
+    let bom_le = [
+        &[0xff, 0xfe],
+        utf16("pässwörd\r\n", u16::to_le_bytes).as_slice(),
+    ]
+    .concat();
+    std::fs::write(tempdir.path().join("le.txt"), &bom_le).unwrap();
+    std::fs::write(
+        tempdir.path().join("be.txt"),
+        utf16("5432", u16::to_be_bytes),
+    )
+    .unwrap();
+    std::fs::write(tempdir.path().join("latin1.txt"), b"gr\xfc\xdfe").unwrap();
+    std::fs::write(tempdir.path().join("odd.txt"), b"abc").unwrap();
+
+    let mut source = FileSource::new()
+        .with_base_path(tempdir.path())
+        .with_encoding(Encoding::Utf16Be)
+        .with_trim(true);
+    // The byte order mark takes precedence over the configured byte order.
+    let mut de = serde_json::Deserializer::from_str(r#"["${le.txt}", "${be.txt}", "${le.txt}"]"#);
+    let r: (String, u16, serde_bytes::ByteBuf) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.0, "pässwörd");
+    assert_eq!(r.1, 5432);
+    assert_eq!(r.2.as_slice(), bom_le);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${odd.txt}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    assert!(
+        err.to_string()
+            .ends_with("stream did not contain valid UTF-16BE"),
+        "{err}"
+    );
+
+    let mut source = source.with_encoding(Encoding::Latin1);
+    let mut de = serde_json::Deserializer::from_str(r#""${latin1.txt}""#);
+    let r: serde_json::Value = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "grüße");
+}
+
+#[test]
+fn test_allowed_paths_and_extensions() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let certs = tempdir.path().join("certs");
+    std::fs::create_dir(&certs).unwrap();
+    std::fs::write(certs.join("ca.pem"), "ca").unwrap();
+    std::fs::write(certs.join("ca.key"), "key").unwrap();
+    std::fs::write(tempdir.path().join("token.pem"), "token").unwrap();
+    std::fs::write(tempdir.path().join("other.pem"), "other").unwrap();
+
+    let mut source = FileSource::new()
+        .with_base_path(tempdir.path())
+        .with_allowed_paths([certs.clone(), "token.pem".into()])
+        .with_allowed_extensions([".pem"]);
+    let mut de = serde_json::Deserializer::from_str(r#"["${certs/ca.pem}", "${token.pem}"]"#);
+    let r: (String, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ("ca".to_owned(), "token".to_owned()));
+
+    let mut rejected = vec![
+        ("certs/ca.key", "file extension is not allowed"),
+        ("other.pem", "path is not allowed"),
+        ("certs/../other.pem", "path is not allowed"),
+    ];
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink("../other.pem", certs.join("link.pem")).unwrap();
+        rejected.push(("certs/link.pem", "path is not allowed"));
+    }
+    for (name, expected) in rejected {
+        let json = format!("{:?}", format!("${{{name}}}"));
+        let mut de = serde_json::Deserializer::from_str(&json);
+        let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+        assert!(err.to_string().ends_with(expected), "{name}: {err}");
+    }
+}
+
+#[test]
+fn test_cache() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("ca.pem");
+    std::fs::write(&path, "first").unwrap();
+
+    let mut source = FileSource::new().with_base_path(tempdir.path());
+    let mut de = serde_json::Deserializer::from_str(r#""${ca.pem}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "first");
+
+    // The file is cached by its resolved path, which is the same for the absolute path.
+    std::fs::write(&path, "second").unwrap();
+    let template = format!(r#"["${{ca.pem}}", "${{{}}}"]"#, path.display());
+    let mut de = serde_json::Deserializer::from_str(&template);
+    let r: (String, serde_bytes::ByteBuf) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.0, "first");
+    assert_eq!(r.1.as_slice(), b"first");
+
+    source.clear_cache();
+    let mut de = serde_json::Deserializer::from_str(r#""${ca.pem}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "second");
+
+    std::fs::write(&path, "third").unwrap();
+    let mut source = source.with_cache(false);
+    let mut de = serde_json::Deserializer::from_str(r#""${ca.pem}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "third");
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_structured_file_keys() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        tempdir.path().join("creds.json"),
+        r#"{"api_key": "secret", "port": 5432, "hosts": ["a", "b"], "tls": {"enabled": true}}"#,
+    )
+    .unwrap();
+    std::fs::write(tempdir.path().join("invalid.json"), "{").unwrap();
+
+    let mut source = FileSource::new().with_base_path(tempdir.path());
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${creds.json#api_key}", "${creds.json#port}", "${creds.json#hosts.1}", "${creds.json#tls.enabled}"]"#,
+    );
+    let r: (String, u16, serde_json::Value, bool) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ("secret".to_owned(), 5432, serde_json::json!("b"), true));
+
+    let mut de =
+        serde_json::Deserializer::from_str(r#"["${creds.json#tls}", "${creds.json#api_key}"]"#);
+    let r: (String, serde_bytes::ByteBuf) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.0, r#"{"enabled":true}"#);
+    assert_eq!(r.1.as_slice(), b"secret");
+
+    let mut de = serde_json::Deserializer::from_str(r#""${creds.json#missing}""#);
+    let err: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+    insta::assert_debug_snapshot!(err.unwrap_err(), @r#"Error("got variable `${creds.json#missing}`, but the key `missing` does not exist", line: 0, column: 0)"#);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${invalid.json#key}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    assert!(err.to_string().contains("invalid JSON"), "{err}");
+}
+
+#[cfg(all(feature = "yaml", feature = "toml"))]
+#[test]
+fn test_structured_file_formats() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        tempdir.path().join("config.yaml"),
+        "database:\n  password: hunter2\n",
+    )
+    .unwrap();
+    std::fs::write(
+        tempdir.path().join("config.toml"),
+        "[database]\nport = 5432\n",
+    )
+    .unwrap();
+
+    let mut source = FileSource::new().with_base_path(tempdir.path());
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${config.yaml#database.password}", "${config.toml#database.port}"]"#,
+    );
+    let r: (String, u16) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ("hunter2".to_owned(), 5432));
+}
+
+#[test]
+fn test_duration_suffixes() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(tempdir.path().join("my_test.file"), "1h30m").unwrap();
+
+    let mut source = FileSource::new()
+        .with_base_path(tempdir.path())
+        .with_duration_suffixes(serde_vars::source::DurationUnit::Minutes);
+    let mut de = serde_json::Deserializer::from_str("\"${my_test.file}\"");
+    let r: u32 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, 90);
+}
+
+#[test]
+fn test_byte_size_suffixes() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(tempdir.path().join("my_test.file"), "512KiB").unwrap();
+
+    let mut source = FileSource::new()
+        .with_base_path(tempdir.path())
+        .with_byte_size_suffixes(true);
+    let mut de = serde_json::Deserializer::from_str("\"${my_test.file}\"");
+    let r: u64 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, 524_288);
+}
@@ -0,0 +1,77 @@
+use std::process::Command;
+
+use serde_vars::source::{GitConfigLookup, GitConfigSource};
+
+#[test]
+fn test_git_config() {
+    let temp = tempfile::tempdir().unwrap();
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(temp.path())
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    git(&["init", "--quiet"]);
+    git(&["config", "user.email", "jane@example.com"]);
+    git(&[
+        "config",
+        "remote.origin.url",
+        "https://example.com/repo.git",
+    ]);
+    git(&["config", "core.bigFileThreshold", "512"]);
+    git(&["config", "--add", "alias.co", "checkout"]);
+    git(&["config", "--add", "alias.co", "checkout --quiet"]);
+
+    let mut source = GitConfigSource::for_dir(temp.path());
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${user.email}", "${remote.origin.url}", "${core.bigfilethreshold}", "${alias.co}"]"#,
+    );
+    let r: (String, String, u32, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.0, "jane@example.com");
+    assert_eq!(r.1, "https://example.com/repo.git");
+    assert_eq!(r.2, 512);
+    assert_eq!(r.3, "checkout --quiet");
+
+    let errors = [
+        r#""${remote.upstream.url}""#,
+        r#""${email}""#,
+        r#""${--help}""#,
+    ]
+    .map(|json| {
+        let mut de = serde_json::Deserializer::from_str(json);
+        let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+        r.unwrap_err().to_string()
+    });
+    insta::assert_debug_snapshot!(errors, @r#"
+    [
+        "got variable `${remote.upstream.url}`, but it does not exist",
+        "got variable `${email}`, but failed to look it up: key does not contain a section: email",
+        "got variable `${--help}`, but failed to look it up: key does not contain a section: --help",
+    ]
+    "#);
+}
+
+#[test]
+fn test_git_config_file() {
+    let temp = tempfile::tempdir().unwrap();
+    std::fs::write(
+        temp.path().join(".gitmodules"),
+        "[submodule \"vendor\"]\n\tpath = vendor\n\turl = https://example.com/vendor.git\n",
+    )
+    .unwrap();
+
+    let mut source = GitConfigSource::new(
+        GitConfigLookup::new()
+            .with_dir(temp.path())
+            .with_file(".gitmodules"),
+    );
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${submodule.vendor.path}", "${submodule.vendor.url}"]"#,
+    );
+    let r: (String, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.0, "vendor");
+    assert_eq!(r.1, "https://example.com/vendor.git");
+}
@@ -0,0 +1,167 @@
+#![cfg(feature = "http")]
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde_vars::source::{HttpLookup, HttpSource};
+
+#[test]
+fn test_http() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+
+    let recorded = Arc::clone(&requests);
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(&stream);
+            let mut request = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end().to_owned();
+                if line.is_empty() {
+                    break;
+                }
+                if line.starts_with("GET") || line.starts_with("x-token") {
+                    request.push(line);
+                }
+            }
+
+            let (status, body): (_, &[u8]) = match request[0].split(' ').nth(1).unwrap() {
+                "/values/db/password" => ("200 OK", b"hunter2"),
+                "/values/db/port" => ("200 OK", b"5432"),
+                "/values/tls%20key" => ("200 OK", b"\xff\x00"),
+                "/values/forbidden" => ("403 Forbidden", b"forbidden"),
+                "/document" => (
+                    "200 OK",
+                    br#"{"data": {"host": "localhost", "port": 5432, "a/b": true, "null": null}}"#,
+                ),
+                _ => ("404 Not Found", b"not found"),
+            };
+            recorded.lock().unwrap().push(request);
+            write!(
+                stream,
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            )
+            .unwrap();
+            stream.write_all(body).unwrap();
+        }
+    });
+
+    let mut source = HttpSource::new(
+        HttpLookup::new(format!("{url}/values/{{name}}"))
+            .with_header("X-Token", "secret")
+            .with_timeout(Duration::from_secs(5)),
+    );
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${db/password}", "${db/port}", "${tls key}", "${db/port}"]"#,
+    );
+    let r: (String, u16, serde_bytes::ByteBuf, u32) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.0, "hunter2");
+    assert_eq!(r.1, 5432);
+    assert_eq!(r.2.as_slice(), b"\xff\x00");
+    assert_eq!(r.3, 5432);
+
+    let debug = format!("{source:?}");
+    assert!(!debug.contains(r#""secret""#));
+    assert!(!debug.contains("hunter2"));
+
+    let errors = [r#""${missing}""#, r#""${forbidden}""#, r#""${tls key}""#].map(|json| {
+        let mut de = serde_json::Deserializer::from_str(json);
+        let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+        r.unwrap_err().to_string().replace(&url, "<url>")
+    });
+    insta::assert_debug_snapshot!(errors, @r#"
+    [
+        "got variable `${missing}`, but it does not exist",
+        "got variable `${forbidden}`, but failed to look it up: `<url>/values/forbidden` responded with 403 Forbidden",
+        "got variable `${tls key}`, but its value is not valid UTF-8",
+    ]
+    "#);
+
+    // Every URL is only requested once.
+    insta::assert_debug_snapshot!(requests.lock().unwrap(), @r#"
+    [
+        [
+            "GET /values/db/password HTTP/1.1",
+            "x-token: secret",
+        ],
+        [
+            "GET /values/db/port HTTP/1.1",
+            "x-token: secret",
+        ],
+        [
+            "GET /values/tls%20key HTTP/1.1",
+            "x-token: secret",
+        ],
+        [
+            "GET /values/missing HTTP/1.1",
+            "x-token: secret",
+        ],
+        [
+            "GET /values/forbidden HTTP/1.1",
+            "x-token: secret",
+        ],
+    ]
+    "#);
+
+    // Failed requests are requested again, other responses until the cache is cleared.
+    requests.lock().unwrap().clear();
+    for json in [
+        r#""${forbidden}""#,
+        r#""${db/password}""#,
+        r#""${missing}""#,
+    ] {
+        let mut de = serde_json::Deserializer::from_str(json);
+        let _: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+    }
+    source.lookup_mut().clear();
+    let mut de = serde_json::Deserializer::from_str(r#""${db/password}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "hunter2");
+    insta::assert_debug_snapshot!(requests.lock().unwrap(), @r#"
+    [
+        [
+            "GET /values/forbidden HTTP/1.1",
+            "x-token: secret",
+        ],
+        [
+            "GET /values/db/password HTTP/1.1",
+            "x-token: secret",
+        ],
+    ]
+    "#);
+
+    let mut source =
+        HttpSource::new(HttpLookup::new(format!("{url}/document")).with_pointer("/data/{name}"));
+    let mut de = serde_json::Deserializer::from_str(r#"["${host}", "${port}", "${a/b}"]"#);
+    let r: (String, u16, bool) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ("localhost".to_owned(), 5432, true));
+
+    let errors = [r#""${null}""#, r#""${missing}""#].map(|json| {
+        let mut de = serde_json::Deserializer::from_str(json);
+        let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+        r.unwrap_err().to_string()
+    });
+    insta::assert_debug_snapshot!(errors, @r#"
+    [
+        "got variable `${null}`, but it does not exist",
+        "got variable `${missing}`, but it does not exist",
+    ]
+    "#);
+
+    let mut source = HttpSource::new(
+        HttpLookup::new(format!("{url}/values/{{name}}")).with_root_certificates("invalid"),
+    );
+    let mut de = serde_json::Deserializer::from_str(r#""${db/password}""#);
+    let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+    insta::assert_snapshot!(r.unwrap_err(), @r#"got variable `${db/password}`, but failed to look it up: invalid root certificates: no PEM encoded certificate found"#);
+}
@@ -0,0 +1,186 @@
+#![cfg(feature = "imds")]
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+};
+
+use serde_vars::source::{ImdsLookup, ImdsSource};
+
+#[test]
+fn test_imds() {
+    // A fake instance metadata service, recording the requests it receives.
+    let serve = |tokens: bool| {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&stream);
+                let mut request = Vec::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end().to_owned();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if request.is_empty() || line.starts_with("x-aws-ec2-metadata-token") {
+                        request.push(line);
+                    }
+                }
+
+                let (status, body) = match request[0].split(' ').take(2).collect::<Vec<_>>()[..] {
+                    ["PUT", "/latest/api/token"] if tokens => ("200 OK", "token"),
+                    ["PUT", "/latest/api/token"] => ("403 Forbidden", ""),
+                    ["GET", "/latest/meta-data/placement/region"] => ("200 OK", "eu-central-1"),
+                    ["GET", "/latest/meta-data/tags/instance/Environment"] => {
+                        ("200 OK", "production")
+                    }
+                    ["GET", "/latest/meta-data/tags/instance/Forbidden"] => {
+                        ("401 Unauthorized", "")
+                    }
+                    _ => ("404 Not Found", "not found"),
+                };
+                recorded.lock().unwrap().push(request);
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+
+        (url, requests)
+    };
+
+    let (url, requests) = serve(true);
+    let mut source = ImdsSource::new(ImdsLookup::new().with_endpoint(&url));
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${meta:placement/region}", "${tag:Environment}", "${meta:placement/region}"]"#,
+    );
+    let r: (String, String, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.0, "eu-central-1");
+    assert_eq!(r.1, "production");
+    assert_eq!(r.2, "eu-central-1");
+
+    let debug = format!("{source:?}");
+    assert!(!debug.contains(r#""token""#));
+    assert!(!debug.contains("production"));
+
+    let errors = [
+        r#""${tag:Missing}""#,
+        r#""${tag:Forbidden}""#,
+        r#""${placement/region}""#,
+    ]
+    .map(|json| {
+        let mut de = serde_json::Deserializer::from_str(json);
+        let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+        r.unwrap_err().to_string()
+    });
+    insta::assert_debug_snapshot!(errors, @r#"
+    [
+        "got variable `${tag:Missing}`, but it does not exist",
+        "got variable `${tag:Forbidden}`, but failed to look it up: the instance metadata service responded with 401 Unauthorized",
+        "got variable `${placement/region}`, but failed to look it up: expected a variable of the form `meta:<path>` or `tag:<key>`",
+    ]
+    "#);
+
+    // The token is requested once and every variable only once.
+    insta::assert_debug_snapshot!(requests.lock().unwrap(), @r#"
+    [
+        [
+            "PUT /latest/api/token HTTP/1.1",
+            "x-aws-ec2-metadata-token-ttl-seconds: 21600",
+        ],
+        [
+            "GET /latest/meta-data/placement/region HTTP/1.1",
+            "x-aws-ec2-metadata-token: token",
+        ],
+        [
+            "GET /latest/meta-data/tags/instance/Environment HTTP/1.1",
+            "x-aws-ec2-metadata-token: token",
+        ],
+        [
+            "GET /latest/meta-data/tags/instance/Missing HTTP/1.1",
+            "x-aws-ec2-metadata-token: token",
+        ],
+        [
+            "GET /latest/meta-data/tags/instance/Forbidden HTTP/1.1",
+            "x-aws-ec2-metadata-token: token",
+        ],
+    ]
+    "#);
+
+    // Failed requests are repeated, other values are cached until the cache is cleared.
+    requests.lock().unwrap().clear();
+    for json in [
+        r#""${tag:Forbidden}""#,
+        r#""${tag:Environment}""#,
+        r#""${tag:Missing}""#,
+    ] {
+        let mut de = serde_json::Deserializer::from_str(json);
+        let _: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+    }
+    source.lookup_mut().clear();
+    let mut de = serde_json::Deserializer::from_str(r#""${tag:Environment}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "production");
+    insta::assert_debug_snapshot!(requests.lock().unwrap(), @r#"
+    [
+        [
+            "GET /latest/meta-data/tags/instance/Forbidden HTTP/1.1",
+            "x-aws-ec2-metadata-token: token",
+        ],
+        [
+            "PUT /latest/api/token HTTP/1.1",
+            "x-aws-ec2-metadata-token-ttl-seconds: 21600",
+        ],
+        [
+            "GET /latest/meta-data/tags/instance/Environment HTTP/1.1",
+            "x-aws-ec2-metadata-token: token",
+        ],
+    ]
+    "#);
+
+    // Falls back to IMDSv1 without tokens.
+    let (url, requests) = serve(false);
+    let mut source = ImdsSource::new(ImdsLookup::new().with_endpoint(format!("{url}/")));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${meta:placement/region}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "eu-central-1");
+
+    insta::assert_debug_snapshot!(requests.lock().unwrap(), @r#"
+    [
+        [
+            "PUT /latest/api/token HTTP/1.1",
+            "x-aws-ec2-metadata-token-ttl-seconds: 21600",
+        ],
+        [
+            "GET /latest/meta-data/placement/region HTTP/1.1",
+        ],
+    ]
+    "#);
+}
+
+#[test]
+fn test_imds_unavailable() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    drop(listener);
+
+    let mut source = ImdsSource::new(ImdsLookup::new().with_endpoint(&url));
+    let mut de = serde_json::Deserializer::from_str(r#""${meta:instance-id}""#);
+    let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+    assert!(r
+        .unwrap_err()
+        .to_string()
+        .starts_with("got variable `${meta:instance-id}`, but failed to look it up: request to the instance metadata service failed"));
+}
@@ -0,0 +1,153 @@
+#![cfg(feature = "kube")]
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+};
+
+use serde_vars::source::{KubeLookup, KubeSource};
+
+#[test]
+fn test_kube() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+
+    let recorded = Arc::clone(&requests);
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(&stream);
+            let mut request = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end().to_owned();
+                if line.is_empty() {
+                    break;
+                }
+                if line.starts_with("GET") || line.starts_with("authorization") {
+                    request.push(line);
+                }
+            }
+
+            let (status, body) = match request[0].split(' ').nth(1).unwrap() {
+                "/api/v1/namespaces/prod/secrets/db" => (
+                    "200 OK",
+                    r#"{"kind": "Secret", "data": {"password": "aHVudGVyMg==", "port": "NTQzMg==", "raw": "/wA="}}"#,
+                ),
+                "/api/v1/namespaces/prod/configmaps/app" => (
+                    "200 OK",
+                    r#"{"kind": "ConfigMap", "data": {"host": "localhost"}, "binaryData": {"raw": "/wA="}}"#,
+                ),
+                "/api/v1/namespaces/kube-system/secrets/admin" => (
+                    "403 Forbidden",
+                    r#"{"kind": "Status", "message": "secrets \"admin\" is forbidden"}"#,
+                ),
+                _ => ("404 Not Found", r#"{"kind": "Status"}"#),
+            };
+            recorded.lock().unwrap().push(request);
+            write!(
+                stream,
+                "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        }
+    });
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Config {
+        password: String,
+        port: u16,
+        #[serde(with = "serde_bytes")]
+        raw: Vec<u8>,
+    }
+
+    let mut source = KubeSource::new(KubeLookup::new(&url).with_token("token"));
+    let mut de = serde_json::Deserializer::from_str(
+        r#"{"password": "${prod/db/password}", "port": "${prod/db/port}", "raw": "${prod/db/raw}"}"#,
+    );
+    let config: Config = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(config.password, "hunter2");
+    assert_eq!(config.port, 5432);
+    assert_eq!(config.raw, b"\xff\x00");
+
+    let debug = format!("{source:?}");
+    assert!(!debug.contains(r#""token""#));
+    assert!(!debug.contains("hunter2"));
+
+    let errors = [
+        r#""${prod/db/missing}""#,
+        r#""${prod/missing/key}""#,
+        r#""${kube-system/admin/password}""#,
+        r#""${prod/db}""#,
+        r#""${prod/db/raw}""#,
+    ]
+    .map(|json| {
+        let mut de = serde_json::Deserializer::from_str(json);
+        let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+        r.unwrap_err().to_string()
+    });
+    insta::assert_debug_snapshot!(errors, @r#"
+    [
+        "got variable `${prod/db/missing}`, but it does not exist",
+        "got variable `${prod/missing/key}`, but it does not exist",
+        "got variable `${kube-system/admin/password}`, but failed to look it up: the Kubernetes API responded with 403 Forbidden: secrets \"admin\" is forbidden",
+        "got variable `${prod/db}`, but failed to look it up: expected a variable of the form `namespace/name/key`",
+        "got variable `${prod/db/raw}`, but its value is not valid UTF-8",
+    ]
+    "#);
+
+    // Every object is only requested once.
+    insta::assert_debug_snapshot!(requests.lock().unwrap(), @r#"
+    [
+        [
+            "GET /api/v1/namespaces/prod/secrets/db HTTP/1.1",
+            "authorization: Bearer token",
+        ],
+        [
+            "GET /api/v1/namespaces/prod/secrets/missing HTTP/1.1",
+            "authorization: Bearer token",
+        ],
+        [
+            "GET /api/v1/namespaces/kube-system/secrets/admin HTTP/1.1",
+            "authorization: Bearer token",
+        ],
+    ]
+    "#);
+
+    // Failures are requested again, other objects until the cache is cleared.
+    requests.lock().unwrap().clear();
+    for json in [
+        r#""${kube-system/admin/password}""#,
+        r#""${prod/db/password}""#,
+        r#""${prod/missing/key}""#,
+    ] {
+        let mut de = serde_json::Deserializer::from_str(json);
+        let _: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+    }
+    source.lookup_mut().clear();
+    let mut de = serde_json::Deserializer::from_str(r#""${prod/db/password}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "hunter2");
+    insta::assert_debug_snapshot!(requests.lock().unwrap(), @r#"
+    [
+        [
+            "GET /api/v1/namespaces/kube-system/secrets/admin HTTP/1.1",
+            "authorization: Bearer token",
+        ],
+        [
+            "GET /api/v1/namespaces/prod/secrets/db HTTP/1.1",
+            "authorization: Bearer token",
+        ],
+    ]
+    "#);
+
+    let mut source = KubeSource::new(KubeLookup::new(&url).with_config_maps(true));
+    let mut de = serde_json::Deserializer::from_str(r#"["${prod/app/host}", "${prod/app/raw}"]"#);
+    let r: (String, serde_bytes::ByteBuf) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.0, "localhost");
+    assert_eq!(r.1.as_slice(), b"\xff\x00");
+}
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use serde_vars::{
+    source::{MapNames, Source},
+    MapSource,
+};
+
+fn map(values: &[(&str, &str)]) -> MapSource {
+    let values = values
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect::<HashMap<_, _>>();
+    MapSource::new(values)
+}
+
+#[test]
+fn test_map_names() {
+    let mut source = map(&[("PROD_USER", "admin"), ("PROD_PORT", "8080")])
+        .map_names(|name| format!("PROD_{name}"));
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${USER}", "${PORT}", "plain"]"#);
+    let r: (String, u16, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ("admin".to_owned(), 8080, "plain".to_owned()));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${USER}""#);
+    let r: serde_bytes::ByteBuf = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.as_slice(), b"admin");
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${PORT}", "plain"]"#);
+    let r: serde_json::Value = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, serde_json::json!([8080, "plain"]));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${HOST}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${PROD_HOST}`, but it does not exist"#);
+}
+
+#[test]
+fn test_map_names_alias() {
+    let mut source = MapNames::new(
+        map(&[("DATABASE_URL", "postgres://db")]),
+        |name| match name {
+            "DB_URL" => "DATABASE_URL".to_owned(),
+            name => name.replace('-', "_"),
+        },
+    )
+    .or(map(&[("DB_URL", "unused"), ("LOG_LEVEL", "info")]));
+
+    let mut de =
+        serde_json::Deserializer::from_str(r#"["${DB_URL}", "${DATABASE-URL}", "${LOG-LEVEL}"]"#);
+    let err = serde_vars::deserialize::<_, _, Vec<String>>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${LOG-LEVEL}`, but it does not exist in any of the sources: StringSource<HashMap<String, String>>, StringSource<HashMap<String, String>> at line 1 column 48"#);
+
+    let mut de =
+        serde_json::Deserializer::from_str(r#"["${DB_URL}", "${DATABASE-URL}", "${LOG_LEVEL}"]"#);
+    let r: Vec<String> = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ["postgres://db", "postgres://db", "info"]);
+}
+
+#[test]
+fn test_map_names_delimiters() {
+    let mut source = map(&[("app.user", "admin")])
+        .with_variable_prefix("{{")
+        .with_variable_suffix("}}")
+        .map_names(|name| format!("app.{}", name.trim()))
+        .with_variable_prefix("{{")
+        .with_variable_suffix("}}");
+
+    let mut de = serde_json::Deserializer::from_str(r#"["{{ user }}", "${user}"]"#);
+    let r: Vec<String> = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ["admin", "${user}"]);
+}
+
+#[test]
+fn test_map_names_prefix_maps() {
+    let mut source = map(&[("PROD_FEATURE_BETA", "on"), ("FEATURE_BETA", "off")])
+        .with_prefix_maps(true)
+        .map_names(|name| format!("PROD_{name}"));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${prefix:FEATURE_}""#);
+    let r: HashMap<String, String> = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, HashMap::from([("BETA".to_owned(), "on".to_owned())]));
+}
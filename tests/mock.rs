@@ -0,0 +1,81 @@
+use serde_vars::source::{MockSource, Source};
+
+#[test]
+fn test_mock() {
+    let mut source = MockSource::new();
+    source.expect("HOST").returns("localhost").times(1);
+    source.expect("PORT").returns("8080").times(2);
+    source.expect("TOKEN").returns("secret");
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${HOST}", "${PORT}", "${PORT}", "plain", "${TOKEN}"]"#,
+    );
+    let r: (String, u16, u64, String, serde_bytes::ByteBuf) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.0, "localhost");
+    assert_eq!(r.1, 8080);
+    assert_eq!(r.2, 8080);
+    assert_eq!(r.3, "plain");
+    assert_eq!(r.4.as_slice(), b"secret");
+
+    source.verify();
+
+    source.expect("PORT").returns("9090");
+    let mut de = serde_json::Deserializer::from_str(r#""${PORT}""#);
+    let r: serde_json::Value = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, serde_json::json!(9090));
+}
+
+#[test]
+fn test_mock_missing() {
+    let mut source = MockSource::new();
+    source.expect("HOST").times(1);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${HOST}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${HOST}`, but it does not exist"#);
+
+    source.expect("PORT").returns("8080");
+    let mut source = source.or(MockSource::new());
+    let mut de = serde_json::Deserializer::from_str(r#""${PORT}""#);
+    let r: u16 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, 8080);
+}
+
+#[test]
+fn test_mock_unexpected() {
+    let mut source = MockSource::new();
+    source.expect("HOST").returns("localhost").times(1);
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${HOST}", "${HOST}"]"#);
+    let err = serde_vars::deserialize::<_, _, Vec<String>>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${HOST}`, but it was expected to be expanded 1 times at line 1 column 22"#);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${PORT}""#);
+    let err = serde_vars::deserialize::<_, _, u16>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${PORT}`, but it was not expected"#);
+
+    source.expect("USER");
+    source.expect("TOKEN").returns("secret").times(2);
+    let mut de = serde_json::Deserializer::from_str(r#""${TOKEN}""#);
+    let _: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+
+    let err = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| source.verify()))
+        .unwrap_err()
+        .downcast::<String>()
+        .unwrap();
+    insta::assert_snapshot!(err, @r#"
+    mock source expectations violated:
+    unexpected variable `${PORT}`
+    variable `${HOST}` expected 1 times, but expanded 2 times
+    variable `${USER}` expected, but never expanded
+    variable `${TOKEN}` expected 2 times, but expanded 1 times
+    "#);
+}
+
+#[test]
+#[should_panic = "variable `${HOST}` expected, but never expanded"]
+fn test_mock_drop() {
+    let mut source = MockSource::new();
+    source.expect("HOST").returns("localhost");
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use serde_vars::{source::MultiSource, MapSource};
+
+fn map(values: &[(&str, &str)]) -> MapSource {
+    let values = values
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect::<HashMap<_, _>>();
+    MapSource::new(values)
+}
+
+#[test]
+fn test_multi() {
+    let mut source = MultiSource::new(
+        "DB_*",
+        map(&[("DB_USER", "admin"), ("DB_PORT", "5432")]),
+        map(&[("HOST", "localhost"), ("DB_USER", "fallback")]),
+    )
+    .with_rule("FEATURE_*", map(&[("FEATURE_SEARCH", "true")]));
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${DB_USER}", "${DB_PORT}", "${FEATURE_SEARCH}", "${HOST}", "plain"]"#,
+    );
+    let r: (String, u16, bool, String, String) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(
+        r,
+        (
+            "admin".to_owned(),
+            5432,
+            true,
+            "localhost".to_owned(),
+            "plain".to_owned()
+        )
+    );
+
+    let mut de = serde_json::Deserializer::from_str(r#""${DB_USER}""#);
+    let r: serde_bytes::ByteBuf = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.as_slice(), b"admin");
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${DB_PORT}", "${FEATURE_SEARCH}"]"#);
+    let r: serde_json::Value = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, serde_json::json!([5432, true]));
+
+    // Variables matching a rule are never expanded by other sources.
+    let mut de = serde_json::Deserializer::from_str(r#"{"host": "${DB_HOST}"}"#);
+    let err =
+        serde_vars::deserialize::<_, _, HashMap<String, String>>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${DB_HOST}`, but it does not exist at line 1 column 22"#);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${FEATURE_LOGIN}""#);
+    let err = serde_vars::deserialize::<_, _, bool>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${FEATURE_LOGIN}`, but it does not exist"#);
+}
+
+#[test]
+fn test_multi_patterns() {
+    let patterns = [
+        ("DB_*", "DB_USER", true),
+        ("DB_*", "DB_", true),
+        ("DB_*", "MY_DB_USER", false),
+        ("*_PASSWORD", "DB_PASSWORD", true),
+        ("*_PASSWORD", "DB_PASSWORD_FILE", false),
+        ("*SECRET*", "API_SECRET_KEY", true),
+        ("APP_?_HOST", "APP_1_HOST", true),
+        ("APP_?_HOST", "APP_12_HOST", false),
+        ("A*B*C", "AXXBYYBZZC", true),
+        ("A*B*C", "AXXBYYC", true),
+        ("A*B*C", "AXXCYYB", false),
+        ("HOST", "HOST", true),
+        ("HOST", "HOSTS", false),
+        ("*", "", true),
+    ];
+
+    for (pattern, name, expected) in patterns {
+        let mut source = MultiSource::new(pattern, map(&[(name, "routed")]), map(&[]));
+
+        let input = format!(r#""${{{name}}}""#);
+        let mut de = serde_json::Deserializer::from_str(&input);
+        let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+        assert_eq!(r.is_ok(), expected, "{pattern} {name}");
+    }
+}
+
+#[test]
+fn test_multi_precedence() {
+    let mut source = MultiSource::new("DB_*", map(&[("DB_ADMIN_PASSWORD", "general")]), map(&[]))
+        .with_rule("DB_ADMIN_*", map(&[("DB_ADMIN_PASSWORD", "specific")]));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${DB_ADMIN_PASSWORD}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "specific");
+}
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use serde_vars::{
+    source::{Namespaced, Source},
+    MapSource,
+};
+
+fn map(values: &[(&str, &str)]) -> MapSource {
+    let values = values
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect::<HashMap<_, _>>();
+    MapSource::new(values)
+}
+
+#[test]
+fn test_namespaced() {
+    let mut source = map(&[("MYLIB_USER", "admin"), ("MYLIB_PORT", "8080")]).namespaced("MYLIB_");
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${USER}", "${PORT}", "plain"]"#);
+    let r: (String, u16, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ("admin".to_owned(), 8080, "plain".to_owned()));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${USER}""#);
+    let r: serde_bytes::ByteBuf = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.as_slice(), b"admin");
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${PORT}", "plain"]"#);
+    let r: serde_json::Value = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, serde_json::json!([8080, "plain"]));
+
+    let mut de = serde_json::Deserializer::from_str(r#"{"host": "${HOST}"}"#);
+    let err =
+        serde_vars::deserialize::<_, _, HashMap<String, String>>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${MYLIB_HOST}`, but it does not exist at line 1 column 19"#);
+}
+
+#[test]
+fn test_namespaced_services() {
+    let mut values = map(&[
+        ("BILLING_PORT", "8080"),
+        ("SHIPPING_PORT", "9090"),
+        ("PORT", "80"),
+    ]);
+    let config = r#"{"port": "${PORT}"}"#;
+
+    for (namespace, port) in [("BILLING_", 8080), ("SHIPPING_", 9090), ("", 80)] {
+        let mut source = Namespaced::new(&mut values, namespace);
+        assert_eq!(source.namespace(), namespace);
+
+        let mut de = serde_json::Deserializer::from_str(config);
+        let r: HashMap<String, u16> = serde_vars::deserialize(&mut de, &mut source).unwrap();
+        assert_eq!(r, HashMap::from([("port".to_owned(), port)]));
+    }
+}
+
+#[test]
+fn test_namespaced_delimiters() {
+    let mut source = map(&[("app.user", "admin")])
+        .with_variable_prefix("{{")
+        .with_variable_suffix("}}")
+        .namespaced("app.")
+        .with_variable_prefix("{{")
+        .with_variable_suffix("}}");
+
+    let mut de = serde_json::Deserializer::from_str(r#"["{{user}}", "${user}"]"#);
+    let r: Vec<String> = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ["admin", "${user}"]);
+}
+
+#[test]
+fn test_namespaced_prefix_maps() {
+    let mut source = map(&[("APP_FEATURE_BETA", "on"), ("FEATURE_DARK_MODE", "on")])
+        .with_prefix_maps(true)
+        .namespaced("APP_");
+
+    let mut de = serde_json::Deserializer::from_str(r#""${prefix:FEATURE_}""#);
+    let r: HashMap<String, String> = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, HashMap::from([("BETA".to_owned(), "on".to_owned())]));
+}
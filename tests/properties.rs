@@ -0,0 +1,83 @@
+use serde_vars::{source::PropertiesLookup, PropertiesSource};
+
+#[test]
+fn test_syntax() {
+    let contents = concat!(
+        "# A comment\n",
+        "! Another comment \\\n",
+        "\n",
+        "   equals = value with trailing spaces  \n",
+        "colon:value\r\n",
+        "space value\n",
+        "empty\n",
+        "escapes = tab\\tnew\\nline \\\\ \\# \\x\n",
+        "unicode = gr\\u00fc\\u00DFe \\ud83d\\ude00 grüße\n",
+        "key\\ with\\=escaped\\:separators = value\n",
+        "continued = first \\\n",
+        "            second\\\\\n",
+        "not.continued = third\n",
+        "continued.comment = a\\\n",
+        "  # b\n",
+        "equals = overridden\n",
+        "eof = last\\",
+    );
+
+    let mut source = PropertiesSource::from_contents(contents).unwrap();
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${equals}", "${colon}", "${space}", "${empty}", "${escapes}", "${unicode}", "${key with=escaped:separators}", "${continued}", "${not.continued}", "${continued.comment}", "${eof}"]"#,
+    );
+    let r: Vec<String> = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    insta::assert_debug_snapshot!(r, @r#"
+    [
+        "overridden",
+        "value",
+        "value",
+        "",
+        "tab\tnew\nline \\ # x",
+        "grüße 😀 grüße",
+        "value",
+        "first second\\",
+        "third",
+        "a# b",
+        "last",
+    ]
+    "#);
+}
+
+#[test]
+fn test_errors() {
+    let errors = [
+        "valid = 1\ninvalid = \\u12\n",
+        "invalid = \\u12g4",
+        "a = 1\nb = \\\n  2\\\n  \\ud83d",
+        "invalid = \\ude00",
+        "invalid = \\ud83dx",
+    ]
+    .map(|contents| PropertiesLookup::parse(contents).unwrap_err().to_string());
+    insta::assert_debug_snapshot!(errors, @r#"
+    [
+        "malformed \\uXXXX escape at line 2",
+        "malformed \\uXXXX escape at line 1",
+        "invalid surrogate pair in \\uXXXX escape at line 2",
+        "invalid surrogate pair in \\uXXXX escape at line 1",
+        "invalid surrogate pair in \\uXXXX escape at line 1",
+    ]
+    "#);
+}
+
+#[test]
+fn test_from_path() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("application.properties");
+    std::fs::write(&path, "server.port=8080\n").unwrap();
+
+    let mut source = PropertiesSource::from_path(&path).unwrap();
+    let mut de = serde_json::Deserializer::from_str(r#""${server.port}""#);
+    let r: u16 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, 8080);
+
+    std::fs::write(&path, "invalid = \\u").unwrap();
+    let err = PropertiesSource::from_path(&path).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "malformed \\uXXXX escape at line 1");
+}
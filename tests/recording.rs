@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_vars::{
+    source::{Outcome, Record, RecordingSource},
+    MapSource,
+};
+
+fn map(values: &[(&str, &str)]) -> MapSource {
+    let values = values
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect::<HashMap<_, _>>();
+    MapSource::new(values)
+}
+
+#[test]
+fn test_recording() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Config {
+        host: String,
+        port: u16,
+        debug: bool,
+        name: String,
+        token: serde_bytes::ByteBuf,
+        extra: serde_json::Value,
+    }
+
+    let mut source = RecordingSource::new(map(&[
+        ("HOST", "localhost"),
+        ("PORT", "8080"),
+        ("DEBUG", "true"),
+        ("TOKEN", "secret"),
+        ("EXTRA", "1"),
+    ]));
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"{
+            "host": "${HOST}",
+            "port": "${PORT}",
+            "debug": "${DEBUG}",
+            "name": "plain",
+            "token": "${TOKEN}",
+            "extra": "${EXTRA}"
+        }"#,
+    );
+    let _: Config = serde_vars::deserialize(&mut de, &mut source).unwrap();
+
+    insta::assert_debug_snapshot!(source.records(), @r#"
+    [
+        Record {
+            variable: "HOST",
+            ty: "str",
+            outcome: Hit,
+        },
+        Record {
+            variable: "PORT",
+            ty: "u16",
+            outcome: Hit,
+        },
+        Record {
+            variable: "DEBUG",
+            ty: "bool",
+            outcome: Hit,
+        },
+        Record {
+            variable: "TOKEN",
+            ty: "bytes",
+            outcome: Hit,
+        },
+        Record {
+            variable: "EXTRA",
+            ty: "any",
+            outcome: Hit,
+        },
+    ]
+    "#);
+}
+
+#[test]
+fn test_recording_failures() {
+    let mut source = RecordingSource::new(map(&[("PORT", "not a port")]));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${PORT}""#);
+    serde_vars::deserialize::<_, _, u16>(&mut de, &mut source).unwrap_err();
+
+    let mut de = serde_json::Deserializer::from_str(r#""${HOST}""#);
+    serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+
+    let records = source.take_records();
+    assert_eq!(records[1], Record::new("HOST", "str", Outcome::Miss));
+    insta::assert_debug_snapshot!(records, @r#"
+    [
+        Record {
+            variable: "PORT",
+            ty: "u16",
+            outcome: Error(
+                "invalid value: string \"not a port\", expected variable `${PORT}` to be an unsigned integer (i16)",
+            ),
+        },
+        Record {
+            variable: "HOST",
+            ty: "str",
+            outcome: Miss,
+        },
+    ]
+    "#);
+    assert!(source.records().is_empty());
+}
+
+#[test]
+fn test_recording_prefix_maps() {
+    let mut source = RecordingSource::new(
+        map(&[("FEATURE_BETA", "on"), ("FEATURE_DARK_MODE", "off")]).with_prefix_maps(true),
+    );
+
+    let mut de = serde_json::Deserializer::from_str(r#""${prefix:FEATURE_}""#);
+    let r: HashMap<String, String> = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.len(), 2);
+    assert_eq!(r["BETA"], "on");
+    insta::assert_debug_snapshot!(source.records(), @r#"
+    [
+        Record {
+            variable: "prefix:FEATURE_",
+            ty: "map",
+            outcome: Hit,
+        },
+    ]
+    "#);
+}
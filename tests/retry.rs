@@ -0,0 +1,133 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use serde_vars::{
+    source::{RetrySource, StringLookup},
+    StringSource,
+};
+
+/// A lookup which fails the first `failures` lookups of every variable.
+struct Flaky {
+    failures: usize,
+    failed: HashMap<String, usize>,
+    lookups: usize,
+}
+
+fn flaky(failures: usize) -> Flaky {
+    Flaky {
+        failures,
+        failed: HashMap::new(),
+        lookups: 0,
+    }
+}
+
+impl Flaky {
+    fn is_available(&self, v: &str) -> bool {
+        self.failed.get(v).copied().unwrap_or_default() >= self.failures
+    }
+}
+
+impl StringLookup for Flaky {
+    fn lookup(&mut self, v: &str) -> Option<Cow<'_, str>> {
+        self.lookups += 1;
+        match self.is_available(v) {
+            true => v.strip_prefix("ECHO_").map(|v| Cow::Owned(v.to_owned())),
+            false => None,
+        }
+    }
+
+    fn lookup_error(&mut self, v: &str) -> Option<String> {
+        if self.is_available(v) {
+            return None;
+        }
+        *self.failed.entry(v.to_owned()).or_default() += 1;
+        Some("service unavailable".to_owned())
+    }
+}
+
+#[test]
+fn test_retry() {
+    let mut source = RetrySource::new(StringSource::new(flaky(2))).with_backoff(Duration::ZERO);
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${ECHO_localhost}", "${ECHO_8080}", "${ECHO_true}", "plain"]"#,
+    );
+    let r: (String, u16, bool, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ("localhost".to_owned(), 8080, true, "plain".to_owned()));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${ECHO_bytes}""#);
+    let r: serde_bytes::ByteBuf = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.as_slice(), b"bytes");
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${ECHO_8080}", "${ECHO_false}"]"#);
+    let r: serde_json::Value = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, serde_json::json!([8080, false]));
+}
+
+#[test]
+fn test_retry_exhausted() {
+    // Checking whether the variable exists uses up one of the failures.
+    let mut source = RetrySource::new(StringSource::new(flaky(3)))
+        .with_max_attempts(2)
+        .with_backoff(Duration::ZERO);
+
+    let mut de = serde_json::Deserializer::from_str(r#"{"port": "${ECHO_8080}"}"#);
+    let err =
+        serde_vars::deserialize::<_, _, HashMap<String, u16>>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${ECHO_8080}`, but failed to look it up: service unavailable at line 1 column 24"#);
+
+    // The next attempt succeeds.
+    let mut de = serde_json::Deserializer::from_str(r#"{"port": "${ECHO_8080}"}"#);
+    let r: HashMap<String, u16> = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, HashMap::from([("port".to_owned(), 8080)]));
+}
+
+#[test]
+fn test_retry_if() {
+    let mut source = RetrySource::new(StringSource::new(flaky(1)))
+        .with_backoff(Duration::ZERO)
+        .with_retry_if(|err| err.to_string().contains("unavailable"));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${ECHO_1}""#);
+    let r: u8 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, 1);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${ECHO_x}""#);
+    let err = serde_vars::deserialize::<_, _, u8>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"invalid value: string "x", expected variable `${ECHO_x}` to be an unsigned integer (i8)"#);
+
+    // The error would be retried, but the variable does not exist.
+    let mut de = serde_json::Deserializer::from_str(r#""${USER}""#);
+    let err = serde_vars::deserialize::<_, _, u8>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${USER}`, but failed to look it up: service unavailable"#);
+}
+
+#[test]
+fn test_retry_missing() {
+    let mut source = RetrySource::new(StringSource::new(flaky(0)))
+        .with_max_attempts(5)
+        .with_backoff(Duration::ZERO);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${USER}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${USER}`, but it does not exist"#);
+    // Missing variables are not retried, the failed expansion is followed by a single check
+    // whether the variable exists.
+    assert_eq!(source.into_inner().into_inner().lookups, 2);
+}
+
+#[test]
+fn test_retry_backoff() {
+    let mut source =
+        RetrySource::new(StringSource::new(flaky(3))).with_backoff(Duration::from_millis(20));
+
+    let start = Instant::now();
+    let mut de = serde_json::Deserializer::from_str(r#""${ECHO_1}""#);
+    let r: u8 = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, 1);
+    // Waits 20ms before the first and 40ms before the second retry.
+    assert!(start.elapsed() >= Duration::from_millis(60));
+}
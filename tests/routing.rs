@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use serde_vars::{FileSource, MapSource, RoutingSource};
+
+fn map(values: &[(&str, &str)]) -> MapSource {
+    let values = values
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect::<HashMap<_, _>>();
+    MapSource::new(values)
+}
+
+#[test]
+fn test_routing() {
+    let tempdir = tempfile::tempdir().unwrap();
+    std::fs::write(tempdir.path().join("secret"), "hunter2").unwrap();
+
+    let mut source = RoutingSource::new(
+        "cfg",
+        map(&[("USER", "admin"), ("PORT", "8080")]),
+        map(&[("USER", "default")]),
+    )
+    .with_scheme("file", FileSource::new().with_base_path(tempdir.path()));
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${cfg:USER}", "${file:secret}", "${USER}", "${cfg:PORT}", "plain"]"#,
+    );
+    let r: (String, String, String, u16, String) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(
+        r,
+        (
+            "admin".to_owned(),
+            "hunter2".to_owned(),
+            "default".to_owned(),
+            8080,
+            "plain".to_owned()
+        )
+    );
+}
+
+#[test]
+fn test_routing_bytes() {
+    let mut source = RoutingSource::new("cfg", map(&[("KEY", "value")]), map(&[]));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${cfg:KEY}""#);
+    let r: serde_bytes::ByteBuf = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.as_slice(), b"value");
+}
+
+#[test]
+fn test_routing_any() {
+    let mut source = RoutingSource::new("cfg", map(&[("KEY", "true")]), map(&[]));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${cfg:KEY}""#);
+    let r: serde_json::Value = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, serde_json::Value::Bool(true));
+}
+
+#[test]
+fn test_routing_unknown_scheme() {
+    let mut source = RoutingSource::new("cfg", map(&[("KEY", "value")]), map(&[]));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${other:KEY}""#);
+    let r = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(r, @"got variable `${other:KEY}`, but it does not exist");
+}
+
+#[test]
+fn test_routing_variable_delimiters() {
+    let mut source = RoutingSource::new(
+        "cfg",
+        map(&[("KEY", "value")]).with_variable_prefix("{{"),
+        map(&[]),
+    )
+    .with_variable_prefix("{{");
+
+    let mut de = serde_json::Deserializer::from_str(r#""{{cfg:KEY}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "value");
+}
@@ -0,0 +1,13 @@
+#![cfg(feature = "secret-service")]
+
+use serde_vars::source::{SecretServiceLookup, SecretServiceSource};
+
+#[test]
+fn test_invalid_variable() {
+    // Fails before connecting to the Secret Service, which is not available in tests.
+    let mut source = SecretServiceSource::new(SecretServiceLookup::new());
+
+    let mut de = serde_json::Deserializer::from_str(r#""${db-password}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${db-password}`, but failed to look it up: expected a variable of the form `service/username`"#);
+}
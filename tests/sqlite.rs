@@ -0,0 +1,66 @@
+#![cfg(feature = "sqlite")]
+
+use serde_vars::source::{SqliteLookup, SqliteSource};
+
+#[test]
+fn test_sqlite() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("settings.db");
+    let connection = rusqlite::Connection::open(&path).unwrap();
+    connection
+        .execute_batch(
+            r#"
+            CREATE TABLE vars (key TEXT PRIMARY KEY, value);
+            INSERT INTO vars VALUES
+                ('db.host', 'localhost'),
+                ('db.port', 5432),
+                ('ratio', 0.5),
+                ('key', x'ff00'),
+                ('null', NULL);
+            "#,
+        )
+        .unwrap();
+
+    let mut source = SqliteSource::open(&path).unwrap();
+    let mut de =
+        serde_json::Deserializer::from_str(r#"["${db.host}", "${db.port}", "${ratio}", "${key}"]"#);
+    let r: (String, u16, f64, serde_bytes::ByteBuf) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.0, "localhost");
+    assert_eq!(r.1, 5432);
+    assert_eq!(r.2, 0.5);
+    assert_eq!(r.3.as_slice(), b"\xff\x00");
+
+    // Values are cached.
+    connection
+        .execute(
+            "UPDATE vars SET value = 'example.com' WHERE key = 'db.host'",
+            [],
+        )
+        .unwrap();
+    let mut de = serde_json::Deserializer::from_str(r#""${db.host}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "localhost");
+
+    let errors = [r#""${missing}""#, r#""${null}""#, r#""${key}""#].map(|json| {
+        let mut de = serde_json::Deserializer::from_str(json);
+        let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+        r.unwrap_err().to_string()
+    });
+    insta::assert_debug_snapshot!(errors, @r#"
+    [
+        "got variable `${missing}`, but it does not exist",
+        "got variable `${null}`, but it does not exist",
+        "got variable `${key}`, but its value is not valid UTF-8",
+    ]
+    "#);
+
+    let mut source = SqliteSource::new(
+        SqliteLookup::open(&path)
+            .unwrap()
+            .with_table("missing \"table\""),
+    );
+    let mut de = serde_json::Deserializer::from_str(r#""${db.host}""#);
+    let r: Result<String, _> = serde_vars::deserialize(&mut de, &mut source);
+    insta::assert_snapshot!(r.unwrap_err(), @r#"got variable `${db.host}`, but failed to look it up: no such table: missing "table""#);
+}
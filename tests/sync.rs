@@ -0,0 +1,60 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use serde_vars::{
+    source::{Source, SyncSource},
+    MapSource,
+};
+
+fn map(values: &[(&str, &str)]) -> MapSource {
+    let values = values
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect::<HashMap<_, _>>();
+    MapSource::new(values)
+}
+
+#[test]
+fn test_sync_threads() {
+    let source = Arc::new(Mutex::new(map(&[("HOST", "localhost"), ("PORT", "8080")])));
+
+    let handles = (0..8)
+        .map(|_| {
+            let mut source = Arc::clone(&source);
+            std::thread::spawn(move || {
+                let mut de = serde_json::Deserializer::from_str(r#"["${HOST}", "${PORT}"]"#);
+                serde_vars::deserialize::<_, _, (String, u16)>(&mut de, &mut source)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        let r = handle.join().unwrap().unwrap();
+        assert_eq!(r, ("localhost".to_owned(), 8080));
+    }
+}
+
+#[test]
+fn test_sync_shared_reference() {
+    let source = Mutex::new(map(&[("HOST", "localhost"), ("TOKEN", "secret")]));
+    assert_eq!(source.name(), "StringSource<HashMap<String, String>>");
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${HOST}", "plain"]"#);
+    let r: serde_json::Value = serde_vars::deserialize(&mut de, &mut &source).unwrap();
+    assert_eq!(r, serde_json::json!(["localhost", "plain"]));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${TOKEN}""#);
+    let r: serde_bytes::ByteBuf = serde_vars::deserialize(&mut de, &mut &source).unwrap();
+    assert_eq!(r.as_slice(), b"secret");
+
+    let mut chain = (&source).or(map(&[("PORT", "8080")]));
+    let mut de = serde_json::Deserializer::from_str(r#"["${HOST}", "${PORT}"]"#);
+    let r: (String, u16) = serde_vars::deserialize(&mut de, &mut chain).unwrap();
+    assert_eq!(r, ("localhost".to_owned(), 8080));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${USER}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut chain).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${USER}`, but it does not exist in any of the sources: StringSource<HashMap<String, String>>, StringSource<HashMap<String, String>>"#);
+}
@@ -0,0 +1,78 @@
+use std::{collections::HashMap, time::Duration};
+
+use serde_vars::{source::TimeoutSource, StringSource};
+
+#[allow(clippy::panic)]
+fn slow(v: &str) -> Option<String> {
+    match v {
+        "SLOW" => {
+            std::thread::sleep(Duration::from_millis(500));
+            Some("slow".to_owned())
+        }
+        "PANIC" => panic!("lookup failed"),
+        v => v.strip_prefix("ECHO_").map(str::to_owned),
+    }
+}
+
+#[test]
+fn test_timeout() {
+    let mut source = TimeoutSource::new(StringSource::new(slow), Duration::from_secs(5));
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${ECHO_localhost}", "${ECHO_8080}", "${ECHO_true}", "plain"]"#,
+    );
+    let r: (String, u16, bool, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ("localhost".to_owned(), 8080, true, "plain".to_owned()));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${ECHO_bytes}""#);
+    let r: serde_bytes::ByteBuf = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.as_slice(), b"bytes");
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${ECHO_8080}", "${ECHO_false}"]"#);
+    let r: serde_json::Value = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, serde_json::json!([8080, false]));
+
+    let mut de = serde_json::Deserializer::from_str(r#"{"port": "${ECHO_x}"}"#);
+    let err =
+        serde_vars::deserialize::<_, _, HashMap<String, u16>>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"invalid value: string "x", expected variable `${ECHO_x}` to be an unsigned integer (i16) at line 1 column 21"#);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${USER}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${USER}`, but it does not exist"#);
+}
+
+#[test]
+fn test_timeout_elapsed() {
+    let mut source = TimeoutSource::new(StringSource::new(slow), Duration::from_millis(50));
+    insta::assert_debug_snapshot!(source, @r#"
+    TimeoutSource {
+        source: "StringSource<slow>",
+        timeout: 50ms,
+    }
+    "#);
+
+    let mut de = serde_json::Deserializer::from_str(r#"{"name": "${SLOW}"}"#);
+    let err =
+        serde_vars::deserialize::<_, _, HashMap<String, String>>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${SLOW}`, but StringSource<slow> did not expand it within 50ms at line 1 column 19"#);
+
+    // The source recovers once the slow expansion finished.
+    std::thread::sleep(Duration::from_millis(500));
+    let mut de = serde_json::Deserializer::from_str(r#""${ECHO_fast}""#);
+    let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, "fast");
+}
+
+#[test]
+fn test_timeout_panic() {
+    let mut source = TimeoutSource::new(StringSource::new(slow), Duration::from_secs(5));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${PANIC}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${PANIC}`, but StringSource<slow> is unavailable after it panicked"#);
+
+    let mut de = serde_json::Deserializer::from_str(r#""${ECHO_fast}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${ECHO_fast}`, but StringSource<slow> is unavailable after it panicked"#);
+}
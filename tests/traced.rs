@@ -0,0 +1,87 @@
+#![cfg(feature = "tracing")]
+
+use std::{
+    collections::HashMap,
+    io,
+    sync::{Arc, Mutex},
+};
+
+use serde_vars::{source::TracedSource, MapSource};
+
+fn map(values: &[(&str, &str)]) -> MapSource {
+    let values = values
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect::<HashMap<_, _>>();
+    MapSource::new(values)
+}
+
+#[derive(Clone, Default)]
+struct Events(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Events {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let Ok(mut events) = self.0.lock() else {
+            return Err(io::Error::other("poisoned"));
+        };
+        events.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_traced() {
+    let events = Events::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_writer({
+            let events = events.clone();
+            move || events.clone()
+        })
+        .with_ansi(false)
+        .without_time()
+        .finish();
+
+    let mut source = TracedSource::new(map(&[
+        ("USER", "admin"),
+        ("PORT", "8080"),
+        ("PASSWORD", "hunter2"),
+    ]));
+
+    tracing::subscriber::with_default(subscriber, || {
+        let mut de = serde_json::Deserializer::from_str(
+            r#"["${USER}", "${PORT}", "plain", ["${PASSWORD}"]]"#,
+        );
+        let r: (String, u16, String, serde_json::Value) =
+            serde_vars::deserialize(&mut de, &mut source).unwrap();
+        assert_eq!(
+            r,
+            (
+                "admin".to_owned(),
+                8080,
+                "plain".to_owned(),
+                serde_json::json!(["hunter2"])
+            )
+        );
+
+        let mut de = serde_json::Deserializer::from_str(r#""${HOST}""#);
+        serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+
+        let mut de = serde_json::Deserializer::from_str(r#""${PASSWORD}""#);
+        serde_vars::deserialize::<_, _, u32>(&mut de, &mut source).unwrap_err();
+    });
+
+    let events = String::from_utf8(events.0.lock().unwrap().clone()).unwrap();
+    assert!(!events.contains("hunter2"));
+    insta::assert_snapshot!(events, @r#"
+    DEBUG serde_vars: expanded variable variable="USER" type="str" outcome="hit"
+    DEBUG serde_vars: expanded variable variable="PORT" type="u16" outcome="hit"
+    DEBUG serde_vars: expanded variable variable="PASSWORD" type="any" outcome="hit"
+    DEBUG serde_vars: expanded variable variable="HOST" type="str" outcome="miss"
+    DEBUG serde_vars: expanded variable variable="PASSWORD" type="u32" outcome="error"
+    "#);
+}
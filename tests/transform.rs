@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use serde_vars::source::{TransformLookup, TransformSource};
+
+#[test]
+fn test_transform() {
+    let values = HashMap::from([
+        ("HOST", "vault:localhost"),
+        ("PORT", "vault:8080"),
+        ("DEBUG", "vault:true"),
+        ("NAME", "unprefixed"),
+    ]);
+    let mut source = TransformSource::new(TransformLookup::new(values, |value: &str| {
+        value.strip_prefix("vault:").unwrap_or(value).to_owned()
+    }));
+
+    let mut de = serde_json::Deserializer::from_str(
+        r#"["${HOST}", "${PORT}", "${DEBUG}", "${NAME}", "plain"]"#,
+    );
+    let r: (String, u16, bool, String, String) =
+        serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(
+        r,
+        (
+            "localhost".to_owned(),
+            8080,
+            true,
+            "unprefixed".to_owned(),
+            "plain".to_owned()
+        )
+    );
+
+    let mut de = serde_json::Deserializer::from_str(r#""${HOST}""#);
+    let r: serde_bytes::ByteBuf = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r.as_slice(), b"localhost");
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${PORT}", "${DEBUG}"]"#);
+    let r: serde_json::Value = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, serde_json::json!([8080, true]));
+
+    let mut de = serde_json::Deserializer::from_str(r#""${USER}""#);
+    let err = serde_vars::deserialize::<_, _, String>(&mut de, &mut source).unwrap_err();
+    insta::assert_snapshot!(err, @r#"got variable `${USER}`, but it does not exist"#);
+}
+
+#[test]
+fn test_transform_decode() {
+    let values = HashMap::from([("PASSWORD", "hunter%202%21"), ("TIMEOUT", "%31%30")]);
+    let mut source = TransformSource::new(TransformLookup::new(values, |value: &str| {
+        let mut decoded = Vec::new();
+        let mut bytes = value.bytes();
+        while let Some(b) = bytes.next() {
+            match b {
+                b'%' => {
+                    let hex = [bytes.next().unwrap_or(b'0'), bytes.next().unwrap_or(b'0')];
+                    let hex = std::str::from_utf8(&hex).unwrap_or("00");
+                    decoded.push(u8::from_str_radix(hex, 16).unwrap_or(0));
+                }
+                b => decoded.push(b),
+            }
+        }
+        String::from_utf8_lossy(&decoded).into_owned()
+    }));
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${PASSWORD}", "${TIMEOUT}"]"#);
+    let r: (String, u64) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(r, ("hunter 2!".to_owned(), 10));
+}
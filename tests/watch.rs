@@ -0,0 +1,91 @@
+#![cfg(feature = "notify")]
+
+use std::{path::Path, sync::mpsc, time::Duration};
+
+use serde_vars::{source::StdFileSystem, FileSource};
+
+#[test]
+fn test_watch() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let password = tempdir.path().join("password");
+    let token = tempdir.path().join("token");
+    std::fs::write(&password, "hunter2").unwrap();
+
+    let mut source = FileSource::new()
+        .with_base_path(tempdir.path())
+        .with_cache(false);
+    let mut de = serde_json::Deserializer::from_str(r#"["${password}", "${token}"]"#);
+    let r: Result<(String, String), _> = serde_vars::deserialize(&mut de, &mut source);
+    assert!(r.is_err());
+
+    let (tx, rx) = mpsc::channel();
+    let _watcher = serde_vars::watch::watch(&source, move |changed| {
+        tx.send(changed.to_vec()).unwrap();
+    })
+    .unwrap();
+
+    // A single write can be reported multiple times, e.g. once for creating and once for
+    // writing the file.
+    let wait_for = |path: &Path| loop {
+        let changed: Vec<_> = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        if changed.iter().any(|changed| changed == path) {
+            break;
+        }
+    };
+
+    // A file which did not exist yet is created.
+    std::fs::write(&token, "secret").unwrap();
+    wait_for(&token);
+
+    // A file is replaced atomically.
+    let tmp = tempdir.path().join("password.tmp");
+    std::fs::write(&tmp, "correct horse battery staple").unwrap();
+    std::fs::rename(&tmp, &password).unwrap();
+    wait_for(&password);
+
+    let mut de = serde_json::Deserializer::from_str(r#"["${password}", "${token}"]"#);
+    let r: (String, String) = serde_vars::deserialize(&mut de, &mut source).unwrap();
+    assert_eq!(
+        r,
+        (
+            "correct horse battery staple".to_owned(),
+            "secret".to_owned()
+        )
+    );
+}
+
+#[test]
+fn test_cache_invalidation() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let token = tempdir.path().join("token");
+
+    let source = FileSource::new()
+        .with_base_path(tempdir.path())
+        .with_cache_invalidation(true);
+    // Cache invalidation is kept, when the filesystem is configured afterwards.
+    let with_file_system = FileSource::new()
+        .with_base_path(tempdir.path())
+        .with_cache_invalidation(true)
+        .with_file_system(StdFileSystem);
+
+    for mut source in [source, with_file_system] {
+        std::fs::write(&token, "first").unwrap();
+        let mut de = serde_json::Deserializer::from_str(r#""${token}""#);
+        let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+        assert_eq!(r, "first");
+
+        // Notifications are delivered asynchronously, the cached contents are used until then.
+        std::fs::write(&token, "second").unwrap();
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        loop {
+            let mut de = serde_json::Deserializer::from_str(r#""${token}""#);
+            let r: String = serde_vars::deserialize(&mut de, &mut source).unwrap();
+            if r == "second" {
+                break;
+            }
+            assert_eq!(r, "first");
+            assert!(std::time::Instant::now() < deadline);
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}